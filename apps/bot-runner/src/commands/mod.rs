@@ -6,7 +6,7 @@ use quote::handle_quote;
 use swap::handle_swap_command;
 
 pub async fn handle_command(bot: Bot, msg: Message, text: &str) {
-    let mut parts = text.trim().split_whitespace();
+    let mut parts = text.split_whitespace();
     let command = parts.next().unwrap_or("");
     let args: Vec<String> = parts.map(|s| s.to_string()).collect();
 
@@ -2,6 +2,7 @@ use teloxide::{prelude::*, utils::markdown::escape};
 use client::jupiter::quote::fetch_jupiter_quote;
 use utils::resolve_mint_address;
 use tracing::{info, warn};
+use crate::handlers::jupiter_limiter;
 
 pub async fn handle_quote(bot: Bot, msg: Message, args: Vec<String>) {
     let chat_id = msg.chat.id;
@@ -34,8 +35,9 @@ pub async fn handle_quote(bot: Bot, msg: Message, args: Vec<String>) {
         return;
     };
 
-    // ✅ Step 3: 调用 Jupiter Quote 接口
-    match fetch_jupiter_quote(input_mint, output_mint, amount).await {
+    // ✅ Step 3: 调用 Jupiter Quote 接口（先取令牌）
+    jupiter_limiter().acquire().await;
+    match fetch_jupiter_quote(input_mint, output_mint, amount, 50).await {
         Ok(quote) => {
             let reply = format!(
                 "📊 套利报价结果：\n🔁 {} → {}\n🔹 输出数量: {}\n🔹 路由平台: {}",
@@ -1,5 +1,53 @@
-pub mod quote;
 pub mod swap;
 
-pub use quote::handle_quote;
-pub use swap::handle_swap;
\ No newline at end of file
+use std::sync::OnceLock;
+use arbitrage::RateLimiter;
+use executor::{FeeLedger, SolanaRpc};
+use tracing::warn;
+
+/// 默认 RPC（SOLANA_RPC_URLS 未设置时）
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// 默认费用记录文件（FEE_LOG 未设置时），即 cli-runner quote-matrix --fee-log 读的文件
+const DEFAULT_FEE_LOG: &str = "out/fees.ndjson";
+
+/// 所有命令共用一个 Jupiter 令牌桶（JUPITER_QPS / JUPITER_BURST，默认 5 / 2）
+pub fn jupiter_limiter() -> &'static RateLimiter {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(|| {
+        let env = |k: &str, d: u32| std::env::var(k).ok().and_then(|v| v.parse().ok()).unwrap_or(d);
+        RateLimiter::new(env("JUPITER_QPS", 5), env("JUPITER_BURST", 2))
+    })
+}
+
+/// 所有命令共用的 RPC：SOLANA_RPC_URLS 逗号分隔，按顺序故障转移
+pub fn solana_rpc() -> &'static SolanaRpc {
+    static RPC: OnceLock<SolanaRpc> = OnceLock::new();
+    RPC.get_or_init(|| {
+        let urls = std::env::var("SOLANA_RPC_URLS").unwrap_or_default();
+        let mut urls: Vec<String> = urls.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+        if urls.is_empty() {
+            urls.push(DEFAULT_RPC_URL.to_string());
+        }
+        SolanaRpc::from_urls(urls)
+    })
+}
+
+/// /swap 每笔交易的费用预算（lamports，SWAP_FEE_BUDGET_LAMPORTS，默认 0.001 SOL）
+pub fn swap_fee_budget() -> u64 {
+    std::env::var("SWAP_FEE_BUDGET_LAMPORTS").ok().and_then(|v| v.parse().ok()).unwrap_or(1_000_000)
+}
+
+/// 所有发送共用的费用记录（FEE_LOG，默认 out/fees.ndjson）；打不开时只告警、不记录
+pub fn fee_ledger() -> Option<&'static FeeLedger> {
+    static LEDGER: OnceLock<Option<FeeLedger>> = OnceLock::new();
+    LEDGER
+        .get_or_init(|| {
+            let path = std::env::var("FEE_LOG").unwrap_or_else(|_| DEFAULT_FEE_LOG.to_string());
+            if let Some(dir) = std::path::Path::new(&path).parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            FeeLedger::create(&path).inspect_err(|e| warn!("⚠️ 费用记录 {} 打不开: {}", path, e)).ok()
+        })
+        .as_ref()
+}
@@ -1,12 +1,15 @@
 use teloxide::prelude::*;
-use client::jupiter::swap::fetch_jupiter_swap_tx_safe;
-use solana_sdk::{signature::Signer, transaction::Transaction};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use base64::prelude::*;
+use client::{fetch_jupiter_swap, fetch_quote_response, PrioritizationFee, SwapRequest};
+use solana_sdk::{pubkey::Pubkey, signature::Signer, transaction::VersionedTransaction};
 use tracing::{info, error, warn};
 use utils::wallet::load_wallet;
 use std::time::Instant;
 use utils::resolve_mint_address;
+use executor::priority_fee::apply_plan;
+use executor::{ExecError, FeePolicy, ProfitAccount, RpcSender, SendFailure, SimulationGate};
+use executor::sender::classify;
+use executor::priority_fee::BASE_FEE_PER_SIGNATURE;
+use super::{fee_ledger, jupiter_limiter, solana_rpc, swap_fee_budget};
 
 /// 处理 /swap 命令的核心逻辑
 pub async fn handle_swap(
@@ -51,82 +54,151 @@ pub async fn handle_swap(
     let user_pubkey = keypair.pubkey();
     info!("✅ 钱包加载成功: {}", user_pubkey);
 
-    // 3. 请求 Jupiter swap 交易体
+    // 3. 报价（输出 mint 先校验，后面模拟要用）
+    let Ok(out_mint) = output_mint.parse::<Pubkey>() else {
+        let _ = bot.send_message(chat_id, format!("❌ 输出 mint 无效: {}", output_mint)).await;
+        return;
+    };
+    let amount_u64 = (amount * 10f64.powi(9)) as u64; // TODO: 可根据 token 设置 decimals
     let t1 = Instant::now();
-    let swap_tx_base64 = match fetch_jupiter_swap_tx_safe(
-        &input_mint,
-        &output_mint,
-        amount,
-        9, // TODO: 可根据 token 设置 decimals
-        &user_pubkey.to_string(),
-    ).await {
-        Ok(tx) => {
-            info!("📦 Jupiter 返回交易体 base64 长度: {}, 用时: {:?}", tx.len(), t1.elapsed());
-            tx
+    jupiter_limiter().acquire().await;
+    let quote = match fetch_quote_response(input_mint, output_mint, amount_u64, 50).await {
+        Ok(q) => q,
+        Err(err) => {
+            error!("❌ Jupiter 报价失败: {}", err);
+            let _ = bot.send_message(chat_id, format!("❌ Jupiter 报价失败: {}", err)).await;
+            return;
         }
+    };
+    let min_out = quote.other_amount_threshold;
+
+    // 4. 请求 swap 交易体（v0 交易）；优先费模拟后由我们自己定，这里先不加
+    jupiter_limiter().acquire().await;
+    let req = SwapRequest::new(user_pubkey.to_string(), quote).priority_fee(PrioritizationFee::Lamports(0));
+    let swap = match fetch_jupiter_swap(&req).await {
+        Ok(s) => s,
         Err(err) => {
-            warn!("🧪 Jupiter swap 请求失败，使用 mock 替代。错误: {}", err);
-            let _ = bot.send_message(chat_id, "⚠️ Jupiter 出错，使用测试交易体替代执行").await;
+            error!("🚫 Jupiter swap 请求失败: {}", err);
+            let _ = bot.send_message(chat_id, format!("❌ Jupiter swap 请求失败: {}", err)).await;
+            return;
+        }
+    };
+    info!(
+        "📦 Jupiter 返回交易体，优先费 {:?} lamports，CU {:?}，用时: {:?}",
+        swap.prioritization_fee_lamports, swap.compute_unit_limit, t1.elapsed()
+    );
 
-            // ✅ 你需替换为有效的 base64（手动抓日志或控制台打印）
-            let mock_base64 = "AgABBAIBAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhs=";
-            mock_base64.to_string()
+    // 5. 解码 VersionedTransaction
+    let unsigned = match swap.transaction() {
+        Ok(tx) => tx,
+        Err(err) => {
+            error!("❌ 交易体解析失败: {}", err);
+            let _ = bot.send_message(chat_id, "❌ 交易结构解析失败").await;
+            return;
         }
     };
 
-    // 4. 解码 base64
-    let tx_data = match BASE64_STANDARD.decode(&swap_tx_base64) {
-        Ok(data) => data,
+    // 6. 签名（沿用 Jupiter 填入的 blockhash，过期高度取自响应）
+    let last_valid_block_height = swap.last_valid_block_height;
+    let tx = match VersionedTransaction::try_new(unsigned.message, &[&keypair]) {
+        Ok(tx) => tx,
         Err(err) => {
-            error!("❌ base64 解码失败: {:?}", err);
-            let _ = bot.send_message(chat_id, "❌ Jupiter 返回数据格式有误（base64 解析失败）").await;
+            error!("❌ 签名失败: {}", err);
+            let _ = bot.send_message(chat_id, "❌ 签名失败").await;
             return;
         }
     };
+    info!("✍️ 已签名，开始模拟");
 
-    // 5. bincode 反序列化 Transaction
-    let mut tx: Transaction = match bincode::deserialize::<Transaction>(&tx_data) {
-        Ok(tx) => {
-            info!("🧾 Transaction 解析成功，签名数: {}", tx.signatures.len());
-            tx
+    // 7. 模拟：失败或到手量低于报价的最少到手量就不发
+    let rpc = solana_rpc();
+    let profit_account = ProfitAccount::for_mint(&user_pubkey, &out_mint);
+    let pre_balance = match profit_account.balance_on(rpc).await {
+        Ok(b) => b,
+        Err(err) => {
+            error!("❌ 查询余额失败: {}", err);
+            let _ = bot.send_message(chat_id, "❌ 查询余额失败").await;
+            return;
+        }
+    };
+    // 输出为 SOL 时余额变化里还扣掉了交易费（模拟时还没有优先费）
+    let fee = swap.prioritization_fee_lamports.unwrap_or(0) + BASE_FEE_PER_SIGNATURE;
+    let min_profit = match profit_account {
+        ProfitAccount::Token(_) => min_out as i128,
+        ProfitAccount::Native(_) => min_out as i128 - fee as i128,
+    };
+    let gate = SimulationGate { min_profit: min_profit.max(1) };
+    let sim_start = Instant::now();
+    let report = match gate.check(rpc, &tx, profit_account, pre_balance).await {
+        Ok(report) => {
+            info!(
+                "🧪 模拟通过：输出 +{}，CU {:?}，用时: {:?}",
+                report.profit, report.units_consumed, sim_start.elapsed()
+            );
+            report
+        }
+        Err(ExecError::SimulationFailed { err, logs }) => {
+            error!("❌ 模拟失败: {} {:?}", err, logs);
+            let reason = classify(&tx, &err);
+            let _ = bot.send_message(chat_id, format!("❌ 模拟失败，未发送: {}\n{}", reason, logs.join("\n"))).await;
+            return;
         }
         Err(err) => {
-            error!("❌ bincode 反序列化失败: {:?}", err);
-            let _ = bot.send_message(chat_id, "❌ 交易结构解析失败").await;
+            error!("❌ 模拟未通过: {}", err);
+            let _ = bot.send_message(chat_id, format!("❌ 模拟未通过，未发送: {}", err)).await;
             return;
         }
     };
 
-    // 6. 获取最新 Blockhash
-    let rpc = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
-    let bh_start = Instant::now();
-    let recent_blockhash = match rpc.get_latest_blockhash().await {
-        Ok(bh) => {
-            info!("⛓️ 获取最新 Blockhash 成功，用时: {:?}", bh_start.elapsed());
-            bh
+    // 8. 优先费：按近期费用和模拟消耗定 CU 上限/价格，写回交易后重签
+    // 普通兑换没有“预期利润”，以费用预算代替：总费用（基础费+优先费）不超过预算
+    let units = report.units_consumed.or(swap.compute_unit_limit.map(u64::from)).unwrap_or(200_000);
+    let policy = FeePolicy { max_profit_share_bps: 10_000, ..FeePolicy::default() };
+    let plan = match policy.plan_for(rpc, &tx.message, units, swap_fee_budget() as i128).await {
+        Ok(plan) => plan,
+        Err(err) => {
+            error!("❌ 优先费估算失败: {}", err);
+            let _ = bot.send_message(chat_id, format!("❌ 优先费估算失败，未发送: {}", err)).await;
+            return;
         }
+    };
+    let mut message = tx.message;
+    let tx = match apply_plan(&plan, &mut message).and_then(|_| {
+        VersionedTransaction::try_new(message, &[&keypair]).map_err(|e| ExecError::Sign(e.to_string()))
+    }) {
+        Ok(tx) => tx,
         Err(err) => {
-            error!("❌ 获取 Blockhash 失败: {:?}", err);
-            let _ = bot.send_message(chat_id, "❌ 获取最新 Blockhash 失败").await;
+            error!("❌ 设置优先费失败: {}", err);
+            let _ = bot.send_message(chat_id, "❌ 设置优先费失败").await;
             return;
         }
     };
-    tx.message.recent_blockhash = recent_blockhash;
-
-    // 7. 签名
-    tx.sign(&[&keypair], recent_blockhash);
-    info!("✍️ 已签名，开始广播");
+    info!("⛽ CU 上限 {}，价格 {} µlamports/CU，预计费用 {} lamports", plan.unit_limit, plan.unit_price, plan.total());
 
-    // 8. 广播交易
-    match rpc.send_and_confirm_transaction(&tx).await {
-        Ok(sig) => {
-            let url = format!("https://solscan.io/tx/{}", sig);
-            info!("✅ 交易成功，哈希: {}", sig);
+    // 9. 广播交易：未确认前反复重发，直到 blockhash 过期；记下实际花费
+    let outcome = RpcSender::new(rpc).send(&tx, last_valid_block_height).await;
+    if let Some(ledger) = fee_ledger() {
+        if let Err(err) = ledger.record(&outcome.fee_record(plan, 0)) {
+            warn!("⚠️ 费用记录写入失败: {}", err);
+        }
+    }
+    let url = format!("https://solscan.io/tx/{}", outcome.signature);
+    match &outcome.failure {
+        None => {
+            info!("✅ 交易成功，哈希: {}，广播 {} 次，用时: {:?}", outcome.signature, outcome.broadcasts, outcome.elapsed);
             let _ = bot.send_message(chat_id, format!("✅ 成功！交易哈希:\n{}", url)).await;
         }
-        Err(err) => {
-            error!("❌ 交易广播失败: {:?}", err);
-            let _ = bot.send_message(chat_id, format!("❌ 交易失败: {}", err)).await;
+        Some(f @ SendFailure::Unconfirmed(_)) => {
+            warn!("⏳ 交易结果未定: {} {}", outcome.signature, f);
+            let _ = bot.send_message(chat_id, format!("⏳ {}，请稍后查看:\n{}", f, url)).await;
+        }
+        Some(f @ (SendFailure::Expired | SendFailure::Unsent(_))) => {
+            warn!("⌛ 交易未上链: {}（广播 {} 次）", f, outcome.broadcasts);
+            let _ = bot.send_message(chat_id, format!("⌛ 交易未上链: {}", f)).await;
+        }
+        Some(f) => {
+            error!("❌ 交易执行失败: {} {}", outcome.signature, f);
+            let _ = bot.send_message(chat_id, format!("❌ 交易失败: {}\n{}", f, url)).await;
         }
     }
 }
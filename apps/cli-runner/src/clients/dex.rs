@@ -0,0 +1,58 @@
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use dex::{AccountStream, AccountUpdate, DexQuoter, FileAccounts, HttpAccounts, WsAccounts};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+
+pub type DexSource = DexQuoter<HttpAccounts>;
+
+/// 账户变更来源：websocket 或回放文件
+pub enum DexStream {
+    Ws(WsAccounts),
+    File(FileAccounts),
+}
+
+impl DexStream {
+    pub fn new(ws_url: &str, file: Option<&Path>) -> Self {
+        match file {
+            Some(path) => DexStream::File(FileAccounts::new(path)),
+            None => DexStream::Ws(WsAccounts::new(ws_url)),
+        }
+    }
+}
+
+impl AccountStream for DexStream {
+    async fn subscribe(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        match self {
+            DexStream::Ws(s)   => s.subscribe(keys, tx).await,
+            DexStream::File(s) => s.subscribe(keys, tx).await,
+        }
+    }
+}
+
+/// 读池子列表（JSON 字符串数组，或每行一个地址，# 开头为注释），通过 RPC 加载池子和金库
+pub async fn load(pools_file: &Path, rpc_url: &str) -> Result<DexSource> {
+    let text = std::fs::read_to_string(pools_file)
+        .with_context(|| format!("读取池子列表失败: {}", pools_file.display()))?;
+    let keys: Vec<String> = match serde_json::from_str(&text) {
+        Ok(v) => v,
+        Err(_) => text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+            .collect(),
+    };
+    let keys = keys
+        .iter()
+        .map(|k| k.parse::<Pubkey>().with_context(|| format!("无效的池子地址: {}", k)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let quoter = DexQuoter::new(HttpAccounts::new(rpc_url));
+    let n = quoter.add_pools(&keys).await?;
+    if n == 0 {
+        bail!("{} 里没有可用的池子", pools_file.display());
+    }
+    println!("🏊 已加载 {}/{} 个池子（{}）", n, keys.len(), rpc_url);
+    Ok(quoter)
+}
@@ -1,9 +1,9 @@
 use anyhow::Result;
-use arbitrage::QuoteProvider as StratQuoter;
+use arbitrage::{Quote, QuoteProvider as StratQuoter};
 
 pub struct MockQuoter;
 impl StratQuoter for MockQuoter {
-    async fn quote(&self, _i: String, _o: String, amount: u64) -> Result<u64> {
-        Ok(amount + 1234) // 随便返回个正收益
+    async fn quote(&self, i: String, o: String, amount: u64) -> Result<Quote> {
+        Ok(Quote::simple(i, o, amount, amount + 1234)) // 随便返回个正收益
     }
 }
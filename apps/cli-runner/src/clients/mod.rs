@@ -1,52 +1,220 @@
-pub mod jupiter_http;
+pub mod dex;
 pub mod mock;
-// 将来: pub mod orca_http;
 
-use anyhow::Result;
-use arbitrage::QuoteProvider as StratQuoter;
+use std::path::PathBuf;
+use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use arbitrage::{
+    BestQuote, BreakerConfig, CircuitBreaker, Quote, QuoteProvider as StratQuoter, RateLimiter, Recorder,
+    ReplayMode, ReplayQuoter, ThrottleRetry,
+};
+use clap::ValueEnum;
+use client::{JupiterClient, JupiterConfig, JupiterHttp};
 
 // 供 CLI 选择
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum ProviderKind {
     Jupiter,
     Mock,
-    // Orca,
+    /// 从录制文件回放（--replay-file）
+    Replay,
+    /// 直连 DEX 池子（--pools），本地计算报价
+    Dex,
+    /// 同时问 --best-of 里的几个报价源，截止时间内取输出最多的
+    Best,
 }
 
-// 统一的“具体类型”，避免 Box<dyn …>
-pub enum Provider {
-    // 拥有型：限速+重试 包装 真实客户端
-    Jupiter(jupiter_http::ThrottleRetry<jupiter_http::JupiterHttp>),
+impl ProviderKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProviderKind::Jupiter => "jupiter",
+            ProviderKind::Mock => "mock",
+            ProviderKind::Replay => "replay",
+            ProviderKind::Dex => "dex",
+            ProviderKind::Best => "best",
+        }
+    }
+}
+
+// 回放匹配方式（对应 arbitrage::ReplayMode）
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ReplayMatch {
+    /// amount 完全一致
+    #[default]
+    Exact,
+    /// amount 最近的记录按比例缩放
+    Nearest,
+}
+
+impl From<ReplayMatch> for ReplayMode {
+    fn from(m: ReplayMatch) -> Self {
+        match m {
+            ReplayMatch::Exact => ReplayMode::Exact,
+            ReplayMatch::Nearest => ReplayMode::Nearest,
+        }
+    }
+}
+
+// 构建参数
+#[derive(Clone)]
+pub struct ProviderOpts {
+    pub kind: ProviderKind,
+    /// 由调用方创建，可在多个报价源之间共享同一个令牌桶
+    pub limiter: RateLimiter,
+    pub retries: u32,
+    /// 录制所有请求/响应到该 NDJSON 文件
+    pub record: Option<PathBuf>,
+    pub replay_file: Option<PathBuf>,
+    pub replay_match: ReplayMatch,
+    /// --provider dex 的池子列表
+    pub pools_file: Option<PathBuf>,
+    pub rpc_url: String,
+    /// --provider best 的成员
+    pub best_of: Vec<ProviderKind>,
+    /// --provider best 每次报价的截止时间
+    pub deadline_ms: u64,
+}
+
+// 实际的报价源
+pub enum Source {
+    // 拥有型：熔断 → 限速+重试 → 真实客户端
+    Jupiter(CircuitBreaker<ThrottleRetry<JupiterHttp>>),
     Mock(mock::MockQuoter),
-    // Orca(...),
+    Replay(ReplayQuoter),
+    Dex(dex::DexSource),
+    Best(BestQuote<Source>),
 }
 
-impl Provider {
-    pub fn build(kind: ProviderKind, qps: u32, retries: u32) -> Self {
-        match kind {
+// 统一的“具体类型”，避免 Box<dyn …>；可选再包一层录制
+pub enum Provider {
+    Direct(Source),
+    Recording(Recorder<Source>),
+}
+
+impl Source {
+    pub async fn build(opts: &ProviderOpts) -> Result<Self> {
+        if !matches!(opts.kind, ProviderKind::Best) {
+            return Self::build_one(opts.kind, opts).await;
+        }
+        if opts.best_of.len() < 2 {
+            bail!("--provider best 需要 --best-of 至少两个报价源");
+        }
+        let mut members = Vec::with_capacity(opts.best_of.len());
+        for &kind in &opts.best_of {
+            if matches!(kind, ProviderKind::Best) {
+                bail!("--best-of 不能包含 best");
+            }
+            members.push((kind.name().to_string(), Self::build_one(kind, opts).await?));
+        }
+        Ok(Source::Best(BestQuote::new(members, Duration::from_millis(opts.deadline_ms))))
+    }
+
+    async fn build_one(kind: ProviderKind, opts: &ProviderOpts) -> Result<Self> {
+        Ok(match kind {
             ProviderKind::Jupiter => {
-                let inner = jupiter_http::JupiterHttp::new();
-                let throttled = jupiter_http::ThrottleRetry::new(inner, qps, retries);
-                Provider::Jupiter(throttled)
+                let inner = JupiterHttp::with_client(JupiterClient::new(JupiterConfig::from_env())?, 50);
+                let throttled = ThrottleRetry::new(inner, opts.limiter.clone(), opts.retries);
+                Source::Jupiter(CircuitBreaker::new(throttled, BreakerConfig::default()))
+            }
+            ProviderKind::Mock => Source::Mock(mock::MockQuoter),
+            ProviderKind::Replay => {
+                let path = opts.replay_file.as_ref().context("--provider replay 需要 --replay-file")?;
+                Source::Replay(ReplayQuoter::load(path, opts.replay_match.into())?)
             }
-            ProviderKind::Mock => Provider::Mock(mock::MockQuoter),
-            // ProviderKind::Orca => { ... }
+            ProviderKind::Dex => {
+                let path = opts.pools_file.as_ref().context("--provider dex 需要 --pools")?;
+                Source::Dex(dex::load(path, &opts.rpc_url).await?)
+            }
+            ProviderKind::Best => bail!("best 不能嵌套"),
+        })
+    }
+}
+
+impl Provider {
+    pub async fn build(opts: &ProviderOpts) -> Result<Self> {
+        let src = Source::build(opts).await?;
+        Ok(match &opts.record {
+            Some(path) => Provider::Recording(Recorder::create(src, path)?),
+            None => Provider::Direct(src),
+        })
+    }
+
+    fn source(&self) -> &Source {
+        match self {
+            Provider::Direct(s) => s,
+            Provider::Recording(r) => r.inner(),
+        }
+    }
+
+    /// 底层的池子报价器（--provider dex，或 best 的成员里有 dex），订阅账户变更用
+    pub fn dex(&self) -> Option<&dex::DexSource> {
+        match self.source() {
+            Source::Dex(q) => Some(q),
+            Source::Best(b) => b.sources().find_map(|(_, s)| match s {
+                Source::Dex(q) => Some(q),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// --record 写入失败的条数（未录制时为 0）
+    pub fn record_errors(&self) -> u64 {
+        match self {
+            Provider::Direct(_) => 0,
+            Provider::Recording(r) => r.write_errors(),
+        }
+    }
+
+    /// --provider best 时的组合报价器（读胜出统计用）
+    pub fn best(&self) -> Option<&BestQuote<Source>> {
+        match self.source() {
+            Source::Best(b) => Some(b),
+            _ => None,
         }
     }
 }
 
 // 让枚举充当报价器（模式匹配转发）
+impl StratQuoter for Source {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        match self {
+            Source::Jupiter(q) => q.quote(input_mint, output_mint, amount).await,
+            Source::Mock(q)    => q.quote(input_mint, output_mint, amount).await,
+            Source::Replay(q)  => q.quote(input_mint, output_mint, amount).await,
+            Source::Dex(q)     => q.quote(input_mint, output_mint, amount).await,
+            Source::Best(q)    => q.quote(input_mint, output_mint, amount).await,
+        }
+    }
+
+    fn available(&self) -> bool {
+        match self {
+            Source::Jupiter(q) => q.available(),
+            Source::Mock(q)    => q.available(),
+            Source::Replay(q)  => q.available(),
+            Source::Dex(q)     => q.available(),
+            Source::Best(q)    => q.available(),
+        }
+    }
+}
+
 impl StratQuoter for Provider {
-    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<u64> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        match self {
+            Provider::Direct(q)    => q.quote(input_mint, output_mint, amount).await,
+            Provider::Recording(q) => q.quote(input_mint, output_mint, amount).await,
+        }
+    }
+
+    fn available(&self) -> bool {
         match self {
-            Provider::Jupiter(q) => q.quote(input_mint, output_mint, amount).await,
-            Provider::Mock(q)    => q.quote(input_mint, output_mint, amount).await,
-            // Provider::Orca(q) => q.quote(input_mint, output_mint, amount).await,
+            Provider::Direct(q)    => q.available(),
+            Provider::Recording(q) => q.available(),
         }
     }
 }
 
 // 如果你更喜欢保持原来的函数名：
-pub fn build_provider(kind: ProviderKind, qps: u32, retries: u32) -> Provider {
-    Provider::build(kind, qps, retries)
+pub async fn build_provider(opts: &ProviderOpts) -> Result<Provider> {
+    Provider::build(opts).await
 }
@@ -0,0 +1,74 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use arbitrage::Amount;
+
+use crate::clients::ReplayMatch;
+use crate::commands::quote_matrix::Hops;
+
+#[derive(Debug, Args, Clone)]
+pub struct BacktestArgs {
+    /// 录制文件（quote-matrix --record 生成的 NDJSON）
+    pub file: PathBuf,
+
+    /// Base token symbol or mint (e.g. SOL)
+    pub base: String,
+
+    /// Mid tokens（与录制时一致）
+    #[arg(num_args = 1.., value_name = "TOKENS")]
+    pub tokens: Vec<String>,
+
+    /// Amount in human units of BASE（与录制时一致）
+    #[arg(long, default_value = "1.0")]
+    pub amount: Amount,
+
+    /// 路径跳数
+    #[arg(long, value_enum, default_value_t = Hops::Two)]
+    pub hops: Hops,
+
+    // ---- 费用/阈值（逗号分隔可给多个值，逐一组合回测） ----
+    /// 每跳手续费（bps）
+    #[arg(long, value_delimiter = ',', default_value = "25")]
+    pub fee_bps_per_hop: Vec<u16>,
+
+    /// 每跳假设滑点（bps）
+    #[arg(long, default_value_t = 30)]
+    pub slippage_bps: u16,
+
+    /// 每笔交易的链上费用（lamports，基础费+优先费），计入净收益；仅 base 为 SOL 时生效
+    #[arg(long, conflicts_with = "fee_log")]
+    pub tx_fee_lamports: Option<u64>,
+
+    /// 执行层的费用记录（NDJSON，同 quote-matrix --fee-log），取已上链交易的平均费用作为 --tx-fee-lamports
+    #[arg(long)]
+    pub fee_log: Option<PathBuf>,
+
+    /// 净收益阈值（百分比），达到即视为发现并执行
+    #[arg(long, value_delimiter = ',', default_value = "0.30")]
+    pub min_net_change: Vec<f64>,
+
+    // ---- 回放 ----
+    /// 发现后隔几轮快照成交（0 = 按发现时的报价成交）
+    #[arg(long, default_value_t = 1)]
+    pub exec_delay: usize,
+
+    /// 相邻记录间隔超过该值（毫秒）即视为新一轮快照
+    #[arg(long, default_value_t = 2000)]
+    pub round_gap_ms: u64,
+
+    /// 回放匹配方式（成交时按路径重新报价，amount 往往对不上，默认 nearest）
+    #[arg(long, value_enum, default_value_t = ReplayMatch::Nearest)]
+    pub replay_match: ReplayMatch,
+
+    /// 最大并发
+    #[arg(long, default_value_t = 5)]
+    pub concurrency: usize,
+
+    // ---- 输出 ----
+    /// 逐笔打印模拟成交
+    #[arg(long, default_value_t = false)]
+    pub show_trades: bool,
+
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
@@ -1,2 +1,3 @@
 pub mod quote;
-pub mod quote_matrix;
\ No newline at end of file
+pub mod quote_matrix;
+pub mod backtest;
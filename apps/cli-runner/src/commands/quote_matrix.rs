@@ -1,8 +1,18 @@
 use clap::{Args, ValueEnum};
 use std::path::PathBuf;
 
+use arbitrage::{Amount, Bps};
+
+use crate::clients::{ProviderKind, ReplayMatch};
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
-pub enum Hops { Two, Three }
+pub enum Hops { Two, Three, Four, Five }
+
+impl Hops {
+    pub fn count(self) -> usize {
+        match self { Hops::Two => 2, Hops::Three => 3, Hops::Four => 4, Hops::Five => 5 }
+    }
+}
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum ExportFormat { Csv, Json }
@@ -18,17 +28,38 @@ pub struct QuoteMatrixArgs {
 
     // ---- 金额与跳数 ----
     /// Amount in human units of BASE (e.g. 1.0 SOL). 与 --amount-lamports 互斥
-    #[arg(long, default_value_t = 1.0, conflicts_with = "amount_lamports")]
-    pub amount: f64,
+    #[arg(long, default_value = "1.0", conflicts_with = "amount_lamports")]
+    pub amount: Amount,
 
     /// Amount in smallest units (e.g. lamports). 与 --amount 互斥
     #[arg(long)]
     pub amount_lamports: Option<u64>,
 
-    /// 路径跳数（Two=BASE→X→BASE，Three=BASE→X→Y→BASE）
+    /// 路径跳数（Two=BASE→X→BASE，Three=BASE→X→Y→BASE，Four/Five 依此类推）
     #[arg(long, value_enum, default_value_t = Hops::Two)]
     pub hops: Hops,
 
+    /// 中间亏损剪枝（bps）：前缀按 BASE 估值亏损超过该值即停止扩展（不填则不剪枝）
+    #[arg(long)]
+    pub prune_loss_bps: Option<Bps>,
+
+    // ---- 下单量搜索 ----
+    /// 对展示的每条路径搜索最优下单量（会额外消耗报价次数）
+    #[arg(long, default_value_t = false)]
+    pub optimize_size: bool,
+
+    /// 下单量搜索下限（BASE 人类单位）
+    #[arg(long, default_value = "0.1")]
+    pub size_min: Amount,
+
+    /// 下单量搜索上限（BASE 人类单位）
+    #[arg(long, default_value = "10.0")]
+    pub size_max: Amount,
+
+    /// 每条路径最多试探几个下单量
+    #[arg(long, default_value_t = 10)]
+    pub size_probes: usize,
+
     // ---- 过滤与排序 ----
     /// 最小“毛收益”阈值（百分比，0.10 = 0.10%），低于此不展示
     #[arg(long, default_value_t = 0.10)]
@@ -55,19 +86,93 @@ pub struct QuoteMatrixArgs {
     #[arg(long, default_value_t = 30)]
     pub slippage_bps: u16,
 
+    /// 每笔交易的链上费用（lamports，基础费+优先费），计入净收益；仅 base 为 SOL 时生效
+    #[arg(long, conflicts_with = "fee_log")]
+    pub tx_fee_lamports: Option<u64>,
+
+    /// 执行层的费用记录（NDJSON，bot-runner 写到 FEE_LOG，默认 out/fees.ndjson），取已上链交易的平均费用作为 --tx-fee-lamports
+    #[arg(long)]
+    pub fee_log: Option<PathBuf>,
+
+    // ---- 报价源 ----
+    /// 报价源（jupiter / mock / replay / dex / best）
+    #[arg(long, value_enum, default_value_t = ProviderKind::Jupiter)]
+    pub provider: ProviderKind,
+
+    /// --provider best 同时询价的报价源（逗号分隔，如 jupiter,dex）
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [ProviderKind::Jupiter, ProviderKind::Dex])]
+    pub best_of: Vec<ProviderKind>,
+
+    /// --provider best 每次报价的截止时间（毫秒），到点只在已返回的报价里选
+    #[arg(long, default_value_t = 1500)]
+    pub deadline_ms: u64,
+
+    /// 跨报价源价差分析：代币列表（含 BASE）里每对代币在多个数量下比较 --best-of 各报价源的输出（需 --provider best）；
+    /// 单独运行，不做环路扫描
+    #[arg(long, default_value_t = false, conflicts_with = "watch")]
+    pub discrepancy: bool,
+
+    /// 价差分析的询价数量（BASE 人类单位，逗号分隔）
+    #[arg(long, value_delimiter = ',', default_value = "0.1,1,10")]
+    pub discrepancy_sizes: Vec<Amount>,
+
+    /// 价差达到该值（bps）才报告
+    #[arg(long, default_value = "20")]
+    pub discrepancy_bps: Bps,
+
+    /// 把每次报价请求/响应追加录制到该 NDJSON 文件（之后可用 --provider replay 离线复现）
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// 回放用的录制文件（--provider replay 时必填）
+    #[arg(long, required_if_eq("provider", "replay"))]
+    pub replay_file: Option<PathBuf>,
+
+    /// 回放匹配方式：exact = amount 完全一致；nearest = 取最近 amount 按比例缩放
+    #[arg(long, value_enum, default_value_t = ReplayMatch::Exact)]
+    pub replay_match: ReplayMatch,
+
+    /// 池子地址列表（JSON 数组或每行一个；--provider dex 时必填）
+    #[arg(long, required_if_eq("provider", "dex"))]
+    pub pools: Option<PathBuf>,
+
+    /// 加载池子账户用的 RPC
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+
+    /// 扫描一轮后订阅池子账户变更，池子一变就重算经过它的路径（需 --provider dex，或 best 成员含 dex）
+    #[arg(long, default_value_t = false)]
+    pub watch: bool,
+
+    /// 订阅账户变更的 websocket（accountSubscribe）
+    #[arg(long, default_value = "wss://api.mainnet-beta.solana.com")]
+    pub ws_url: String,
+
+    /// 从 NDJSON 文件回放账户变更，代替 --ws-url（离线调试）
+    #[arg(long)]
+    pub stream_file: Option<PathBuf>,
+
     // ---- 性能与稳定性 ----
     /// 最大并发请求数
     #[arg(long, default_value_t = 5)]
     pub concurrency: usize,
 
-    /// 速率限制（每秒最多请求数）
+    /// 速率限制（每秒最多请求数，所有并发任务共享）
     #[arg(long, default_value_t = 8)]
     pub qps: u32,
 
+    /// 令牌桶容量（允许的瞬时突发请求数）
+    #[arg(long, default_value_t = 1)]
+    pub burst: u32,
+
     /// 每次报价最大重试次数
     #[arg(long, default_value_t = 3)]
     pub retries: u32,
 
+    /// 报价缓存有效期（毫秒，0 表示不缓存）
+    #[arg(long, default_value_t = 1000)]
+    pub cache_ttl_ms: u64,
+
     // ---- 导出 ----
     /// 导出路径（不填则不导出）
     #[arg(long)]
@@ -98,6 +203,10 @@ pub struct QuoteMatrixArgs {
     #[arg(long, default_value_t = false)]
     pub json: bool,
 
+    /// 表格/导出附带逐跳明细（金额、每跳 bps、AMM），CSV 另写 <文件名>.legs.csv
+    #[arg(long, default_value_t = false)]
+    pub show_legs: bool,
+
     #[arg(long)]
     pub meme: bool,
 
@@ -0,0 +1,99 @@
+use anyhow::{bail, Result};
+use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use serde::Serialize;
+
+use crate::commands::backtest::BacktestArgs;
+use crate::handlers::tx_fee_lamports;
+use arbitrage::{read_records, run_backtest, split_snapshots, BacktestConfig, BacktestReport, Bps, FeeModel};
+use token_registry::api::MintResolver;
+
+#[derive(Serialize)]
+struct SweepOut {
+    fee_bps_per_hop: u16,
+    slippage_bps: u16,
+    min_net_change: f64,
+    #[serde(flatten)]
+    report: BacktestReport,
+}
+
+/// 百分比阈值 → Bps（0.30% = 30 bps）
+fn pct_to_bps(pct: f64) -> Bps {
+    Bps::from_micro((pct * 100.0 * Bps::SCALE as f64).round() as i128)
+}
+
+pub async fn handle_backtest<R>(args: BacktestArgs, resolver: &R) -> Result<()>
+where
+    R: MintResolver + Sync,
+{
+    let recs = read_records(&args.file)?;
+    let snapshots = split_snapshots(recs, args.round_gap_ms, args.replay_match.into());
+    if snapshots.is_empty() {
+        bail!("录制文件为空: {}", args.file.display());
+    }
+    let tx_cost = tx_fee_lamports(&args.base, args.tx_fee_lamports, args.fee_log.as_deref())?;
+    if !args.json {
+        println!(
+            "🧪 回测 | file={} snapshots={} base={} amount={} hops={:?} tx_fee={} exec_delay={}",
+            args.file.display(), snapshots.len(), args.base, args.amount, args.hops, tx_cost, args.exec_delay
+        );
+    }
+
+    // 逐一组合 fee × 阈值
+    let mut outs = Vec::new();
+    for &fee in &args.fee_bps_per_hop {
+        for &min_net in &args.min_net_change {
+            let cfg = BacktestConfig {
+                base: args.base.clone(),
+                mids: args.tokens.clone(),
+                amount: args.amount,
+                hops: args.hops.count(),
+                fees: FeeModel { fee_bps_per_hop: fee, slippage_bps: args.slippage_bps, tx_cost },
+                min_net_bps: pct_to_bps(min_net),
+                exec_delay: args.exec_delay,
+                concurrency: args.concurrency,
+            };
+            let mut report = run_backtest(resolver, &snapshots, &cfg).await?;
+            if !args.show_trades && !args.json {
+                report.trades.clear();
+            }
+            outs.push(SweepOut { fee_bps_per_hop: fee, slippage_bps: args.slippage_bps, min_net_change: min_net, report });
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outs)?);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Fee/hop", "Min net (%)", "Hits", "Executed", "False +", "FP rate", "PnL", "Max DD"]);
+    for o in &outs {
+        let r = &o.report;
+        table.add_row(vec![
+            Cell::new(format!("{}bps", o.fee_bps_per_hop)),
+            Cell::new(format!("{:.2}", o.min_net_change)),
+            Cell::new(r.hits),
+            Cell::new(r.executed),
+            Cell::new(r.false_positives),
+            Cell::new(format!("{:.1}%", r.false_positive_rate * 100.0)),
+            Cell::new(r.pnl),
+            Cell::new(r.max_drawdown),
+        ]);
+    }
+    println!("{table}");
+
+    if args.show_trades {
+        for o in &outs {
+            println!("— fee={}bps min_net={:.2}%", o.fee_bps_per_hop, o.min_net_change);
+            for t in &o.report.trades {
+                let realized = t.realized_net_bps.map(|b| b.to_string()).unwrap_or_else(|| "n/a".into());
+                println!("  {} {} expected={}bps realized={}bps profit={}", t.ts_ms, t.path, t.expected_net_bps, realized, t.profit);
+            }
+        }
+    }
+
+    Ok(())
+}
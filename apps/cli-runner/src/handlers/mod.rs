@@ -1,2 +1,25 @@
 pub mod quote;
-pub mod quote_matrix;
\ No newline at end of file
+pub mod quote_matrix;
+pub mod backtest;
+
+use std::path::Path;
+
+use anyhow::Result;
+use utils::fees::{average_paid, read_fee_records};
+
+/// 每笔交易费用（lamports）：--tx-fee-lamports 优先，其次 --fee-log 的平均值；base 不是 SOL 时不计
+pub fn tx_fee_lamports(base: &str, fixed: Option<u64>, fee_log: Option<&Path>) -> Result<u64> {
+    let fee = match (fixed, fee_log) {
+        (Some(f), _) => f,
+        (None, Some(path)) => {
+            let recs = read_fee_records(path)?;
+            average_paid(&recs).unwrap_or(0)
+        }
+        (None, None) => 0,
+    };
+    if fee > 0 && !base.eq_ignore_ascii_case("SOL") {
+        eprintln!("ℹ️ base={} 不是 SOL，交易费不计入净收益", base);
+        return Ok(0);
+    }
+    Ok(fee)
+}
@@ -14,7 +14,7 @@ pub async fn handle_quote(args: QuoteArgs) -> Result<()> {
     let lamports = (args.amount * 1_000_000_000.0) as u64;
 
     // ✅ quote 获取 + 容错处理
-    let quote = match fetch_jupiter_quote(input_mint, output_mint, lamports).await {
+    let quote = match fetch_jupiter_quote(input_mint, output_mint, lamports, args.slippage).await {
         Ok(q) => q,
         Err(e) => {
             eprintln!("❌ quote 获取失败: {} → {} | 错误: {}", args.input, args.output, e);
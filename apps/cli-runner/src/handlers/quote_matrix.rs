@@ -1,210 +1,107 @@
 use anyhow::Result;
-use std::cmp::Ordering;
+use std::cmp::Reverse;
 use std::collections::HashSet;
-use std::time::Duration;
 
 use crate::commands::quote_matrix::{QuoteMatrixArgs, Hops, ExportFormat};
-use arbitrage::{evaluate_2hop, evaluate_3hop, ArbRow, QuoteProvider as StratQuoter};
+use crate::handlers::tx_fee_lamports;
+use arbitrage::{
+    evaluate_2hop, evaluate_cycles, find_discrepancies, optimize_rows, requote_cycle,
+    Amount, ArbLeg, ArbRow, BestQuote, Bps, CircuitOpen, Discrepancy, DiscrepancyScan, Outcome, CycleSearch, FeeModel, QuoteCache, SizeSearch, QuoteProvider as StratQuoter,
+};
+use dex::{AccountSource, AccountStream, DexQuoter, PoolChange};
 use token_registry::api::MintResolver;
+use tokio::sync::mpsc;
 
 use utils::printer::{MatrixRow, print_matrix_table};
 
 use serde::Serialize;
-use tokio::time::sleep;
-
-// ===================== 限速 + 重试包装器（针对任意 Q） =====================
-pub struct ThrottleRetry<'a, Q: StratQuoter + ?Sized> {
-    inner: &'a Q,
-    interval_ms: u64,
-    retries: u32,
-}
-
-impl<'a, Q: StratQuoter + ?Sized> ThrottleRetry<'a, Q> {
-    pub fn new(inner: &'a Q, qps: u32, retries: u32) -> Self {
-        let interval_ms = (1000f64 / qps.max(1) as f64) as u64;
-        Self { inner, interval_ms, retries }
-    }
-}
-
-impl<'a, Q: StratQuoter + Send + Sync + ?Sized> StratQuoter for ThrottleRetry<'a, Q> {
-    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<u64> {
-        let mut attempt = 0u32;
-        let mut backoff = 100u64;
-        loop {
-            match self.inner.quote(input_mint.clone(), output_mint.clone(), amount).await {
-                Ok(out) => {
-                    sleep(Duration::from_millis(self.interval_ms)).await; // 限速
-                    return Ok(out);
-                }
-                Err(e) => {
-                    attempt += 1;
-                    if attempt > self.retries {
-                        return Err(e);
-                    }
-                    sleep(Duration::from_millis(backoff)).await;          // 退避
-                    backoff = (backoff * 2).min(1200);
-                }
-            }
-        }
-    }
-}
 
 // ===================== 导出与告警辅助 =====================
 #[derive(Serialize)]
 struct RowOut {
     path: String,
-    start: f64,
-    end_gross: f64,
-    end_net: f64,
+    start: Amount,
+    end_gross: Amount,
+    end_net: Amount,
     gross_pct: f64,
     net_pct: f64,
     profitable_net: bool,
     hops: u32,
+    /// 最优下单量（BASE 最小单位，需 --optimize-size）
+    optimal_in: Option<u64>,
+    /// 最优下单量下的预期净利润（BASE 最小单位）
+    expected_profit: Option<i128>,
     ts: i64,
+    /// 逐跳明细（需 --show-legs；CSV 导出时另写到 *.legs.csv）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    legs: Vec<ArbLeg>,
 }
 
-async fn send_telegram(token: &str, chat_id: &str, text: &str) -> Result<()> {
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
-    let body = serde_json::json!({ "chat_id": chat_id, "text": text });
-    let client = reqwest::Client::new();
-    let _ = client.post(url).json(&body).send().await?;
-    Ok(())
+/// CSV 不支持嵌套，逐跳明细单独成表：一跳一行
+#[derive(Serialize)]
+struct LegCsv {
+    path: String,
+    leg: usize,
+    from_symbol: String,
+    to_symbol: String,
+    from_mint: String,
+    to_mint: String,
+    amount_in: u64,
+    amount_out: u64,
+    leg_bps: Bps,
+    price_impact_pct: f64,
+    amm_label: String,
 }
 
-fn unix_ts() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs() as i64
+/// 价差分析导出：CSV 写到 <文件名>.discrepancy.csv，JSON 写到 <文件名>.discrepancy.json
+#[derive(Serialize)]
+struct DiscrepancyOut {
+    pair: String,
+    input_mint: String,
+    output_mint: String,
+    amount: u64,
+    best: String,
+    best_out: u64,
+    worst: String,
+    worst_out: u64,
+    spread_bps: Bps,
+    /// 各报价源输出，如 "jupiter=123 dex=456"（失败记为 -）
+    outs: String,
+    ts: i64,
 }
 
-// ===================== 主处理函数（泛型 Q） =====================
-pub async fn handle_quote_matrix<R, Q>(
-    args: QuoteMatrixArgs,
-    resolver: &R,
-    quoter: &Q,                 // ✅ 任何实现了 StratQuoter 的类型
-    require_tradable: bool,
-) -> Result<()>
-where
-    R: MintResolver,
-    Q: StratQuoter + Sync,
-{
-    
-    // 三跳时，默认并发自动降到 2 更稳
-    let effective_conc = match args.hops {
-        Hops::Three if args.concurrency == 5 => 2,
-        _ => args.concurrency,
-    };
-
-    let hops_u32 = match args.hops { Hops::Two => 2, Hops::Three => 3 };
-    let fee_slip_per_hop_bps = args.fee_bps_per_hop as i32 + args.slippage_bps as i32;
-    let total_fee_slip_bps = fee_slip_per_hop_bps * hops_u32 as i32;
-
-    println!(
-        "🧪 扫描 | base={} amount={} hops={:?} fee_per_hop={}bps slip={}bps total_fee_slip={}bps conc={} qps={} retries={}",
-        args.base, args.amount, args.hops, args.fee_bps_per_hop, args.slippage_bps,
-        total_fee_slip_bps, effective_conc, args.qps, args.retries
-    );
-
-    // 包一层限速+重试（不转移所有权，包的是 &Q）
-    let throttled = ThrottleRetry::new(quoter, args.qps, args.retries);
-
-    // 可选：在扫描前做 tradable 过滤
-    let tokens_filtered: Vec<String> = if require_tradable {
-        args.tokens
+impl DiscrepancyOut {
+    fn new(d: &Discrepancy, ts: i64) -> Self {
+        let outs: Vec<String> = d
+            .outs
             .iter()
-            .cloned()
-            .filter(|sym| {
-                if let Ok(mint) = resolver.get_mint(sym) {
-                    resolver.is_tradable(mint).unwrap_or(true)
-                } else { false }
+            .map(|(n, o)| match o {
+                Some(out) => format!("{}={}", n, out),
+                None => format!("{}=-", n),
             })
-            .collect()
-    } else {
-        args.tokens.clone()
-    };
-
-    // 1) 计算（策略层不做最小盈利阈值）
-    let mut rows: Vec<ArbRow> = match args.hops {
-        Hops::Two => {
-            evaluate_2hop(
-                resolver, &throttled, &args.base, &tokens_filtered, args.amount, i32::MIN, effective_conc
-            ).await
-        }
-        Hops::Three => {
-            let r = evaluate_3hop(
-                resolver, &throttled, &args.base, &tokens_filtered, args.amount, effective_conc, args.verbose
-            ).await;
-            if r.is_empty() {
-                eprintln!("ℹ️ 3-hop 返回为空，回退跑一轮 2-hop…");
-                evaluate_2hop(
-                    resolver, &throttled, &args.base, &tokens_filtered, args.amount, i32::MIN, effective_conc
-                ).await
-            } else { r }
+            .collect();
+        Self {
+            pair: d.pair.clone(),
+            input_mint: d.input_mint.clone(),
+            output_mint: d.output_mint.clone(),
+            amount: d.amount,
+            best: d.best.clone(),
+            best_out: d.best_out,
+            worst: d.worst.clone(),
+            worst_out: d.worst_out,
+            spread_bps: d.spread,
+            outs: outs.join(" "),
+            ts,
         }
-    };use anyhow::Result;
-use std::cmp::Ordering;
-use std::collections::HashSet;
-use std::time::Duration;
-
-use crate::commands::quote_matrix::{QuoteMatrixArgs, Hops, ExportFormat};
-use arbitrage::{evaluate_2hop, evaluate_3hop, ArbRow, QuoteProvider as StratQuoter};
-use token_registry::api::MintResolver;
-
-use utils::printer::{MatrixRow, print_matrix_table};
-
-use serde::Serialize;
-use tokio::time::sleep;
-
-// ===================== 限速 + 重试包装器（针对任意 Q） =====================
-pub struct ThrottleRetry<'a, Q: StratQuoter + ?Sized> {
-    inner: &'a Q,
-    interval_ms: u64,
-    retries: u32,
-}
-
-impl<'a, Q: StratQuoter + ?Sized> ThrottleRetry<'a, Q> {
-    pub fn new(inner: &'a Q, qps: u32, retries: u32) -> Self {
-        let interval_ms = (1000f64 / qps.max(1) as f64) as u64;
-        Self { inner, interval_ms, retries }
     }
 }
 
-impl<'a, Q: StratQuoter + Send + Sync + ?Sized> StratQuoter for ThrottleRetry<'a, Q> {
-    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<u64> {
-        let mut attempt = 0u32;
-        let mut backoff = 100u64;
-        loop {
-            match self.inner.quote(input_mint.clone(), output_mint.clone(), amount).await {
-                Ok(out) => {
-                    sleep(Duration::from_millis(self.interval_ms)).await; // 限速
-                    return Ok(out);
-                }
-                Err(e) => {
-                    attempt += 1;
-                    if attempt > self.retries {
-                        return Err(e);
-                    }
-                    sleep(Duration::from_millis(backoff)).await;          // 退避
-                    backoff = (backoff * 2).min(1200);
-                }
-            }
-        }
-    }
-}
-
-// ===================== 导出与告警辅助 =====================
-#[derive(Serialize)]
-struct RowOut {
-    path: String,
-    start: f64,
-    end_gross: f64,
-    end_net: f64,
-    gross_pct: f64,
-    net_pct: f64,
-    profitable_net: bool,
-    hops: u32,
-    ts: i64,
+fn leg_line(l: &ArbLeg) -> String {
+    format!(
+        "{}→{} {}→{} {}bps [{}]",
+        l.from_symbol, l.to_symbol, l.amount_in, l.amount_out, l.leg_bps,
+        if l.amm_label.is_empty() { "?" } else { &l.amm_label }
+    )
 }
 
 async fn send_telegram(token: &str, chat_id: &str, text: &str) -> Result<()> {
@@ -222,78 +119,118 @@ fn unix_ts() -> i64 {
         .as_secs() as i64
 }
 
+/// 要扫描的代币：require_tradable 时只留可交易的
+fn scan_tokens<R: MintResolver>(args: &QuoteMatrixArgs, resolver: &R, require_tradable: bool) -> Vec<String> {
+    if !require_tradable {
+        return args.tokens.clone();
+    }
+    args.tokens
+        .iter()
+        .filter(|sym| {
+            if let Ok(mint) = resolver.get_mint(sym) {
+                resolver.is_tradable(mint).unwrap_or(true)
+            } else { false }
+        })
+        .cloned()
+        .collect()
+}
+
 // ===================== 主处理函数（泛型 Q） =====================
 pub async fn handle_quote_matrix<R, Q>(
-    args: QuoteMatrixArgs,
+    args: &QuoteMatrixArgs,
     resolver: &R,
     quoter: &Q,                 // ✅ 任何实现了 StratQuoter 的类型
     require_tradable: bool,
-) -> Result<()>
+) -> Result<Vec<ArbRow>>
 where
     R: MintResolver,
     Q: StratQuoter + Sync,
 {
     
-    // 三跳时，默认并发自动降到 2 更稳
+    // 三跳及以上时，默认并发自动降到 2 更稳
     let effective_conc = match args.hops {
-        Hops::Three if args.concurrency == 5 => 2,
+        Hops::Two => args.concurrency,
+        _ if args.concurrency == 5 => 2,
         _ => args.concurrency,
     };
 
-    let hops_u32 = match args.hops { Hops::Two => 2, Hops::Three => 3 };
-    let fee_slip_per_hop_bps = args.fee_bps_per_hop as i32 + args.slippage_bps as i32;
-    let total_fee_slip_bps = fee_slip_per_hop_bps * hops_u32 as i32;
+    let hops_n = args.hops.count();
+    let tx_cost = tx_fee_lamports(&args.base, args.tx_fee_lamports, args.fee_log.as_deref())?;
+    let fees = FeeModel { fee_bps_per_hop: args.fee_bps_per_hop, slippage_bps: args.slippage_bps, tx_cost };
+    let total_fee_slip = fees.cost(hops_n);
+
+    // 起始数量：--amount-lamports 优先（已是最小单位），否则按人类单位
+    let base_dec = resolver.get_decimals(&args.base).unwrap_or(9);
+    let amount = match args.amount_lamports {
+        Some(l) => Amount::from_u64(l, base_dec),
+        None => args.amount.rescale(base_dec)?,
+    };
 
     println!(
-        "🧪 扫描 | base={} amount={} hops={:?} fee_per_hop={}bps slip={}bps total_fee_slip={}bps conc={} qps={} retries={}",
-        args.base, args.amount, args.hops, args.fee_bps_per_hop, args.slippage_bps,
-        total_fee_slip_bps, effective_conc, args.qps, args.retries
+        "🧪 扫描 | base={} amount={} hops={:?} fee_per_hop={}bps slip={}bps total_fee_slip={}bps tx_fee={}({}bps) conc={} qps={} burst={} retries={}",
+        args.base, amount, args.hops, args.fee_bps_per_hop, args.slippage_bps,
+        total_fee_slip, tx_cost, fees.tx_cost_bps(amount), effective_conc, args.qps, args.burst, args.retries
     );
 
-    // 包一层限速+重试（不转移所有权，包的是 &Q）
-    let throttled = ThrottleRetry::new(quoter, args.qps, args.retries);
+    // 包一层缓存（限速+重试已在报价源里做）：相同 (input, output, amount) 在 TTL 内不重复请求，命中不占限速额度
+    let cached = QuoteCache::new(quoter, args.cache_ttl_ms);
 
     // 可选：在扫描前做 tradable 过滤
-    let tokens_filtered: Vec<String> = if require_tradable {
-        args.tokens
-            .iter()
-            .cloned()
-            .filter(|sym| {
-                if let Ok(mint) = resolver.get_mint(sym) {
-                    resolver.is_tradable(mint).unwrap_or(true)
-                } else { false }
-            })
-            .collect()
-    } else {
-        args.tokens.clone()
-    };
+    let tokens_filtered = scan_tokens(args, resolver, require_tradable);
 
     // 1) 计算（策略层不做最小盈利阈值）
-    let mut rows: Vec<ArbRow> = match args.hops {
-        Hops::Two => {
-            evaluate_2hop(
-                resolver, &throttled, &args.base, &tokens_filtered, args.amount, i32::MIN, effective_conc
-            ).await
-        }
-        Hops::Three => {
-            let r = evaluate_3hop(
-                resolver, &throttled, &args.base, &tokens_filtered, args.amount, effective_conc, args.verbose
-            ).await;
-            if r.is_empty() {
-                eprintln!("ℹ️ 3-hop 返回为空，回退跑一轮 2-hop…");
-                evaluate_2hop(
-                    resolver, &throttled, &args.base, &tokens_filtered, args.amount, i32::MIN, effective_conc
-                ).await
-            } else { r }
-        }
+    let search = CycleSearch {
+        max_prefix_loss_bps: args.prune_loss_bps,
+        ..CycleSearch::exact(args.hops.count(), effective_conc)
     };
+    let mut rows: Vec<ArbRow> = evaluate_cycles(
+        resolver, &cached, &args.base, &tokens_filtered, amount, &search
+    ).await;
+    if rows.is_empty() && !matches!(args.hops, Hops::Two) && cached.available() {
+        eprintln!("ℹ️ {}-hop 返回为空，回退跑一轮 2-hop…", hops_n);
+        rows = evaluate_2hop(
+            resolver, &cached, &args.base, &tokens_filtered, amount, Bps::MIN, effective_conc
+        ).await;
+    }
+
+    // 报价源熔断：直接报错，而不是输出一张空表
+    if rows.is_empty() && !cached.available() {
+        return Err(CircuitOpen.into());
+    }
+
+    if args.verbose {
+        let st = cached.stats();
+        println!("🗃️ 报价缓存 | hits={} coalesced={} misses={}", st.hits, st.coalesced, st.misses);
+    }
 
     // 2) 去重（按 path）
     let mut seen = HashSet::new();
     rows.retain(|r| seen.insert(r.path.clone()));
 
     // 3) 按毛收益排序（delta_bps 高在前）
-    rows.sort_by(|a, b| b.delta_bps.partial_cmp(&a.delta_bps).unwrap_or(Ordering::Equal));
+    rows.sort_by_key(|r| Reverse(r.delta_bps));
+
+    // 3.5) 可选：对达到毛收益阈值的路径搜索最优下单量
+    if args.optimize_size {
+        let size_cfg = SizeSearch {
+            min_in: args.size_min.rescale(base_dec)?.to_u64()?,
+            max_in: args.size_max.rescale(base_dec)?.to_u64()?,
+            probes: args.size_probes,
+            cost_bps: total_fee_slip,
+            fixed_cost: tx_cost,
+        };
+        let keep = rows.iter().take_while(|r| r.delta_bps.to_pct() >= args.min_change).count();
+        let curves = optimize_rows(&cached, &mut rows[..keep], &size_cfg, effective_conc).await;
+        if args.verbose {
+            for (r, c) in rows.iter().zip(&curves) {
+                let Some(c) = c else { continue };
+                println!("📈 {} 最优下单 {} → 预期利润 {}", r.path, c.best.amount_in, c.best.profit);
+                for p in &c.curve {
+                    println!("    in={:<16} out={:<16} profit={}", p.amount_in, p.amount_out, p.profit);
+                }
+            }
+        }
+    }
 
     // 4) 计算净收益 & 过滤/导出/告警
     let now_ts = unix_ts();
@@ -302,31 +239,29 @@ where
     // 表格视图（用净 bps 驱动 Δ）
     let mut view_rows: Vec<MatrixRow> = Vec::new();
 
-    for r in rows.into_iter() {
-        // 毛收益（bps → %）
-        let gross_bps = r.delta_bps as i32;
-        let gross_pct = (gross_bps as f64) / 100.0;
-
-        // 线性净收益估算：net_bps = gross_bps - hops*(fee+slip)
-        let net_bps = gross_bps - total_fee_slip_bps;
-        let net_pct = (net_bps as f64) / 100.0;
+    for r in &rows {
+        // 毛收益 / 净收益（扣每跳手续费+滑点，按该行实际跳数，回退 2-hop 时也准确）
+        let gross = r.delta_bps;
+        let net = fees.net_bps(r);
+        let hops = r.hops() as u32;
 
         // 展示过滤：按毛收益阈值
-        if gross_pct < args.min_change {
+        if gross.to_pct() < args.min_change {
             continue;
         }
 
-        // 估算净终值（近似：按净百分比作用于起始值）
+        // 净终值：按净 bps 作用于起始数量（整数运算，向下取整）
         let end_gross = r.end;
-        let end_net = r.start * (1.0 + net_pct / 100.0);
+        let end_net = fees.end_net(r);
 
         // 表格输出：净 bps
         view_rows.push(MatrixRow {
-            profitable: net_bps > 0,
+            profitable: net.is_positive(),
             path: r.path.clone(),
-            start: r.start,
-            end: end_net,
-            delta_bps: net_bps as f64,
+            start: r.start.to_f64(),
+            end: end_net.to_f64(),
+            delta_bps: net.to_f64(),
+            legs: if args.show_legs { r.legs.iter().map(leg_line).collect() } else { vec![] },
         });
 
         // 导出结构
@@ -335,19 +270,22 @@ where
             start: r.start,
             end_gross,
             end_net,
-            gross_pct,
-            net_pct,
-            profitable_net: net_bps > 0,
-            hops: hops_u32,
+            gross_pct: gross.to_pct(),
+            net_pct: net.to_pct(),
+            profitable_net: net.is_positive(),
+            hops,
+            optimal_in: r.optimal_in,
+            expected_profit: r.expected_profit,
             ts: now_ts,
+            legs: if args.show_legs { r.legs.clone() } else { vec![] },
         });
 
         // 告警：净收益达到阈值才提醒
-        if net_pct >= args.min_net_change {
+        if net.to_pct() >= args.min_net_change {
             if let (Some(tok), Some(chat)) = (&args.tg_token, &args.tg_chat) {
                 let _ = send_telegram(tok, chat, &format!(
-                    "✅ {}\nGross: {:.3}%  Net: {:.3}%\nEnd(net): {:.6}",
-                    r.path, gross_pct, net_pct, end_net
+                    "✅ {}\nGross: {:.3}%  Net: {:.3}%\nEnd(net): {}",
+                    r.path, gross.to_pct(), net.to_pct(), end_net
                 )).await;
             }
         }
@@ -373,9 +311,35 @@ where
         match args.export_format {
             ExportFormat::Csv => {
                 let mut wtr = csv::Writer::from_path(path)?;
-                for row in &export_rows { wtr.serialize(row)?; }
+                let mut legs_out: Vec<LegCsv> = Vec::new();
+                for row in &mut export_rows {
+                    for (i, l) in std::mem::take(&mut row.legs).into_iter().enumerate() {
+                        legs_out.push(LegCsv {
+                            path: row.path.clone(),
+                            leg: i + 1,
+                            from_symbol: l.from_symbol,
+                            to_symbol: l.to_symbol,
+                            from_mint: l.from_mint,
+                            to_mint: l.to_mint,
+                            amount_in: l.amount_in,
+                            amount_out: l.amount_out,
+                            leg_bps: l.leg_bps,
+                            price_impact_pct: l.price_impact_pct,
+                            amm_label: l.amm_label,
+                        });
+                    }
+                    wtr.serialize(&*row)?;
+                }
                 wtr.flush()?;
                 println!("💾 CSV 导出: {}", path.display());
+
+                if !legs_out.is_empty() {
+                    let legs_path = path.with_extension("legs.csv");
+                    let mut wtr = csv::Writer::from_path(&legs_path)?;
+                    for l in &legs_out { wtr.serialize(l)?; }
+                    wtr.flush()?;
+                    println!("💾 逐跳明细导出: {}", legs_path.display());
+                }
             }
             ExportFormat::Json => {
                 std::fs::write(path, serde_json::to_vec_pretty(&export_rows)?)?;
@@ -384,105 +348,160 @@ where
         }
     }
 
-    Ok(())
+    Ok(rows)
 }
 
-
-    // 2) 去重（按 path）
-    let mut seen = HashSet::new();
-    rows.retain(|r| seen.insert(r.path.clone()));
-
-    // 3) 按毛收益排序（delta_bps 高在前）
-    rows.sort_by(|a, b| b.delta_bps.partial_cmp(&a.delta_bps).unwrap_or(Ordering::Equal));
-
-    // 4) 计算净收益 & 过滤/导出/告警
-    let now_ts = unix_ts();
-    let mut export_rows: Vec<RowOut> = Vec::new();
-
-    // 表格视图（用净 bps 驱动 Δ）
-    let mut view_rows: Vec<MatrixRow> = Vec::new();
-
-    for r in rows.into_iter() {
-        // 毛收益（bps → %）
-        let gross_bps = r.delta_bps as i32;
-        let gross_pct = (gross_bps as f64) / 100.0;
-
-        // 线性净收益估算：net_bps = gross_bps - hops*(fee+slip)
-        let net_bps = gross_bps - total_fee_slip_bps;
-        let net_pct = (net_bps as f64) / 100.0;
-
-        // 展示过滤：按毛收益阈值
-        if gross_pct < args.min_change {
-            continue;
-        }
-
-        // 估算净终值（近似：按净百分比作用于起始值）
-        let end_gross = r.end;
-        let end_net = r.start * (1.0 + net_pct / 100.0);
-
-        // 表格输出：净 bps
-        view_rows.push(MatrixRow {
-            profitable: net_bps > 0,
-            path: r.path.clone(),
-            start: r.start,
-            end: end_net,
-            delta_bps: net_bps as f64,
-        });
-
-        // 导出结构
-        export_rows.push(RowOut {
-            path: r.path.clone(),
-            start: r.start,
-            end_gross,
-            end_net,
-            gross_pct,
-            net_pct,
-            profitable_net: net_bps > 0,
-            hops: hops_u32,
-            ts: now_ts,
-        });
-
-        // 告警：净收益达到阈值才提醒
-        if net_pct >= args.min_net_change {
-            if let (Some(tok), Some(chat)) = (&args.tg_token, &args.tg_chat) {
-                let _ = send_telegram(tok, chat, &format!(
-                    "✅ {}\nGross: {:.3}%  Net: {:.3}%\nEnd(net): {:.6}",
-                    r.path, gross_pct, net_pct, end_net
-                )).await;
-            }
-        }
+// ===================== 多报价源对比 =====================
+/// --provider best：各报价源的胜出次数、平均差距，以及价差最大的 top 次比较
+pub fn print_best_quote_report<Q: StratQuoter>(best: &BestQuote<Q>, top: usize) {
+    println!("🏁 报价源对比");
+    for s in best.stats() {
+        println!(
+            "  {:<8} wins={:<5} quoted={:<5} failed={:<4} timeout={:<4} skipped={:<4} avg_shortfall={:.2}bps",
+            s.name, s.wins, s.quoted, s.failed, s.timed_out, s.skipped, s.avg_shortfall().to_f64()
+        );
     }
 
-    // 5) Top-K
-    if args.top_k > 0 && view_rows.len() > args.top_k {
-        view_rows.truncate(args.top_k);
-    }
-    if args.top_k > 0 && export_rows.len() > args.top_k {
-        export_rows.truncate(args.top_k);
+    let mut cs = best.comparisons();
+    cs.retain(|c| c.spread.is_positive());
+    cs.sort_by_key(|c| Reverse(c.spread));
+    for c in cs.iter().take(top) {
+        let outs: Vec<String> = c
+            .outcomes
+            .iter()
+            .map(|(n, o)| match o {
+                Outcome::Quoted(out) => format!("{}={}", n, out),
+                other => format!("{}={:?}", n, other),
+            })
+            .collect();
+        println!(
+            "  {}→{} in={} winner={} spread={:.2}bps | {}",
+            c.input_mint, c.output_mint, c.amount, c.winner, c.spread.to_f64(), outs.join(" ")
+        );
     }
+}
 
-    // 6) 输出
+/// --discrepancy：用 best 的各成员分别询价，报告价差超过阈值的交易对
+pub async fn handle_discrepancies<R, Q>(
+    args: &QuoteMatrixArgs,
+    resolver: &R,
+    best: &BestQuote<Q>,
+    require_tradable: bool,
+) -> Result<()>
+where
+    R: MintResolver + Sync,
+    Q: StratQuoter + Sync,
+{
+    let sources: Vec<(String, &Q)> = best.sources().map(|(n, q)| (n.to_string(), q)).collect();
+    let cfg = DiscrepancyScan {
+        sizes: args.discrepancy_sizes.clone(),
+        min_spread: args.discrepancy_bps,
+        reverse: true,
+        concurrency: args.concurrency,
+    };
+    let tokens = scan_tokens(args, resolver, require_tradable);
+    let found = find_discrepancies(resolver, &sources, &args.base, &tokens, &cfg).await;
+
+    let ts = unix_ts();
+    let out: Vec<DiscrepancyOut> = found.iter().map(|d| DiscrepancyOut::new(d, ts)).collect();
     if args.json {
-        println!("{}", serde_json::to_string_pretty(&export_rows)?);
+        println!("{}", serde_json::to_string_pretty(&out)?);
     } else {
-        print_matrix_table(view_rows);
+        println!("🔍 报价源价差 ≥ {}bps：{} 条", args.discrepancy_bps.to_f64(), out.len());
+        for d in &out {
+            println!(
+                "  {} in={} best={}({}) worst={}({}) spread={:.2}bps | {}",
+                d.pair, d.amount, d.best, d.best_out, d.worst, d.worst_out, d.spread_bps.to_f64(), d.outs
+            );
+        }
     }
 
-    // 7) 导出到文件
     if let Some(path) = &args.export {
         match args.export_format {
             ExportFormat::Csv => {
-                let mut wtr = csv::Writer::from_path(path)?;
-                for row in &export_rows { wtr.serialize(row)?; }
+                let path = path.with_extension("discrepancy.csv");
+                let mut wtr = csv::Writer::from_path(&path)?;
+                for d in &out { wtr.serialize(d)?; }
                 wtr.flush()?;
-                println!("💾 CSV 导出: {}", path.display());
+                println!("💾 价差导出: {}", path.display());
             }
             ExportFormat::Json => {
-                std::fs::write(path, serde_json::to_vec_pretty(&export_rows)?)?;
-                println!("💾 JSON 导出: {}", path.display());
+                let path = path.with_extension("discrepancy.json");
+                std::fs::write(&path, serde_json::to_vec_pretty(&out)?)?;
+                println!("💾 价差导出: {}", path.display());
             }
         }
     }
+    Ok(())
+}
+
+// ===================== 事件驱动：池子变化 → 重算受影响路径 =====================
+/// 订阅池子账户变更，每次池子状态变化只重算经过该交易对的路径；推送结束（回放文件读完）时返回
+pub async fn watch_quote_matrix<Q, A, S>(
+    args: &QuoteMatrixArgs,
+    quoter: &Q,
+    pools: &DexQuoter<A>,
+    stream: &S,
+    mut rows: Vec<ArbRow>,
+) -> Result<()>
+where
+    Q: StratQuoter + Sync,
+    A: AccountSource,
+    S: AccountStream,
+{
+    let tx_cost = tx_fee_lamports(&args.base, args.tx_fee_lamports, args.fee_log.as_deref())?;
+    let fees = FeeModel { fee_bps_per_hop: args.fee_bps_per_hop, slippage_bps: args.slippage_bps, tx_cost };
+    println!("👀 监听 {} 个账户 | {} 个池子 | {} 条路径", pools.accounts().len(), pools.len(), rows.len());
+
+    let (tx, mut rx) = mpsc::channel::<PoolChange>(1024);
+    let consume = async {
+        while let Some(first) = rx.recv().await {
+            // 合并积压的变化，同一条路径一轮只重算一次
+            let mut changes = vec![first];
+            while let Ok(c) = rx.try_recv() {
+                changes.push(c);
+            }
+            let slot = changes.iter().map(|c| c.slot).max().unwrap_or_default();
 
+            for r in rows.iter_mut() {
+                let mints = r.mints();
+                if !changes.iter().any(|c| c.touches(&mints)) {
+                    continue;
+                }
+                match requote_cycle(quoter, r).await {
+                    Ok(fresh) => *r = fresh,
+                    Err(e) => {
+                        eprintln!("⚠️ {} 重算失败: {}", r.path, e);
+                        continue;
+                    }
+                }
+                let gross = r.delta_bps;
+                let net = fees.net_bps(r);
+                if gross.to_pct() < args.min_change {
+                    continue;
+                }
+                let end_net = fees.end_net(r);
+                println!(
+                    "🔄 slot={} {} gross={:.3}% net={:.3}% end(net)={}",
+                    slot, r.path, gross.to_pct(), net.to_pct(), end_net
+                );
+                if args.show_legs {
+                    for l in &r.legs {
+                        println!("    {}", leg_line(l));
+                    }
+                }
+                if net.to_pct() >= args.min_net_change {
+                    if let (Some(tok), Some(chat)) = (&args.tg_token, &args.tg_chat) {
+                        let _ = send_telegram(tok, chat, &format!(
+                            "✅ {} (slot {})\nGross: {:.3}%  Net: {:.3}%\nEnd(net): {}",
+                            r.path, slot, gross.to_pct(), net.to_pct(), end_net
+                        )).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    };
+    tokio::try_join!(pools.watch(stream, tx), consume)?;
     Ok(())
 }
@@ -5,10 +5,13 @@ mod clients;
 use clap::{Parser, Subcommand};
 use commands::quote::{run as run_quote, QuoteArgs};
 use commands::quote_matrix::QuoteMatrixArgs;
-use handlers::quote_matrix::handle_quote_matrix;
-use crate::clients::{build_provider, ProviderKind};
+use commands::backtest::BacktestArgs;
+use handlers::quote_matrix::{handle_discrepancies, handle_quote_matrix, print_best_quote_report, watch_quote_matrix};
+use handlers::backtest::handle_backtest;
+use crate::clients::{build_provider, dex::DexStream, ProviderOpts};
 
-use token_registry::LocalMintResolver; // 如果你没有这个类型，就用 Registry::from_sources(...)
+use arbitrage::RateLimiter;
+use token_registry::LocalResolver;
 
 #[derive(Parser)]
 #[command(name = "cli-runner")]
@@ -21,7 +24,9 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     Quote(QuoteArgs),
-    QuoteMatrix(QuoteMatrixArgs),
+    QuoteMatrix(Box<QuoteMatrixArgs>),
+    /// 用录制的报价快照回测阈值/费用参数
+    Backtest(BacktestArgs),
 }
 
 #[tokio::main]
@@ -38,14 +43,49 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Quote(args) => run_quote(args).await?,
         Commands::QuoteMatrix(args) => {
             // 1) resolver
-            let resolver = LocalMintResolver::new(); // 或 Registry::from_sources(...)
+            let resolver = LocalResolver::with_builtin();
             let require_tradable = true;
 
-            // 2) quoter（用工厂：Jupiter/Mock 可切换）
-            let quoter = build_provider(ProviderKind::Jupiter, args.qps, args.retries);
+            // 2) quoter（用工厂：Jupiter/Mock/Replay/Dex/Best 可切换，可选录制）
+            let quoter = build_provider(&ProviderOpts {
+                kind: args.provider,
+                limiter: RateLimiter::new(args.qps, args.burst),
+                retries: args.retries,
+                record: args.record.clone(),
+                replay_file: args.replay_file.clone(),
+                replay_match: args.replay_match,
+                pools_file: args.pools.clone(),
+                rpc_url: args.rpc_url.clone(),
+                best_of: args.best_of.clone(),
+                deadline_ms: args.deadline_ms,
+            }).await?;
 
-            // 3) 跑
-            handle_quote_matrix(args, &resolver, &quoter, require_tradable).await?;
+            // 3) 价差分析单独跑，不用等整个环路矩阵
+            if args.discrepancy {
+                let best = quoter.best().ok_or("--discrepancy 需要 --provider best")?;
+                handle_discrepancies(&args, &resolver, best, require_tradable).await?;
+                return Ok(());
+            }
+
+            // 4) 跑
+            let rows = handle_quote_matrix(&args, &resolver, &quoter, require_tradable).await?;
+            if let Some(best) = quoter.best() {
+                print_best_quote_report(best, if args.top_k > 0 { args.top_k } else { 10 });
+            }
+            if quoter.record_errors() > 0 {
+                eprintln!("⚠️ 报价录制有 {} 条写入失败", quoter.record_errors());
+            }
+
+            // 5) 可选：订阅池子变更，事件驱动地重算
+            if args.watch {
+                let pools = quoter.dex().ok_or("--watch 需要 --provider dex（或 --best-of 含 dex）")?;
+                let stream = DexStream::new(&args.ws_url, args.stream_file.as_deref());
+                watch_quote_matrix(&args, &quoter, pools, &stream, rows).await?;
+            }
+        }
+        Commands::Backtest(args) => {
+            let resolver = LocalResolver::with_builtin();
+            handle_backtest(args, &resolver).await?;
         }
     }
 
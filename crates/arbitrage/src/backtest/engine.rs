@@ -0,0 +1,121 @@
+use anyhow::Result;
+use serde::Serialize;
+use crate::core::amount::{Amount, Bps};
+use crate::core::cycle::{evaluate_cycles, CycleSearch};
+use crate::core::eval_2hop::evaluate_2hop;
+use crate::core::eval_3hop::evaluate_3hop;
+use crate::core::fees::FeeModel;
+use crate::core::sizing::quote_path;
+use crate::core::types::ArbRow;
+use crate::ports::resolver::MintResolver;
+use super::snapshot::Snapshot;
+
+/// 回测参数（阈值/费用与 quote-matrix 的含义一致）
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    pub base: String,
+    pub mids: Vec<String>,
+    pub amount: Amount,
+    pub hops: usize,
+    pub fees: FeeModel,
+    /// 净收益达到该值才“发现并执行”
+    pub min_net_bps: Bps,
+    /// 发现后隔几个快照成交（模拟延迟，0 = 按发现时的报价成交）
+    pub exec_delay: usize,
+    pub concurrency: usize,
+}
+
+/// 一次模拟成交
+#[derive(Debug, Clone, Serialize)]
+pub struct Trade {
+    /// 发现时快照的时间
+    pub ts_ms: u64,
+    pub path: String,
+    /// 发现时预期的净收益
+    pub expected_net_bps: Bps,
+    /// 成交时快照下的实际净收益（None = 成交快照里报不出价，按未成交处理）
+    pub realized_net_bps: Option<Bps>,
+    /// 实际净利润（base 最小单位）
+    pub profit: i128,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BacktestReport {
+    pub snapshots: usize,
+    /// 达到阈值被标记的路径数
+    pub hits: usize,
+    /// 成功成交（能在成交快照里报出价）的次数
+    pub executed: usize,
+    /// 成交后实际净收益 <= 0 的次数（含报不出价的）
+    pub false_positives: usize,
+    pub false_positive_rate: f64,
+    /// 累计净利润 / 最大回撤（base 最小单位）
+    pub pnl: i128,
+    pub max_drawdown: i128,
+    pub trades: Vec<Trade>,
+}
+
+/// 把快照序列逐个喂给环路评估，模拟“发现 → 延迟 exec_delay 个快照后按当时价格成交”
+///
+/// 每个快照内同一路径最多成交一次；一个快照内可同时成交多条路径，互不影响（不建模资金占用）。
+/// 起始数量超出 u64 时报错。
+pub async fn run_backtest<R>(resolver: &R, snapshots: &[Snapshot], cfg: &BacktestConfig) -> Result<BacktestReport>
+where
+    R: MintResolver + Sync,
+{
+    let mut rep = BacktestReport { snapshots: snapshots.len(), ..Default::default() };
+    let (mut peak, mut pnl) = (0i128, 0i128);
+
+    for (i, snap) in snapshots.iter().enumerate() {
+        let rows = match cfg.hops {
+            2 => evaluate_2hop(resolver, &snap.quoter, &cfg.base, &cfg.mids, cfg.amount, Bps::MIN, cfg.concurrency).await,
+            3 => evaluate_3hop(resolver, &snap.quoter, &cfg.base, &cfg.mids, cfg.amount, cfg.concurrency, false).await,
+            n => {
+                let search = CycleSearch::exact(n, cfg.concurrency);
+                evaluate_cycles(resolver, &snap.quoter, &cfg.base, &cfg.mids, cfg.amount, &search).await
+            }
+        };
+
+        for r in rows {
+            let expected = cfg.fees.net_bps(&r);
+            if expected < cfg.min_net_bps { continue; }
+            rep.hits += 1;
+
+            // 成交快照：不够往后就按最后一个
+            let exec = &snapshots[(i + cfg.exec_delay).min(snapshots.len() - 1)];
+            let start = r.start.to_u64()?;
+            // 成交时的净收益：与预期同一套费用（含交易费），只换毛收益
+            let realized = quote_path(&exec.quoter, &r.mints(), start).await.ok().map(|out| {
+                let delta_bps = Bps::from_ratio(out as u128, start as u128);
+                cfg.fees.net_bps(&ArbRow { delta_bps, ..r.clone() })
+            });
+
+            let profit = match realized {
+                Some(net) => {
+                    rep.executed += 1;
+                    r.start.apply_bps(net).raw() as i128 - r.start.raw() as i128
+                }
+                None => 0,
+            };
+            if !realized.is_some_and(|b| b.is_positive()) {
+                rep.false_positives += 1;
+            }
+
+            pnl += profit;
+            peak = peak.max(pnl);
+            rep.max_drawdown = rep.max_drawdown.max(peak - pnl);
+
+            rep.trades.push(Trade {
+                ts_ms: snap.ts_ms,
+                path: r.path,
+                expected_net_bps: expected,
+                realized_net_bps: realized,
+                profit,
+            });
+        }
+    }
+
+    rep.pnl = pnl;
+    rep.false_positive_rate = if rep.hits > 0 { rep.false_positives as f64 / rep.hits as f64 } else { 0.0 };
+    Ok(rep)
+}
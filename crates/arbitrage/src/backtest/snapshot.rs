@@ -0,0 +1,31 @@
+use crate::middleware::recorder::QuoteRecord;
+use crate::providers::replay::{ReplayMode, ReplayQuoter};
+
+/// 一轮扫描录到的所有报价（同一时刻的市场快照）
+pub struct Snapshot {
+    /// 本轮第一条记录的时间
+    pub ts_ms: u64,
+    pub quoter: ReplayQuoter,
+}
+
+/// 按时间把录制记录切成快照：相邻两条记录间隔超过 gap_ms 即视为新的一轮
+pub fn split_snapshots(mut recs: Vec<QuoteRecord>, gap_ms: u64, mode: ReplayMode) -> Vec<Snapshot> {
+    recs.sort_by_key(|r| r.ts_ms);
+
+    let mut out = Vec::new();
+    let mut cur: Vec<QuoteRecord> = Vec::new();
+    for r in recs {
+        if let Some(last) = cur.last() {
+            if r.ts_ms.saturating_sub(last.ts_ms) > gap_ms {
+                let ts_ms = cur[0].ts_ms;
+                out.push(Snapshot { ts_ms, quoter: ReplayQuoter::from_records(std::mem::take(&mut cur), mode) });
+            }
+        }
+        cur.push(r);
+    }
+    if let Some(first) = cur.first() {
+        let ts_ms = first.ts_ms;
+        out.push(Snapshot { ts_ms, quoter: ReplayQuoter::from_records(cur, mode) });
+    }
+    out
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+use anyhow::{anyhow, bail, Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 最多支持的小数位（10^30 仍在 u128 范围内，留出乘法余量）
+pub const MAX_DECIMALS: u8 = 30;
+
+#[inline]
+fn pow10(n: u8) -> u128 {
+    10u128.pow(n as u32)
+}
+
+/* ===================== Amount：定点数量 ===================== */
+
+/// 定点数量：`raw / 10^decimals`，raw 即链上最小单位
+///
+/// 从字符串解析时 decimals = 小数部分的位数（"1.50" → raw 150, decimals 2），
+/// 再用 `rescale` 换到代币精度；换精度不会丢任何非零位，丢位直接报错。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Amount {
+    raw: u128,
+    decimals: u8,
+}
+
+impl Amount {
+    pub const fn from_raw(raw: u128, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    pub const fn from_u64(raw: u64, decimals: u8) -> Self {
+        Self { raw: raw as u128, decimals }
+    }
+
+    /// 按代币精度解析人类可读数量，如 `parse_ui("0.25", 9)` → 250_000_000
+    pub fn parse_ui(s: &str, decimals: u8) -> Result<Self> {
+        s.parse::<Amount>()?.rescale(decimals)
+    }
+
+    pub const fn raw(self) -> u128 {
+        self.raw
+    }
+
+    pub const fn decimals(self) -> u8 {
+        self.decimals
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.raw == 0
+    }
+
+    /// 换成链上 u64（超出范围报错）
+    pub fn to_u64(self) -> Result<u64> {
+        u64::try_from(self.raw).map_err(|_| anyhow!("amount {} overflows u64", self))
+    }
+
+    /// 精确换精度：升精度补零，降精度要求被截掉的位全为 0
+    pub fn rescale(self, decimals: u8) -> Result<Self> {
+        if decimals > MAX_DECIMALS {
+            bail!("decimals {} > {}", decimals, MAX_DECIMALS);
+        }
+        let raw = if decimals >= self.decimals {
+            self.raw
+                .checked_mul(pow10(decimals - self.decimals))
+                .ok_or_else(|| anyhow!("amount {} overflows at {} decimals", self, decimals))?
+        } else {
+            let f = pow10(self.decimals - decimals);
+            if !self.raw.is_multiple_of(f) {
+                bail!("amount {} has more than {} decimals", self, decimals);
+            }
+            self.raw / f
+        };
+        Ok(Self { raw, decimals })
+    }
+
+    /// 按 bps 增减（向下取整，最低到 0）
+    pub fn apply_bps(self, bps: Bps) -> Self {
+        let one = Bps::ONE_UNIT;
+        let factor = one.saturating_add(bps.0).max(0);
+        let raw = (self.raw as i128)
+            .checked_mul(factor)
+            .map(|x| x.div_euclid(one))
+            .unwrap_or(i128::MAX)
+            .max(0) as u128;
+        Self { raw, decimals: self.decimals }
+    }
+
+    /// 仅用于展示/画图，不参与计算
+    pub fn to_f64(self) -> f64 {
+        self.raw as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let t = s.trim();
+        let t = t.strip_prefix('+').unwrap_or(t);
+        let (int, frac) = t.split_once('.').unwrap_or((t, ""));
+
+        if int.is_empty() && frac.is_empty() {
+            bail!("invalid amount: {:?}", s);
+        }
+        if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            bail!("invalid amount: {:?}", s);
+        }
+        if frac.len() > MAX_DECIMALS as usize {
+            bail!("amount {:?} has more than {} decimals", s, MAX_DECIMALS);
+        }
+
+        let decimals = frac.len() as u8;
+        let digits = format!("{}{}", int, frac);
+        let raw = if digits.is_empty() { 0 } else {
+            digits.parse::<u128>().map_err(|_| anyhow!("amount {:?} overflows", s))?
+        };
+        Ok(Self { raw, decimals })
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.decimals == 0 {
+            return write!(f, "{}", self.raw);
+        }
+        let p = pow10(self.decimals);
+        write!(f, "{}.{:0width$}", self.raw / p, self.raw % p, width = self.decimals as usize)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/* ===================== Bps：定点基点 ===================== */
+
+/// 定点基点，内部单位 1e-6 bps（`Bps::SCALE`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Bps(i128);
+
+impl Bps {
+    /// 1 bps = SCALE 个内部单位
+    pub const SCALE: i128 = 1_000_000;
+    pub const ZERO: Bps = Bps(0);
+    pub const MIN: Bps = Bps(i128::MIN);
+    pub const MAX: Bps = Bps(i128::MAX);
+    /// 100% 对应的内部单位
+    const ONE_UNIT: i128 = 10_000 * Self::SCALE;
+
+    pub const fn from_int(bps: i64) -> Self {
+        Bps(bps as i128 * Self::SCALE)
+    }
+
+    pub const fn from_micro(micro_bps: i128) -> Self {
+        Bps(micro_bps)
+    }
+
+    pub const fn micro(self) -> i128 {
+        self.0
+    }
+
+    /// (end / start - 1)，向下取整到 1e-6 bps；start 为 0 时返回 0
+    pub fn from_ratio(end: u128, start: u128) -> Self {
+        if start == 0 {
+            return Self::ZERO;
+        }
+        let (Ok(e), Ok(s)) = (i128::try_from(end), i128::try_from(start)) else {
+            return if end >= start { Self::MAX } else { Self::MIN };
+        };
+        match (e - s).checked_mul(Self::ONE_UNIT) {
+            Some(x) => Bps(x.div_euclid(s)),
+            None => if e >= s { Self::MAX } else { Self::MIN },
+        }
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// 仅用于展示
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    /// 百分比（仅用于展示），100 bps = 1%
+    pub fn to_pct(self) -> f64 {
+        self.to_f64() / 100.0
+    }
+
+    /// raw × bps 对应的数量（向上取整，估算成本时不低估）
+    pub fn of_raw(self, raw: u128) -> i128 {
+        let x = (raw as i128).saturating_mul(self.0);
+        -(-x).div_euclid(Self::ONE_UNIT)
+    }
+
+    /// 取整到整 bps（向零取整）
+    pub fn whole(self) -> i128 {
+        self.0 / Self::SCALE
+    }
+}
+
+impl Add for Bps {
+    type Output = Bps;
+    fn add(self, rhs: Bps) -> Bps { Bps(self.0.saturating_add(rhs.0)) }
+}
+
+impl Sub for Bps {
+    type Output = Bps;
+    fn sub(self, rhs: Bps) -> Bps { Bps(self.0.saturating_sub(rhs.0)) }
+}
+
+impl Neg for Bps {
+    type Output = Bps;
+    fn neg(self) -> Bps { Bps(self.0.saturating_neg()) }
+}
+
+impl FromStr for Bps {
+    type Err = Error;
+
+    /// "12"、"-3.5"、"+0.000001"；小数最多 6 位
+    fn from_str(s: &str) -> Result<Self> {
+        let t = s.trim();
+        let (neg, t) = match t.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, t.strip_prefix('+').unwrap_or(t)),
+        };
+        let a: Amount = t.parse()?;
+        let micro = a.rescale(6).map_err(|_| anyhow!("bps {:?} has more than 6 decimals", s))?.raw();
+        let micro = i128::try_from(micro).map_err(|_| anyhow!("bps {:?} overflows", s))?;
+        Ok(Bps(if neg { -micro } else { micro }))
+    }
+}
+
+impl fmt::Display for Bps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "+" };
+        let abs = self.0.unsigned_abs();
+        let scale = Self::SCALE as u128;
+        write!(f, "{}{}.{:06}", sign, abs / scale, abs % scale)
+    }
+}
+
+impl Serialize for Bps {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bps {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use anyhow::{bail, Result};
+use futures::{stream, StreamExt};
+use crate::ports::quote::QuoteProvider;
+use crate::ports::resolver::MintResolver;
+use crate::ports::quote::Quote;
+use super::amount::{Amount, Bps};
+use super::types::{ArbLeg, ArbRow};
+
+/// 环路最多几跳（含回到 base 的最后一跳）
+pub const MAX_HOPS: usize = 5;
+
+/// N 跳环路搜索参数：BASE → X1 → … → Xk → BASE
+#[derive(Debug, Clone)]
+pub struct CycleSearch {
+    /// 最少跳数（>= 2）
+    pub min_hops: usize,
+    /// 最多跳数（<= MAX_HOPS）
+    pub max_hops: usize,
+    /// 中间亏损剪枝：前缀按 base 估值的亏损超过该值就不再往下扩展；None 表示不剪枝
+    pub max_prefix_loss_bps: Option<Bps>,
+    /// 收尾后低于该值的环路不返回（Bps::MIN 表示全部返回）
+    pub min_profit_bps: Bps,
+    /// 最大并发报价数
+    pub concurrency: usize,
+}
+
+impl CycleSearch {
+    /// 只搜固定跳数的环路
+    pub fn exact(hops: usize, concurrency: usize) -> Self {
+        Self { min_hops: hops, max_hops: hops, max_prefix_loss_bps: None, min_profit_bps: Bps::MIN, concurrency }
+    }
+
+    /// 搜 2..=max_hops 的所有环路
+    pub fn up_to(max_hops: usize, concurrency: usize) -> Self {
+        Self { min_hops: 2, max_hops, max_prefix_loss_bps: None, min_profit_bps: Bps::MIN, concurrency }
+    }
+}
+
+/* 已走过的前缀：tokens 为 mids 的下标，amount 为当前持有量（最小单位） */
+#[derive(Clone)]
+struct Prefix {
+    tokens: Vec<usize>,
+    amount: u64,
+    legs: Vec<ArbLeg>,
+}
+
+fn leg(from: (&str, &str), to: (&str, &str), q: &Quote) -> ArbLeg {
+    ArbLeg {
+        from_symbol: from.0.to_string(),
+        from_mint: from.1.to_string(),
+        to_symbol: to.0.to_string(),
+        to_mint: to.1.to_string(),
+        amount_in: q.in_amount,
+        amount_out: q.out_amount,
+        leg_bps: Bps::ZERO,
+        amm_label: q.label(),
+        price_impact_pct: q.price_impact_pct,
+    }
+}
+
+enum Step {
+    /// 前缀 → BASE，得到一条完整环路
+    Close(Prefix),
+    /// 前缀 → 下一个 mid
+    Extend(Prefix, usize),
+}
+
+enum Outcome {
+    Row(ArbRow),
+    Grown(Prefix),
+}
+
+/// 在 base + mids 构成的完全图上枚举以 base 为起终点的简单环路
+///
+/// 第一层 BASE → X 的报价同时作为各 mid 的“估值基准”：持有 n 个 X 约等于
+/// `n * start / ref(X)` 个 BASE，用来判断前缀是否已经亏得太多（剪枝），
+/// 也用来算每一跳的 `leg_bps`（首跳按定义为 0，偏差体现在后续各跳）。
+pub async fn evaluate_cycles<R, Q>(
+    resolver: &R,
+    quoter: &Q,
+    base: &str,
+    mids: &[String],
+    ui_amount: Amount,
+    cfg: &CycleSearch,
+) -> Vec<ArbRow>
+where
+    R: MintResolver + Sync,
+    Q: QuoteProvider + Sync,
+{
+    let max_hops = cfg.max_hops.clamp(2, MAX_HOPS);
+    let min_hops = cfg.min_hops.max(2);
+    if min_hops > max_hops { return vec![]; }
+
+    let base_uc   = base.to_uppercase();
+    let base_mint = match resolver.get_mint(&base_uc) { Ok(m) => m.to_string(), Err(_) => return vec![] };
+    let base_dec  = resolver.get_decimals(&base_uc).unwrap_or(9);
+    let Ok(start) = ui_amount.rescale(base_dec) else { return vec![] };
+    let Ok(start_amt) = start.to_u64() else { return vec![] };
+
+    // (symbol, mint)，按 mint 去重，并跳过 base 自己
+    let mut tokens: Vec<(String, String)> = Vec::new();
+    for s in mids {
+        if let Ok(m) = resolver.get_mint(s) {
+            if m != base_mint && !tokens.iter().any(|(_, x)| x == m) {
+                tokens.push((s.to_uppercase(), m.to_string()));
+            }
+        }
+    }
+
+    let conc = cfg.concurrency.max(1);
+
+    // 第一层：BASE → X
+    let firsts: Vec<(usize, Quote)> = stream::iter(0..tokens.len())
+        .map(|i| {
+            let (base_mint, tokens) = (&base_mint, &tokens);
+            async move {
+                let q = quoter.quote(base_mint.clone(), tokens[i].1.clone(), start_amt).await.ok()?;
+                Some((i, q))
+            }
+        })
+        .buffer_unordered(conc)
+        .filter_map(|x| async move { x })
+        .collect()
+        .await;
+
+    let refs: HashMap<usize, u64> = firsts.iter().map(|(i, q)| (*i, q.out_amount)).collect();
+    let mut frontier: Vec<Prefix> = firsts
+        .into_iter()
+        .filter(|(_, q)| q.out_amount > 0)
+        .map(|(i, q)| Prefix {
+            tokens: vec![i],
+            amount: q.out_amount,
+            legs: vec![leg((&base_uc, &base_mint), (&tokens[i].0, &tokens[i].1), &q)],
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+
+    // depth = 前缀已走的跳数；收尾后总跳数为 depth + 1
+    for depth in 1..max_hops {
+        if frontier.is_empty() { break; }
+
+        let mut steps = Vec::new();
+        for p in &frontier {
+            if depth + 1 >= min_hops {
+                steps.push(Step::Close(p.clone()));
+            }
+            if depth + 1 < max_hops {
+                for j in 0..tokens.len() {
+                    if !p.tokens.contains(&j) {
+                        steps.push(Step::Extend(p.clone(), j));
+                    }
+                }
+            }
+        }
+
+        let outcomes: Vec<Outcome> = stream::iter(steps)
+            .map(|step| {
+                let (base_mint, base_uc, tokens, refs) = (&base_mint, &base_uc, &tokens, &refs);
+                async move {
+                    match step {
+                        Step::Close(mut p) => {
+                            let last = &tokens[*p.tokens.last()?];
+                            let q = quoter.quote(last.1.clone(), base_mint.clone(), p.amount).await.ok()?;
+                            let out = q.out_amount;
+
+                            let delta_bps = Bps::from_ratio(out as u128, start_amt as u128);
+                            if delta_bps < cfg.min_profit_bps { return None; }
+
+                            let mut path = vec![base_uc.clone()];
+                            path.extend(p.tokens.iter().map(|&i| tokens[i].0.clone()));
+                            path.push(base_uc.clone());
+
+                            p.legs.push(leg((&last.0, &last.1), (base_uc, base_mint), &q));
+                            mark_legs(&mut p.legs, &p.tokens, refs, start_amt);
+
+                            Some(Outcome::Row(ArbRow {
+                                profitable: delta_bps >= Bps::ZERO,
+                                path: path.join(" → "),
+                                start,
+                                end: Amount::from_u64(out, base_dec),
+                                delta_bps,
+                                legs: p.legs,
+                                optimal_in: None,
+                                expected_profit: None,
+                            }))
+                        }
+                        Step::Extend(mut p, j) => {
+                            let from = &tokens[*p.tokens.last()?];
+                            let q = quoter.quote(from.1.clone(), tokens[j].1.clone(), p.amount).await.ok()?;
+                            let out = q.out_amount;
+                            if out == 0 { return None; }
+
+                            // 按第一层基准把持仓折回 BASE，亏损过大则剪掉
+                            if let (Some(max_loss), Some(&r)) = (cfg.max_prefix_loss_bps, refs.get(&j)) {
+                                let marked = mark(out, start_amt, r);
+                                if -Bps::from_ratio(marked, start_amt as u128) > max_loss { return None; }
+                            }
+
+                            p.legs.push(leg((&from.0, &from.1), (&tokens[j].0, &tokens[j].1), &q));
+                            p.tokens.push(j);
+                            p.amount = out;
+                            Some(Outcome::Grown(p))
+                        }
+                    }
+                }
+            })
+            .buffer_unordered(conc)
+            .filter_map(|x| async move { x })
+            .collect()
+            .await;
+
+        frontier = Vec::new();
+        for o in outcomes {
+            match o {
+                Outcome::Row(r)    => rows.push(r),
+                Outcome::Grown(p)  => frontier.push(p),
+            }
+        }
+    }
+
+    rows
+}
+
+/// 按 row 原来的路径和起始数量重新报价，用于池子状态变化后只重算受影响的环路
+///
+/// leg_bps 的估值基准与 `evaluate_cycles` 相同（中间币种按 BASE → X 的报价折回 BASE）；
+/// 尺寸搜索的结果不再成立，optimal_in / expected_profit 置空
+pub async fn requote_cycle<Q>(quoter: &Q, row: &ArbRow) -> Result<ArbRow>
+where
+    Q: QuoteProvider + ?Sized,
+{
+    let mints = row.mints();
+    if mints.len() < 3 {
+        bail!("not a cycle: {}", row.path);
+    }
+    let start_amt = row.start.to_u64()?;
+
+    let mut legs = Vec::with_capacity(row.legs.len());
+    let mut amount = start_amt;
+    for l in &row.legs {
+        let q = quoter.quote(l.from_mint.clone(), l.to_mint.clone(), amount).await?;
+        amount = q.out_amount;
+        legs.push(leg((&l.from_symbol, &l.from_mint), (&l.to_symbol, &l.to_mint), &q));
+    }
+
+    // 第一个中间币种的基准就是首跳；其余补报 BASE → X，失败的按“不变”处理
+    let tokens: Vec<usize> = (0..legs.len() - 1).collect();
+    let mut refs = HashMap::new();
+    for &k in &tokens {
+        let r = match k {
+            0 => legs[0].amount_out,
+            _ => match quoter.quote(mints[0].clone(), mints[k + 1].clone(), start_amt).await {
+                Ok(q) => q.out_amount,
+                Err(_) => continue,
+            },
+        };
+        refs.insert(k, r);
+    }
+    mark_legs(&mut legs, &tokens, &refs, start_amt);
+
+    let delta_bps = Bps::from_ratio(amount as u128, start_amt as u128);
+    Ok(ArbRow {
+        profitable: delta_bps >= Bps::ZERO,
+        path: row.path.clone(),
+        start: row.start,
+        end: Amount::from_u64(amount, row.start.decimals()),
+        delta_bps,
+        legs,
+        optimal_in: None,
+        expected_profit: None,
+    })
+}
+
+/// 持有 amount 个 X 折合多少 BASE：amount * start / ref(X)
+fn mark(amount: u64, start_amt: u64, reference: u64) -> u128 {
+    amount as u128 * start_amt as u128 / reference.max(1) as u128
+}
+
+/// 按第一层基准把每跳之后的持仓折成 BASE，相邻两次估值之比即该跳的 bps
+fn mark_legs(legs: &mut [ArbLeg], tokens: &[usize], refs: &HashMap<usize, u64>, start_amt: u64) {
+    let mut prev = start_amt as u128;
+    for (k, l) in legs.iter_mut().enumerate() {
+        let marked = match tokens.get(k) {
+            // 中间跳：持有 tokens[k]
+            Some(t) => match refs.get(t) {
+                Some(&r) if r > 0 => mark(l.amount_out, start_amt, r),
+                _ => prev,
+            },
+            // 末跳：已回到 BASE
+            None => l.amount_out as u128,
+        };
+        l.leg_bps = Bps::from_ratio(marked, prev);
+        prev = marked;
+    }
+}
@@ -0,0 +1,149 @@
+use futures::{future, stream, StreamExt};
+use serde::Serialize;
+use crate::ports::quote::QuoteProvider;
+use crate::ports::resolver::MintResolver;
+use super::amount::{Amount, Bps};
+
+/// 跨报价源价差扫描参数
+#[derive(Debug, Clone)]
+pub struct DiscrepancyScan {
+    /// 询价数量（BASE 人类单位），每个数量单独比较
+    pub sizes: Vec<Amount>,
+    /// 价差达到该值才报告
+    pub min_spread: Bps,
+    /// 每对代币两个方向都比较；关闭时只按列表顺序比较一个方向（BASE 在最前）
+    pub reverse: bool,
+    /// 最大并发（按 交易对 × 数量 计）
+    pub concurrency: usize,
+}
+
+/// 同一交易对、同一数量下，各报价源给出的输出不一致
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discrepancy {
+    /// 如 "SOL→USDC"
+    pub pair: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    /// 按报价源顺序；None = 报价失败
+    pub outs: Vec<(String, Option<u64>)>,
+    pub best: String,
+    pub best_out: u64,
+    pub worst: String,
+    pub worst_out: u64,
+    /// 最好相对最差多拿多少
+    pub spread: Bps,
+}
+
+/// 所有报价源同时询价，按报价源顺序返回输出；None = 报价失败
+async fn quote_all<Q>(sources: &[(String, Q)], input_mint: &str, output_mint: &str, amount: u64) -> Vec<(String, Option<u64>)>
+where
+    Q: QuoteProvider + Sync,
+{
+    future::join_all(sources.iter().map(|(name, q)| async move {
+        let out = q.quote(input_mint.to_string(), output_mint.to_string(), amount).await.ok();
+        (name.clone(), out.map(|q| q.out_amount))
+    }))
+    .await
+}
+
+/// 成功的报价不足两个时无从比较
+fn compare(pair: String, input_mint: &str, output_mint: &str, amount: u64, outs: Vec<(String, Option<u64>)>) -> Option<Discrepancy> {
+    let ok = || outs.iter().filter_map(|(n, o)| o.map(|o| (n.clone(), o)));
+    if ok().count() < 2 {
+        return None;
+    }
+    let (best, best_out) = ok().max_by_key(|(_, o)| *o)?;
+    let (worst, worst_out) = ok().min_by_key(|(_, o)| *o)?;
+    Some(Discrepancy {
+        pair,
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        amount,
+        outs,
+        best,
+        best_out,
+        worst,
+        worst_out,
+        spread: Bps::from_ratio(best_out as u128, worst_out as u128),
+    })
+}
+
+/// 对代币列表（BASE + mids）里的每一对，在多个数量下比较各报价源的输出，返回价差达到阈值的（价差大的在前）
+///
+/// 聚合器和单个池子对同一笔交易给出的价格不同，本身就是套利信号。数量以 BASE 计：
+/// 先比较 BASE → X，其输出里最多的那个作为 X 出发时的询价数量，所以各交易对的名义金额一致；
+/// 只有一个报价源给出 BASE → X 时没有价差可报，但它的输出照样用来询价 X 出发的交易对。
+pub async fn find_discrepancies<R, Q>(
+    resolver: &R,
+    sources: &[(String, Q)],
+    base: &str,
+    mids: &[String],
+    cfg: &DiscrepancyScan,
+) -> Vec<Discrepancy>
+where
+    R: MintResolver + Sync,
+    Q: QuoteProvider + Sync,
+{
+    let base_uc = base.to_uppercase();
+    let Ok(base_mint) = resolver.get_mint(&base_uc).map(str::to_string) else { return vec![] };
+    let base_dec = resolver.get_decimals(&base_uc).unwrap_or(9);
+    let sizes: Vec<u64> = cfg
+        .sizes
+        .iter()
+        .filter_map(|a| a.rescale(base_dec).and_then(|a| a.to_u64()).ok())
+        .filter(|a| *a > 0)
+        .collect();
+
+    // (symbol, mint)，BASE 在最前，按 mint 去重
+    let mut tokens: Vec<(String, String)> = vec![(base_uc.clone(), base_mint.clone())];
+    for s in mids {
+        if let Ok(m) = resolver.get_mint(s) {
+            if !tokens.iter().any(|(_, x)| x == m) {
+                tokens.push((s.to_uppercase(), m.to_string()));
+            }
+        }
+    }
+    let tokens = &tokens;
+    // (最好的成功输出, 价差)
+    let pair = |i: usize, j: usize, amount: u64| async move {
+        let ((a, am), (b, bm)) = (&tokens[i], &tokens[j]);
+        let outs = quote_all(sources, am, bm, amount).await;
+        let best = outs.iter().filter_map(|(_, o)| *o).max();
+        (best, compare(format!("{}→{}", a, b), am, bm, amount, outs))
+    };
+
+    // 1) BASE → X：既是结果，也给出 X 出发时的询价数量
+    let jobs: Vec<(usize, u64)> = (1..tokens.len()).flat_map(|i| sizes.iter().map(move |&s| (i, s))).collect();
+    let fwd: Vec<(usize, Option<u64>, Option<Discrepancy>)> = stream::iter(jobs)
+        .map(|(j, size)| async move {
+            let (best, d) = pair(0, j, size).await;
+            (j, best, d)
+        })
+        .buffer_unordered(cfg.concurrency.max(1))
+        .collect()
+        .await;
+
+    // 2) 其余交易对 X → Y，按对应数量询价
+    let jobs: Vec<(usize, usize, u64)> = fwd
+        .iter()
+        .filter_map(|(i, best, _)| best.filter(|b| *b > 0).map(|b| (*i, b)))
+        .flat_map(|(i, amount)| {
+            (0..tokens.len()).filter(move |&j| j != i && (cfg.reverse || j > i)).map(move |j| (i, j, amount))
+        })
+        .collect();
+    let rest: Vec<Option<Discrepancy>> = stream::iter(jobs)
+        .map(|(i, j, amount)| async move { pair(i, j, amount).await.1 })
+        .buffer_unordered(cfg.concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut found: Vec<Discrepancy> = fwd
+        .into_iter()
+        .filter_map(|(_, _, d)| d)
+        .chain(rest.into_iter().flatten())
+        .filter(|d| d.spread >= cfg.min_spread)
+        .collect();
+    found.sort_by(|a, b| b.spread.cmp(&a.spread).then_with(|| a.pair.cmp(&b.pair)).then(a.amount.cmp(&b.amount)));
+    found
+}
@@ -0,0 +1,38 @@
+use super::amount::{Amount, Bps};
+use super::types::ArbRow;
+
+/// 线性费用模型：每跳手续费 + 每跳假设滑点（bps）+ 每笔交易固定费用
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FeeModel {
+    pub fee_bps_per_hop: u16,
+    pub slippage_bps: u16,
+    /// 每笔交易的链上费用（基础费 + 优先费，base 最小单位）
+    pub tx_cost: u64,
+}
+
+impl FeeModel {
+    pub fn per_hop(&self) -> Bps {
+        Bps::from_int(self.fee_bps_per_hop as i64 + self.slippage_bps as i64)
+    }
+
+    /// hops 跳的总成本
+    pub fn cost(&self, hops: usize) -> Bps {
+        Bps::from_micro(self.per_hop().micro() * hops as i128)
+    }
+
+    /// 交易费折合起始数量的 bps
+    pub fn tx_cost_bps(&self, start: Amount) -> Bps {
+        if self.tx_cost == 0 { return Bps::ZERO; }
+        Bps::from_ratio(start.raw() + self.tx_cost as u128, start.raw())
+    }
+
+    /// 净收益 = 毛收益 - 跳数 × (手续费 + 滑点) - 交易费
+    pub fn net_bps(&self, row: &ArbRow) -> Bps {
+        row.delta_bps - self.cost(row.hops()) - self.tx_cost_bps(row.start)
+    }
+
+    /// 按净收益作用于起始数量得到的净终值
+    pub fn end_net(&self, row: &ArbRow) -> Amount {
+        row.start.apply_bps(self.net_bps(row))
+    }
+}
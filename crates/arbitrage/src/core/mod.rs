@@ -1,3 +1,8 @@
+pub mod amount;
 pub mod types;
+pub mod fees;
+pub mod cycle;
+pub mod sizing;
 pub mod eval_2hop;
 pub mod eval_3hop;
+pub mod discrepancy;
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use anyhow::{Result, bail};
+use futures::{stream, StreamExt};
+use crate::ports::quote::QuoteProvider;
+use super::amount::Bps;
+use super::types::ArbRow;
+
+/// 黄金分割比的倒数（≈ 0.618）
+const INV_PHI: f64 = 0.618_033_988_749_895;
+
+/// 下单量搜索参数（金额均为 base 最小单位）
+#[derive(Debug, Clone)]
+pub struct SizeSearch {
+    pub min_in: u64,
+    pub max_in: u64,
+    /// 最多报价几次（每次 = 整条路径报一遍）
+    pub probes: usize,
+    /// 按比例扣除的成本（bps，手续费+滑点模型）
+    pub cost_bps: Bps,
+    /// 每笔固定成本（如交易费）
+    pub fixed_cost: u64,
+}
+
+/// 一次试探：投入 amount_in，拿回 amount_out，净利润 profit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeProbe {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub profit: i128,
+}
+
+#[derive(Debug, Clone)]
+pub struct SizeResult {
+    /// 净利润最高的一次试探
+    pub best: SizeProbe,
+    /// 所有成功的试探，按 amount_in 升序（利润曲线）
+    pub curve: Vec<SizeProbe>,
+}
+
+impl SizeSearch {
+    fn net_profit(&self, amount_in: u64, amount_out: u64) -> i128 {
+        let cost = self.cost_bps.of_raw(amount_in as u128) + self.fixed_cost as i128;
+        amount_out as i128 - amount_in as i128 - cost
+    }
+}
+
+/// 沿 mints 逐跳报价，返回最终拿到的数量
+pub async fn quote_path<Q>(quoter: &Q, mints: &[String], amount: u64) -> Result<u64>
+where
+    Q: QuoteProvider + ?Sized,
+{
+    if mints.len() < 2 { bail!("path needs at least 2 mints"); }
+    let mut cur = amount;
+    for w in mints.windows(2) {
+        cur = quoter.quote(w[0].clone(), w[1].clone(), cur).await?.out_amount;
+    }
+    Ok(cur)
+}
+
+/// 在 [min_in, max_in] 内对环路做黄金分割搜索，找净利润（base 单位）最高的下单量
+///
+/// 假设利润曲线单峰（小额被固定成本吃掉、大额被价格冲击吃掉）；
+/// 报价失败的点视为极差，搜索区间会自动远离它。全部失败时返回 None。
+pub async fn optimize_size<Q>(quoter: &Q, mints: &[String], cfg: &SizeSearch) -> Option<SizeResult>
+where
+    Q: QuoteProvider + ?Sized,
+{
+    let (lo, hi) = (cfg.min_in.min(cfg.max_in), cfg.min_in.max(cfg.max_in));
+    let budget = cfg.probes.max(1);
+
+    // amount_in -> Some(probe)/None(失败)，避免重复报价
+    let mut seen: BTreeMap<u64, Option<SizeProbe>> = BTreeMap::new();
+
+    let mut a = lo as f64;
+    let mut b = hi as f64;
+    let mut c = b - (b - a) * INV_PHI;
+    let mut d = a + (b - a) * INV_PHI;
+
+    // 先探两端，再进入黄金分割
+    for x in [lo, hi] {
+        if seen.len() >= budget { break; }
+        probe(quoter, mints, cfg, x, &mut seen).await;
+    }
+    let mut fc = score(probe_once(quoter, mints, cfg, c, budget, &mut seen).await);
+    let mut fd = score(probe_once(quoter, mints, cfg, d, budget, &mut seen).await);
+
+    while seen.len() < budget && (b - a) >= 2.0 {
+        if fc >= fd {
+            b = d; d = c; fd = fc;
+            c = b - (b - a) * INV_PHI;
+            fc = score(probe_once(quoter, mints, cfg, c, budget, &mut seen).await);
+        } else {
+            a = c; c = d; fc = fd;
+            d = a + (b - a) * INV_PHI;
+            fd = score(probe_once(quoter, mints, cfg, d, budget, &mut seen).await);
+        }
+    }
+
+    let curve: Vec<SizeProbe> = seen.into_values().flatten().collect();
+    let best = *curve.iter().max_by_key(|p| p.profit)?;
+    Some(SizeResult { best, curve })
+}
+
+/// 对每条环路做尺寸搜索，把最优下单量/预期利润写回 ArbRow；返回值与 rows 一一对应（含利润曲线）
+pub async fn optimize_rows<Q>(
+    quoter: &Q,
+    rows: &mut [ArbRow],
+    cfg: &SizeSearch,
+    concurrency: usize,
+) -> Vec<Option<SizeResult>>
+where
+    Q: QuoteProvider + Sync,
+{
+    let mut results: Vec<(usize, Option<SizeResult>)> = stream::iter(0..rows.len())
+        .map(|i| {
+            let mints = rows[i].mints();
+            async move { (i, optimize_size(quoter, &mints, cfg).await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    results.sort_by_key(|(i, _)| *i);
+
+    results
+        .into_iter()
+        .map(|(i, res)| {
+            if let Some(r) = &res {
+                rows[i].optimal_in = Some(r.best.amount_in);
+                rows[i].expected_profit = Some(r.best.profit);
+            }
+            res
+        })
+        .collect()
+}
+
+/* ---------------- 内部工具 ---------------- */
+
+fn score(p: Option<SizeProbe>) -> i128 {
+    p.map(|p| p.profit).unwrap_or(i128::MIN)
+}
+
+async fn probe_once<Q>(
+    quoter: &Q,
+    mints: &[String],
+    cfg: &SizeSearch,
+    x: f64,
+    budget: usize,
+    seen: &mut BTreeMap<u64, Option<SizeProbe>>,
+) -> Option<SizeProbe>
+where
+    Q: QuoteProvider + ?Sized,
+{
+    let amount = x.round().max(1.0) as u64;
+    if let Some(p) = seen.get(&amount) { return *p; }
+    if seen.len() >= budget { return None; }
+    probe(quoter, mints, cfg, amount, seen).await
+}
+
+async fn probe<Q>(
+    quoter: &Q,
+    mints: &[String],
+    cfg: &SizeSearch,
+    amount_in: u64,
+    seen: &mut BTreeMap<u64, Option<SizeProbe>>,
+) -> Option<SizeProbe>
+where
+    Q: QuoteProvider + ?Sized,
+{
+    let p = quote_path(quoter, mints, amount_in).await.ok().map(|amount_out| SizeProbe {
+        amount_in,
+        amount_out,
+        profit: cfg.net_profit(amount_in, amount_out),
+    });
+    seen.insert(amount_in, p);
+    p
+}
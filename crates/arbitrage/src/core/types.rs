@@ -1,15 +1,50 @@
 use serde::Serialize;
+use super::amount::{Amount, Bps};
+
+/// 环路中的一跳
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ArbLeg {
+    pub from_symbol: String,
+    pub to_symbol: String,
+    pub from_mint: String,
+    pub to_mint: String,
+    /// 最小单位
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// 这一跳对持仓 base 估值的影响，估值基准见 `evaluate_cycles`
+    pub leg_bps: Bps,
+    pub amm_label: String,
+    pub price_impact_pct: f64,
+}
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ArbRow {
     pub profitable: bool,
     pub path: String,
-    pub start: f64,
-    pub end: f64,
-    pub delta_bps: f64,
+    /// 起始 / 回到手的 base 数量（base 精度）
+    pub start: Amount,
+    pub end: Amount,
+    /// 毛收益 = end / start - 1
+    pub delta_bps: Bps,
+    /// 逐跳明细（首跳从 base 出发，末跳回到 base）
+    pub legs: Vec<ArbLeg>,
+    /// 最优下单量（base 最小单位），未做尺寸搜索时为 None
+    pub optimal_in: Option<u64>,
+    /// 最优下单量下的预期净利润（base 最小单位，可为负）
+    pub expected_profit: Option<i128>,
 }
 
-#[inline]
-pub fn amount_from_ui(decimals: u8, ui: f64) -> u64 {
-    (ui * 10f64.powi(decimals as i32)).round() as u64
+impl ArbRow {
+    pub fn hops(&self) -> usize {
+        self.legs.len()
+    }
+
+    /// 环路经过的 mint（首尾都是 base），用于按路径重新报价
+    pub fn mints(&self) -> Vec<String> {
+        let mut v: Vec<String> = self.legs.iter().map(|l| l.from_mint.clone()).collect();
+        if let Some(last) = self.legs.last() {
+            v.push(last.to_mint.clone());
+        }
+        v
+    }
 }
@@ -1,4 +1,8 @@
 pub mod ports;
 pub mod core;
 pub mod middleware;
+pub mod providers;
+pub mod backtest;
 pub mod prelude;
+
+pub use prelude::*;
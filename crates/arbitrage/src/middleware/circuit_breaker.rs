@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use anyhow::Result;
+use serde::Serialize;
+use crate::ports::clock::{Clock, SystemClock};
+use crate::ports::quote::{NoRoute, Quote, QuoteProvider};
+
+/// 熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BreakerState {
+    /// 正常放行，统计失败率
+    Closed,
+    /// 快速失败，冷却结束后转半开
+    Open,
+    /// 只放行少量探测请求：全部成功则关闭，任一失败则重新打开
+    HalfOpen,
+}
+
+#[derive(Debug, Clone)]
+pub struct BreakerConfig {
+    /// 统计最近多少次调用
+    pub window: usize,
+    /// 窗口内至少这么多次调用才判断失败率
+    pub min_calls: usize,
+    /// 失败率达到该百分比即打开
+    pub max_failure_pct: u32,
+    /// 打开后冷却多久转半开
+    pub cooldown_ms: u64,
+    /// 半开时放行的探测请求数（同时也是关闭所需的连续成功数）
+    pub half_open_probes: u32,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self { window: 20, min_calls: 10, max_failure_pct: 50, cooldown_ms: 30_000, half_open_probes: 1 }
+    }
+}
+
+/// 熔断打开时的快速失败错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen;
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "provider unavailable (circuit open)")
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+struct Inner {
+    state: BreakerState,
+    /// 最近的调用结果，true = 失败
+    window: VecDeque<bool>,
+    opened_at: u64,
+    /// 半开：已放出去的探测数 / 已成功的探测数
+    probes_sent: u32,
+    probes_ok: u32,
+}
+
+/// 熔断包装器：失败率过高时打开，冷却期内直接返回 `CircuitOpen`，不再打到内层
+pub struct CircuitBreaker<Q, C = SystemClock> {
+    inner: Q,
+    clock: C,
+    cfg: BreakerConfig,
+    st: Mutex<Inner>,
+    rejected: AtomicU64,
+}
+
+impl<Q: QuoteProvider> CircuitBreaker<Q> {
+    pub fn new(inner: Q, cfg: BreakerConfig) -> Self {
+        Self::with_clock(inner, cfg, SystemClock)
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> CircuitBreaker<Q, C> {
+    pub fn with_clock(inner: Q, cfg: BreakerConfig, clock: C) -> Self {
+        let st = Inner { state: BreakerState::Closed, window: VecDeque::new(), opened_at: 0, probes_sent: 0, probes_ok: 0 };
+        Self { inner, clock, cfg, st: Mutex::new(st), rejected: AtomicU64::new(0) }
+    }
+
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    /// 当前状态（冷却结束的 Open 报告为 HalfOpen）
+    pub fn state(&self) -> BreakerState {
+        let mut st = self.st.lock().unwrap();
+        self.tick(&mut st);
+        st.state
+    }
+
+    /// 打开期间被快速拒绝的请求数
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    fn tick(&self, st: &mut Inner) {
+        if st.state == BreakerState::Open && self.clock.now_ms().saturating_sub(st.opened_at) >= self.cfg.cooldown_ms {
+            st.state = BreakerState::HalfOpen;
+            st.probes_sent = 0;
+            st.probes_ok = 0;
+        }
+    }
+
+    fn open(&self, st: &mut Inner) {
+        st.state = BreakerState::Open;
+        st.opened_at = self.clock.now_ms();
+        st.window.clear();
+    }
+
+    /// 能否放行；半开时占用一个探测名额
+    fn admit(&self) -> bool {
+        let mut st = self.st.lock().unwrap();
+        self.tick(&mut st);
+        match st.state {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen if st.probes_sent < self.cfg.half_open_probes.max(1) => {
+                st.probes_sent += 1;
+                true
+            }
+            BreakerState::HalfOpen => false,
+        }
+    }
+
+    fn record(&self, failed: bool) {
+        let mut st = self.st.lock().unwrap();
+        match st.state {
+            BreakerState::Closed => {
+                st.window.push_back(failed);
+                while st.window.len() > self.cfg.window.max(1) {
+                    st.window.pop_front();
+                }
+                let n = st.window.len();
+                let fails = st.window.iter().filter(|f| **f).count();
+                if n >= self.cfg.min_calls.max(1) && fails * 100 >= self.cfg.max_failure_pct as usize * n {
+                    self.open(&mut st);
+                }
+            }
+            BreakerState::HalfOpen if failed => self.open(&mut st),
+            BreakerState::HalfOpen => {
+                st.probes_ok += 1;
+                if st.probes_ok >= self.cfg.half_open_probes.max(1) {
+                    st.state = BreakerState::Closed;
+                    st.window.clear();
+                }
+            }
+            // 打开后才返回的旧请求不计入
+            BreakerState::Open => {}
+        }
+    }
+
+    /// 探测请求中途被取消（future 被 drop）时归还名额
+    fn release_probe(&self) {
+        let mut st = self.st.lock().unwrap();
+        if st.state == BreakerState::HalfOpen {
+            st.probes_sent = st.probes_sent.saturating_sub(1);
+        }
+    }
+}
+
+/// 调用未完成就被 drop 时归还半开名额
+struct Pending<'a, Q: QuoteProvider, C: Clock> {
+    cb: &'a CircuitBreaker<Q, C>,
+    done: bool,
+}
+
+impl<Q: QuoteProvider, C: Clock> Drop for Pending<'_, Q, C> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.cb.release_probe();
+        }
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> QuoteProvider for CircuitBreaker<Q, C> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        if !self.admit() {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(CircuitOpen.into());
+        }
+        let mut pending = Pending { cb: self, done: false };
+        let res = self.inner.quote(input_mint, output_mint, amount).await;
+        pending.done = true;
+        // 无路由说明报价源是好的，只是这个交易对/数量报不出价
+        self.record(res.as_ref().is_err_and(|e| e.downcast_ref::<NoRoute>().is_none()));
+        res
+    }
+
+    fn available(&self) -> bool {
+        self.state() != BreakerState::Open && self.inner.available()
+    }
+}
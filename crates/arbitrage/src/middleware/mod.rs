@@ -1 +1,5 @@
+pub mod rate_limit;
 pub mod throttle_retry;
+pub mod quote_cache;
+pub mod circuit_breaker;
+pub mod recorder;
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use tokio::sync::Mutex as AsyncMutex;
+use crate::ports::clock::{Clock, SystemClock};
+use crate::ports::quote::{Quote, QuoteProvider};
+
+/// (input_mint, output_mint, amount)
+type Key = (String, String, u64);
+
+/// 每个 key 一个槽位：槽位的异步锁即 in-flight 标记，持锁的请求负责回源
+type Slot = Arc<AsyncMutex<Option<(Quote, u64)>>>;
+
+/// 命中统计快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// 直接命中未过期的缓存
+    pub hits: u64,
+    /// 回源次数
+    pub misses: u64,
+    /// 等待同 key 的 in-flight 请求后拿到结果（不回源）
+    pub coalesced: u64,
+}
+
+/// 报价缓存：按 (input, output, amount) 缓存 ttl_ms 毫秒，
+/// 并发的相同请求只回源一次（single-flight）；失败不缓存。
+pub struct QuoteCache<Q, C = SystemClock> {
+    inner: Q,
+    clock: C,
+    ttl_ms: u64,
+    /// 超过该数量时顺手清掉过期项
+    max_entries: usize,
+    slots: Mutex<HashMap<Key, Slot>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl<Q: QuoteProvider> QuoteCache<Q> {
+    pub fn new(inner: Q, ttl_ms: u64) -> Self {
+        Self::with_clock(inner, ttl_ms, SystemClock)
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> QuoteCache<Q, C> {
+    pub fn with_clock(inner: Q, ttl_ms: u64, clock: C) -> Self {
+        Self {
+            inner,
+            clock,
+            ttl_ms,
+            max_entries: 10_000,
+            slots: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+        }
+    }
+
+    pub fn max_entries(mut self, n: usize) -> Self {
+        self.max_entries = n.max(1);
+        self
+    }
+
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 当前缓存的 key 数（含已过期未清理的）
+    pub fn len(&self) -> usize {
+        self.slots.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.slots.lock().unwrap().clear();
+    }
+
+    fn fresh(&self, at_ms: u64, now: u64) -> bool {
+        now.saturating_sub(at_ms) < self.ttl_ms
+    }
+
+    fn slot(&self, key: Key) -> Slot {
+        let mut slots = self.slots.lock().unwrap();
+        if slots.len() >= self.max_entries && !slots.contains_key(&key) {
+            let now = self.clock.now_ms();
+            // in-flight（锁被占用）的槽位保留
+            slots.retain(|_, s| match s.try_lock() {
+                Ok(v) => v.as_ref().is_some_and(|(_, at)| self.fresh(*at, now)),
+                Err(_) => true,
+            });
+        }
+        slots.entry(key).or_default().clone()
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> QuoteProvider for QuoteCache<Q, C> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        let slot = self.slot((input_mint.clone(), output_mint.clone(), amount));
+
+        let (mut cached, waited) = match slot.try_lock() {
+            Ok(g) => (g, false),
+            Err(_) => (slot.lock().await, true),
+        };
+
+        if let Some((q, at)) = cached.as_ref() {
+            if self.fresh(*at, self.clock.now_ms()) {
+                let counter = if waited { &self.coalesced } else { &self.hits };
+                counter.fetch_add(1, Ordering::Relaxed);
+                return Ok(q.clone());
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let q = self.inner.quote(input_mint, output_mint, amount).await?;
+        *cached = Some((q.clone(), self.clock.now_ms()));
+        Ok(q)
+    }
+
+    fn available(&self) -> bool {
+        self.inner.available()
+    }
+}
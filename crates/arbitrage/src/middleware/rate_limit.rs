@@ -0,0 +1,135 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+/* ===================== 令牌桶 ===================== */
+
+/// 浮点补充的误差：差这么一点也算攒够一个令牌
+const TOKEN_EPS: f64 = 1e-9;
+/// 没拿到令牌时至少等这么久，避免等待时长被舍入成 0 后空转
+const MIN_WAIT: Duration = Duration::from_millis(1);
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+    /// 收到 429 / Retry-After 后，在此之前所有请求都暂停
+    paused_until: Option<Instant>,
+}
+
+/// 令牌桶限速器：clone 出来的句柄共享同一个桶，所有并发任务合计不超过 qps
+#[derive(Clone)]
+pub struct RateLimiter {
+    qps: f64,
+    burst: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// qps：每秒补充的令牌数；burst：桶容量（允许的瞬时并发），初始是满的
+    pub fn new(qps: u32, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            qps: qps.max(1) as f64,
+            burst,
+            bucket: Arc::new(Mutex::new(Bucket { tokens: burst, last: Instant::now(), paused_until: None })),
+        }
+    }
+
+    pub fn qps(&self) -> u32 {
+        self.qps as u32
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst as u32
+    }
+
+    /// 拿到一个令牌才返回
+    pub async fn acquire(&self) {
+        while let Some(at) = self.try_acquire() {
+            sleep_until(at).await;
+        }
+    }
+
+    /// 拿到令牌返回 None，否则返回下一次可以再试的时间点
+    fn try_acquire(&self) -> Option<Instant> {
+        let now = Instant::now();
+        let mut b = self.bucket.lock().unwrap();
+
+        if let Some(until) = b.paused_until {
+            if until > now { return Some(until); }
+            b.paused_until = None;
+            b.last = now;
+        }
+
+        let elapsed = now.saturating_duration_since(b.last).as_secs_f64();
+        b.tokens = (b.tokens + elapsed * self.qps).min(self.burst);
+        b.last = now;
+
+        if b.tokens >= 1.0 - TOKEN_EPS {
+            b.tokens = (b.tokens - 1.0).max(0.0);
+            None
+        } else {
+            Some(now + Duration::from_secs_f64((1.0 - b.tokens) / self.qps).max(MIN_WAIT))
+        }
+    }
+
+    /// 服务端限流（429）：清空令牌并暂停 d，对共享该桶的所有任务生效
+    pub fn pause(&self, d: Duration) {
+        let until = Instant::now() + d;
+        let mut b = self.bucket.lock().unwrap();
+        b.tokens = 0.0;
+        b.paused_until = Some(b.paused_until.map_or(until, |u| u.max(until)));
+    }
+}
+
+/* ===================== 退避 ===================== */
+
+/// 带抖动的指数退避（full jitter）：第 n 次重试等待 [0, min(max, base·2ⁿ)] 的随机时长
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub base_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { base_ms: 100, max_ms: 2_000 }
+    }
+}
+
+impl Backoff {
+    /// attempt 从 0 开始
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let cap = self.base_ms.saturating_mul(1u64 << attempt.min(20)).min(self.max_ms);
+        Duration::from_millis(fastrand::u64(0..=cap))
+    }
+}
+
+/* ===================== 限流错误 ===================== */
+
+/// 报价源被限流（HTTP 429）；报价器返回它（包在 anyhow 里），限速中间件据此暂停整个桶
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    /// 服务端给的 Retry-After
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimited {
+    /// 解析 Retry-After 头（只支持秒数形式）
+    pub fn from_header(v: Option<&str>) -> Self {
+        let retry_after = v.and_then(|s| s.trim().parse::<u64>().ok()).map(Duration::from_secs);
+        Self { retry_after }
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.retry_after {
+            Some(d) => write!(f, "rate limited (retry after {}s)", d.as_secs()),
+            None => write!(f, "rate limited"),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
@@ -0,0 +1,120 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use crate::ports::clock::{Clock, SystemClock};
+use crate::ports::quote::{Quote, QuoteProvider};
+
+/// 录制文件（NDJSON）里的一行：一次报价请求及其结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteRecord {
+    /// 请求发出时间（unix 毫秒）
+    pub ts_ms: u64,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    /// 成功时的 out_amount
+    #[serde(default)]
+    pub out_amount: Option<u64>,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 完整报价（含路由），回放时原样返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<Quote>,
+}
+
+/// 读取整个录制文件（跳过空行）
+pub fn read_records(path: impl AsRef<Path>) -> Result<Vec<QuoteRecord>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("open record file {}", path.display()))?;
+    let mut recs = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() { continue; }
+        let rec: QuoteRecord = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: bad record", path.display(), i + 1))?;
+        recs.push(rec);
+    }
+    Ok(recs)
+}
+
+/// 录制包装器：把每次请求/响应追加写入 NDJSON 文件（每行即时写出，中途崩溃也不丢已写的行）
+///
+/// 写入走 tokio 异步文件，失败不影响报价本身，只计入 `write_errors`。
+pub struct Recorder<Q, C = SystemClock> {
+    inner: Q,
+    clock: C,
+    out: Mutex<tokio::fs::File>,
+    write_errors: AtomicU64,
+}
+
+impl<Q: QuoteProvider> Recorder<Q> {
+    /// 以追加方式打开（不存在则创建）
+    pub fn create(inner: Q, path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_clock(inner, path, SystemClock)
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> Recorder<Q, C> {
+    pub fn with_clock(inner: Q, path: impl AsRef<Path>, clock: C) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            inner,
+            clock,
+            out: Mutex::new(tokio::fs::File::from_std(file)),
+            write_errors: AtomicU64::new(0),
+        })
+    }
+
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    /// 写入失败的记录条数
+    pub fn write_errors(&self) -> u64 {
+        self.write_errors.load(Ordering::Relaxed)
+    }
+
+    async fn write(&self, rec: &QuoteRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(rec)?;
+        line.push(b'\n');
+        let mut out = self.out.lock().await;
+        out.write_all(&line).await?;
+        out.flush().await?;
+        Ok(())
+    }
+}
+
+impl<Q: QuoteProvider, C: Clock> QuoteProvider for Recorder<Q, C> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        let ts_ms = self.clock.now_ms();
+        let t0 = Instant::now();
+        let res = self.inner.quote(input_mint.clone(), output_mint.clone(), amount).await;
+
+        let rec = QuoteRecord {
+            ts_ms,
+            input_mint,
+            output_mint,
+            amount,
+            out_amount: res.as_ref().ok().map(|q| q.out_amount),
+            latency_ms: t0.elapsed().as_millis() as u64,
+            error: res.as_ref().err().map(|e| format!("{:#}", e)),
+            quote: res.as_ref().ok().cloned(),
+        };
+        // 录制失败不影响报价本身
+        if self.write(&rec).await.is_err() {
+            self.write_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        res
+    }
+
+    fn available(&self) -> bool {
+        self.inner.available()
+    }
+}
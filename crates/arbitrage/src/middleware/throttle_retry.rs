@@ -1,40 +1,58 @@
-use std::time::Duration;
 use tokio::time::sleep;
 use anyhow::Result;
-use crate::ports::quote::QuoteProvider;
+use crate::ports::quote::{NoRoute, Quote, QuoteProvider};
+use super::rate_limit::{Backoff, RateLimited, RateLimiter};
 
-pub struct ThrottleRetry {
-    pub qps: u32,
-    pub retries: u32,
-}
-
-pub struct Wrapped<'a, Q: QuoteProvider + ?Sized> {
-    inner: &'a Q,
-    interval_ms: u64,
+/// 限速 + 重试：每次请求（含重试）都从共享令牌桶取令牌；
+/// 遇到 `RateLimited` 暂停整个桶（有 Retry-After 按它，否则按退避时长），`NoRoute` 重试也没用、直接返回，
+/// 其余错误按抖动退避重试。
+pub struct ThrottleRetry<Q> {
+    inner: Q,
+    limiter: RateLimiter,
     retries: u32,
+    backoff: Backoff,
 }
 
-impl ThrottleRetry {
-    pub fn wrap<'a, Q: QuoteProvider + ?Sized>(&'a self, inner: &'a Q) -> Wrapped<'a, Q> {
-        let interval_ms = (1000f64 / self.qps.max(1) as f64) as u64;
-        Wrapped { inner, interval_ms, retries: self.retries }
+impl<Q: QuoteProvider> ThrottleRetry<Q> {
+    pub fn new(inner: Q, limiter: RateLimiter, retries: u32) -> Self {
+        Self { inner, limiter, retries, backoff: Backoff::default() }
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
     }
 }
 
-impl<'a, Q: QuoteProvider + ?Sized + Sync> QuoteProvider for Wrapped<'a, Q> {
-    async fn quote(&self, im: String, om: String, amount: u64) -> Result<u64> {
+impl<Q: QuoteProvider> QuoteProvider for ThrottleRetry<Q> {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
         let mut attempt = 0u32;
-        let mut backoff = 100u64;
         loop {
-            match self.inner.quote(im.clone(), om.clone(), amount).await {
-                Ok(x) => { sleep(Duration::from_millis(self.interval_ms)).await; return Ok(x); }
-                Err(e) => {
-                    attempt += 1;
-                    if attempt > self.retries { return Err(e); }
-                    sleep(Duration::from_millis(backoff)).await;
-                    backoff = (backoff * 2).min(1200);
-                }
+            self.limiter.acquire().await;
+            let e = match self.inner.quote(im.clone(), om.clone(), amount).await {
+                Ok(q) => return Ok(q),
+                Err(e) => e,
+            };
+            if attempt >= self.retries || e.downcast_ref::<NoRoute>().is_some() { return Err(e); }
+
+            let delay = self.backoff.delay(attempt);
+            match e.downcast_ref::<RateLimited>() {
+                Some(rl) => self.limiter.pause(rl.retry_after.unwrap_or(delay)),
+                None => sleep(delay).await,
             }
+            attempt += 1;
         }
     }
+
+    fn available(&self) -> bool {
+        self.inner.available()
+    }
 }
@@ -1,2 +1,3 @@
 pub mod quote;
 pub mod resolver;
+pub mod clock;
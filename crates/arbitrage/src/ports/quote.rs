@@ -1,6 +1,89 @@
 #![allow(async_fn_in_trait)]
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::clock::now_ms;
+
+/// 路由里的一段（对应 Jupiter routePlan 的一项）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteLeg {
+    pub amm_label: String,
+    pub amm_key: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+    pub fee_mint: String,
+    /// 该段占整笔的百分比（拆单时 < 100）
+    pub percent: u8,
+}
+
+/// 一次报价的完整结果（金额均为最小单位）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// 扣除滑点后的最少到手量（Jupiter otherAmountThreshold）
+    pub min_out_amount: u64,
+    /// 价格冲击（百分比，0.12 = 0.12%）
+    pub price_impact_pct: f64,
+    pub route: Vec<RouteLeg>,
+    /// 报价所基于的 slot（拿不到就 None）
+    pub slot: Option<u64>,
+    /// 拿到报价的 unix 毫秒时间
+    pub fetched_at_ms: u64,
+}
+
+impl Quote {
+    /// 只有进出数量的报价（mock / 本地计算用）
+    pub fn simple(input_mint: String, output_mint: String, in_amount: u64, out_amount: u64) -> Self {
+        Self {
+            input_mint,
+            output_mint,
+            in_amount,
+            out_amount,
+            min_out_amount: out_amount,
+            fetched_at_ms: now_ms(),
+            ..Default::default()
+        }
+    }
+
+    /// 路由标签，多段用 “+” 连接
+    pub fn label(&self) -> String {
+        self.route.iter().map(|l| l.amm_label.as_str()).collect::<Vec<_>>().join("+")
+    }
+}
+
+/// 这笔报价没有可用路由（无路由、代币不可交易、数量太小等）：是请求本身的问题，不算报价源故障
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoRoute(pub String);
+
+impl std::fmt::Display for NoRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no route: {}", self.0)
+    }
+}
+
+impl std::error::Error for NoRoute {}
 
 pub trait QuoteProvider: Send + Sync {
-    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<u64>;
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote>;
+
+    /// 报价源当前是否可用（如熔断打开时为 false）；包装器应转发给内层
+    fn available(&self) -> bool {
+        true
+    }
+}
+
+/// 借用也能当报价器用，方便把 `&quoter` 塞进各类包装器
+impl<Q: QuoteProvider + ?Sized> QuoteProvider for &Q {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        (**self).quote(input_mint, output_mint, amount).await
+    }
+
+    fn available(&self) -> bool {
+        (**self).available()
+    }
 }
@@ -1,6 +1,20 @@
-pub use crate::ports::quote::QuoteProvider;
+pub use crate::ports::quote::{NoRoute, Quote, QuoteProvider, RouteLeg};
 pub use crate::ports::resolver::MintResolver;
-pub use crate::core::types::{ArbRow, amount_from_ui};
+pub use crate::ports::clock::{Clock, ManualClock, SystemClock};
+pub use crate::core::amount::{Amount, Bps};
+pub use crate::core::types::{ArbLeg, ArbRow};
+pub use crate::core::fees::FeeModel;
+pub use crate::core::cycle::{evaluate_cycles, requote_cycle, CycleSearch, MAX_HOPS};
+pub use crate::core::sizing::{optimize_size, optimize_rows, quote_path, SizeSearch, SizeProbe, SizeResult};
 pub use crate::core::eval_2hop::evaluate_2hop;
 pub use crate::core::eval_3hop::evaluate_3hop;
+pub use crate::core::discrepancy::{find_discrepancies, Discrepancy, DiscrepancyScan};
+pub use crate::middleware::rate_limit::{Backoff, RateLimited, RateLimiter};
 pub use crate::middleware::throttle_retry::ThrottleRetry;
+pub use crate::middleware::quote_cache::{CacheStats, QuoteCache};
+pub use crate::middleware::recorder::{read_records, QuoteRecord, Recorder};
+pub use crate::middleware::circuit_breaker::{BreakerConfig, BreakerState, CircuitBreaker, CircuitOpen};
+pub use crate::providers::replay::{ReplayMode, ReplayQuoter};
+pub use crate::providers::best_quote::{BestQuote, Comparison, Outcome, SourceStats};
+pub use crate::backtest::snapshot::{split_snapshots, Snapshot};
+pub use crate::backtest::engine::{run_backtest, BacktestConfig, BacktestReport, Trade};
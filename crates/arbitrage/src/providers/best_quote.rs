@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
+use crate::core::amount::Bps;
+use crate::ports::quote::{NoRoute, Quote, QuoteProvider};
+
+/// 单个报价源这一次的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Outcome {
+    Quoted(u64),
+    Failed(String),
+    /// 截止时间前没有返回
+    TimedOut,
+    /// 报价源不可用（如熔断打开），没有请求
+    Skipped,
+}
+
+/// 一次扇出报价的比较结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    /// 胜出的报价源（输出最多）
+    pub winner: String,
+    /// 按报价源顺序
+    pub outcomes: Vec<(String, Outcome)>,
+    /// 最好与最差的成功报价之差（相对最差）；不足两个成功报价时为 0
+    pub spread: Bps,
+}
+
+/// 单个报价源的累计统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SourceStats {
+    pub name: String,
+    pub wins: u64,
+    pub quoted: u64,
+    pub failed: u64,
+    pub timed_out: u64,
+    pub skipped: u64,
+    /// 成功时相对胜出报价少拿的累计 bps（除以 quoted 即平均差距）
+    pub shortfall: Bps,
+}
+
+impl SourceStats {
+    /// 平均比胜出报价差多少
+    pub fn avg_shortfall(&self) -> Bps {
+        if self.quoted == 0 {
+            return Bps::ZERO;
+        }
+        Bps::from_micro(self.shortfall.micro() / self.quoted as i128)
+    }
+}
+
+/// 并发向多个报价源要价，截止时间内取输出最多的一个
+///
+/// 截止前全部返回则立即返回；到点后只在已返回的报价里选，一个都没有才报错；
+/// 每个报价源都报 `NoRoute` 时整体也报 `NoRoute`，否则熔断/重试会把它当作报价源故障。
+/// 每次比较都记下胜者和价差，用来看聚合器的路由在哪些交易对上不如直连池子。
+pub struct BestQuote<Q> {
+    sources: Vec<(String, Q)>,
+    deadline: Duration,
+    max_records: usize,
+    stats: Mutex<Vec<SourceStats>>,
+    records: Mutex<VecDeque<Comparison>>,
+}
+
+impl<Q: QuoteProvider> BestQuote<Q> {
+    pub fn new(sources: Vec<(String, Q)>, deadline: Duration) -> Self {
+        let stats = sources.iter().map(|(n, _)| SourceStats { name: n.clone(), ..Default::default() }).collect();
+        Self {
+            sources,
+            deadline,
+            max_records: 10_000,
+            stats: Mutex::new(stats),
+            records: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 最多保留多少条比较记录（超出丢弃最早的）
+    pub fn max_records(mut self, n: usize) -> Self {
+        self.max_records = n;
+        self
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = (&str, &Q)> {
+        self.sources.iter().map(|(n, q)| (n.as_str(), q))
+    }
+
+    pub fn stats(&self) -> Vec<SourceStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// 最近的比较记录（旧的在前）
+    pub fn comparisons(&self) -> Vec<Comparison> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// 累计各报价源的结果；winner 为 None 表示这次没有任何报价
+    fn tally(&self, outcomes: &[Outcome], winner: Option<(usize, u64)>) {
+        let mut stats = self.stats.lock().unwrap();
+        for (i, (s, o)) in stats.iter_mut().zip(outcomes).enumerate() {
+            match o {
+                Outcome::Quoted(out) => {
+                    s.quoted += 1;
+                    if let Some((w, best)) = winner {
+                        s.shortfall = s.shortfall + Bps::from_ratio(best as u128, *out as u128);
+                        s.wins += (i == w) as u64;
+                    }
+                }
+                Outcome::Failed(_) => s.failed += 1,
+                Outcome::TimedOut => s.timed_out += 1,
+                Outcome::Skipped => s.skipped += 1,
+            }
+        }
+    }
+
+    fn record(&self, c: Comparison) {
+        if self.max_records == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= self.max_records {
+            records.pop_front();
+        }
+        records.push_back(c);
+    }
+}
+
+impl<Q: QuoteProvider> QuoteProvider for BestQuote<Q> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        let deadline = tokio::time::Instant::now() + self.deadline;
+        let mut outcomes: Vec<Outcome> = Vec::with_capacity(self.sources.len());
+        let mut pending = FuturesUnordered::new();
+        for (i, (_, q)) in self.sources.iter().enumerate() {
+            if !q.available() {
+                outcomes.push(Outcome::Skipped);
+                continue;
+            }
+            outcomes.push(Outcome::TimedOut);
+            let (im, om) = (input_mint.clone(), output_mint.clone());
+            pending.push(async move { (i, q.quote(im, om, amount).await) });
+        }
+
+        let mut best: Option<(usize, Quote)> = None;
+        let mut no_route = 0;
+        while let Ok(Some((i, r))) = tokio::time::timeout_at(deadline, pending.next()).await {
+            outcomes[i] = match r {
+                Ok(q) => {
+                    let out = q.out_amount;
+                    if best.as_ref().is_none_or(|(_, b)| out > b.out_amount) {
+                        best = Some((i, q));
+                    }
+                    Outcome::Quoted(out)
+                }
+                Err(e) => {
+                    no_route += e.downcast_ref::<NoRoute>().is_some() as usize;
+                    Outcome::Failed(e.to_string())
+                }
+            };
+        }
+        drop(pending);
+
+        self.tally(&outcomes, best.as_ref().map(|(i, q)| (*i, q.out_amount)));
+        let Some((winner, quote)) = best else {
+            let detail: Vec<String> = self
+                .sources
+                .iter()
+                .zip(&outcomes)
+                .map(|((n, _), o)| match o {
+                    Outcome::Failed(e) => format!("{}: {}", n, e),
+                    other => format!("{}: {:?}", n, other),
+                })
+                .collect();
+            if no_route == self.sources.len() {
+                return Err(NoRoute(format!("{} → {} ({})", input_mint, output_mint, detail.join("; "))).into());
+            }
+            return Err(anyhow!(
+                "best-quote: no quote for {} → {} within {}ms ({})",
+                input_mint, output_mint, self.deadline.as_millis(), detail.join("; ")
+            ));
+        };
+
+        let worst = outcomes
+            .iter()
+            .filter_map(|o| match o {
+                Outcome::Quoted(out) => Some(*out),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(quote.out_amount);
+        let c = Comparison {
+            input_mint,
+            output_mint,
+            amount,
+            winner: self.sources[winner].0.clone(),
+            outcomes: self.sources.iter().map(|(n, _)| n.clone()).zip(outcomes).collect(),
+            spread: Bps::from_ratio(quote.out_amount as u128, worst as u128),
+        };
+        self.record(c);
+        Ok(quote)
+    }
+
+    fn available(&self) -> bool {
+        self.sources.iter().any(|(_, q)| q.available())
+    }
+}
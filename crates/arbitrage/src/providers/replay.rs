@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::{anyhow, bail, Result};
+use crate::middleware::recorder::{read_records, QuoteRecord};
+use crate::ports::quote::{Quote, QuoteProvider};
+
+/// 回放匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayMode {
+    /// 只认 (input, output, amount) 完全一致的记录
+    #[default]
+    Exact,
+    /// 先找完全一致的；没有则取 amount 最近的成功记录，按比例线性缩放
+    Nearest,
+}
+
+/// 回放报价器：从录制的 NDJSON 文件应答，结果完全确定
+///
+/// 同一 (input, output, amount) 有多条记录时取文件中最后一条。
+pub struct ReplayQuoter {
+    mode: ReplayMode,
+    /// (input, output) → 按 amount 升序的记录
+    book: HashMap<(String, String), Vec<QuoteRecord>>,
+}
+
+impl ReplayQuoter {
+    pub fn load(path: impl AsRef<Path>, mode: ReplayMode) -> Result<Self> {
+        Ok(Self::from_records(read_records(path)?, mode))
+    }
+
+    pub fn from_records(recs: impl IntoIterator<Item = QuoteRecord>, mode: ReplayMode) -> Self {
+        let mut book: HashMap<(String, String), Vec<QuoteRecord>> = HashMap::new();
+        for r in recs {
+            let v = book.entry((r.input_mint.clone(), r.output_mint.clone())).or_default();
+            // 同 amount 后来者覆盖
+            match v.binary_search_by_key(&r.amount, |x| x.amount) {
+                Ok(i) => v[i] = r,
+                Err(i) => v.insert(i, r),
+            }
+        }
+        Self { mode, book }
+    }
+
+    /// 记录条数（去重后）
+    pub fn len(&self) -> usize {
+        self.book.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lookup(&self, im: &str, om: &str, amount: u64) -> Result<Quote> {
+        let recs = self
+            .book
+            .get(&(im.to_string(), om.to_string()))
+            .ok_or_else(|| anyhow!("replay: no record for {} → {}", im, om))?;
+
+        if let Ok(i) = recs.binary_search_by_key(&amount, |r| r.amount) {
+            return replay(&recs[i]);
+        }
+        if self.mode == ReplayMode::Exact {
+            bail!("replay: no record for {} → {} amount {}", im, om, amount);
+        }
+
+        let near = recs
+            .iter()
+            .filter(|r| r.amount > 0 && succeeded(r))
+            .min_by_key(|r| r.amount.abs_diff(amount))
+            .ok_or_else(|| anyhow!("replay: no successful record for {} → {}", im, om))?;
+        Ok(scale(&replay(near)?, near.amount, amount))
+    }
+}
+
+/// 能回放出报价的记录：有完整报价，或没有错误且有 out_amount
+fn succeeded(r: &QuoteRecord) -> bool {
+    r.quote.is_some() || (r.error.is_none() && r.out_amount.is_some())
+}
+
+fn replay(r: &QuoteRecord) -> Result<Quote> {
+    match (&r.quote, &r.error) {
+        (Some(q), _) => Ok(q.clone()),
+        (None, Some(e)) => Err(anyhow!("replay: {}", e)),
+        (None, None) => match r.out_amount {
+            Some(out) => Ok(Quote::simple(r.input_mint.clone(), r.output_mint.clone(), r.amount, out)),
+            None => bail!("replay: empty record"),
+        },
+    }
+}
+
+/// 按 amount / from 线性缩放所有数量（向下取整）
+fn scale(q: &Quote, from: u64, amount: u64) -> Quote {
+    let k = |x: u64| (x as u128 * amount as u128 / from as u128).min(u64::MAX as u128) as u64;
+    let mut s = q.clone();
+    s.in_amount = amount;
+    s.out_amount = k(q.out_amount);
+    s.min_out_amount = k(q.min_out_amount);
+    for l in &mut s.route {
+        l.in_amount = k(l.in_amount);
+        l.out_amount = k(l.out_amount);
+        l.fee_amount = k(l.fee_amount);
+    }
+    s
+}
+
+impl QuoteProvider for ReplayQuoter {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        self.lookup(&input_mint, &output_mint, amount)
+    }
+}
@@ -0,0 +1,103 @@
+use arbitrage::prelude::*;
+use proptest::prelude::*;
+
+/* ---- 固定用例 ---- */
+#[test]
+fn parse_and_rescale_are_exact() {
+    let a = Amount::parse_ui("0.25", 9).unwrap();
+    assert_eq!((a.raw(), a.decimals()), (250_000_000, 9));
+    assert_eq!(a.to_string(), "0.250000000");
+
+    // 0.1 在 f64 下有误差，这里必须精确
+    assert_eq!(Amount::parse_ui("0.1", 6).unwrap().raw(), 100_000);
+    // 精度不够时报错而不是悄悄截断
+    assert!(Amount::parse_ui("0.0000001", 6).is_err());
+    assert!(Amount::parse_ui("abc", 6).is_err());
+    assert!(Amount::parse_ui("-1", 6).is_err());
+}
+
+#[test]
+fn bps_ratio_and_display() {
+    assert_eq!(Bps::from_ratio(1_020_000_000, 1_000_000_000), Bps::from_int(200));
+    assert_eq!(Bps::from_ratio(990, 1_000), Bps::from_int(-100));
+    // 1/3 bps 向下取整到 1e-6
+    assert_eq!(Bps::from_ratio(3_001, 3_000).to_string(), "+3.333333");
+    assert_eq!(Bps::from_int(-25).to_string(), "-25.000000");
+    assert_eq!("-3.5".parse::<Bps>().unwrap(), Bps::from_micro(-3_500_000));
+    assert!("1.0000001".parse::<Bps>().is_err());
+}
+
+#[test]
+fn fee_model_is_exact() {
+    let fees = FeeModel { fee_bps_per_hop: 10, slippage_bps: 5, tx_cost: 0 };
+    assert_eq!(fees.cost(3), Bps::from_int(45));
+
+    let a = Amount::from_u64(1_000_000_000, 9);
+    assert_eq!(a.apply_bps(-fees.cost(3)).raw(), 995_500_000);
+    assert_eq!(a.apply_bps(Bps::from_int(-20_000)).raw(), 0);
+
+    // 1 SOL 起步，交易费 10_000 lamports = 0.1 bps
+    assert_eq!(fees.tx_cost_bps(a), Bps::ZERO);
+    let fees = FeeModel { tx_cost: 10_000, ..fees };
+    assert_eq!(fees.tx_cost_bps(a), Bps::from_micro(100_000));
+}
+
+/* ---- 性质测试 ---- */
+proptest! {
+    #[test]
+    fn display_parse_roundtrip(raw in any::<u64>(), dec in 0u8..=18) {
+        let a = Amount::from_u64(raw, dec);
+        let b: Amount = a.to_string().parse().unwrap();
+        prop_assert_eq!(b, a);
+    }
+
+    #[test]
+    fn rescale_up_then_down_is_identity(raw in any::<u64>(), dec in 0u8..=12, extra in 0u8..=12) {
+        let a = Amount::from_u64(raw, dec);
+        let up = a.rescale(dec + extra).unwrap();
+        prop_assert_eq!(up.raw(), raw as u128 * 10u128.pow(extra as u32));
+        prop_assert_eq!(up.rescale(dec).unwrap(), a);
+    }
+
+    #[test]
+    fn rescale_down_never_drops_digits(raw in 1u64.., dec in 1u8..=12) {
+        let a = Amount::from_u64(raw, dec);
+        match a.rescale(dec - 1) {
+            Ok(b) => prop_assert_eq!(b.raw() * 10, raw as u128),
+            Err(_) => prop_assert_ne!(raw % 10, 0),
+        }
+    }
+
+    #[test]
+    fn parse_ui_matches_integer_math(int in 0u64..1_000_000, frac in 0u32..1_000_000, dec in 6u8..=12) {
+        let s = format!("{}.{:06}", int, frac);
+        let a = Amount::parse_ui(&s, dec).unwrap();
+        let want = (int as u128 * 1_000_000 + frac as u128) * 10u128.pow(dec as u32 - 6);
+        prop_assert_eq!(a.raw(), want);
+    }
+
+    #[test]
+    fn bps_display_parse_roundtrip(micro in -(1i128 << 100)..(1i128 << 100)) {
+        let b = Bps::from_micro(micro);
+        prop_assert_eq!(b.to_string().parse::<Bps>().unwrap(), b);
+    }
+
+    #[test]
+    fn ratio_then_apply_is_within_one_unit(start in 1u64.., end in any::<u64>()) {
+        // from_ratio 向下取整，apply_bps 也向下取整：最多差 1 个最小单位（加上 1e-6 bps 的舍入）
+        let bps = Bps::from_ratio(end as u128, start as u128);
+        let back = Amount::from_u64(start, 0).apply_bps(bps).raw() as i128;
+        let err = (end as i128 - back).abs();
+        prop_assert!(err <= 1 + start as i128 / 10_000_000_000, "end={} back={} bps={}", end, back, bps);
+    }
+
+    #[test]
+    fn serde_roundtrip(raw in any::<u64>(), dec in 0u8..=18, micro in any::<i64>()) {
+        let a = Amount::from_u64(raw, dec);
+        let b = Bps::from_micro(micro as i128);
+        let ja = serde_json::to_string(&a).unwrap();
+        let jb = serde_json::to_string(&b).unwrap();
+        prop_assert_eq!(serde_json::from_str::<Amount>(&ja).unwrap(), a);
+        prop_assert_eq!(serde_json::from_str::<Bps>(&jb).unwrap(), b);
+    }
+}
@@ -0,0 +1,87 @@
+use arbitrage::prelude::*;
+
+/* ---- Mock Resolver：大写即 mint ---- */
+struct DummyResolver;
+impl MintResolver for DummyResolver {
+    fn get_mint(&self, sym: &str) -> anyhow::Result<&str> {
+        match sym.to_ascii_uppercase().as_str() {
+            "SOL" => Ok("SOL"), "A" => Ok("A"),
+            _ => anyhow::bail!("unknown"),
+        }
+    }
+    fn get_decimals(&self, _sym: &str) -> Option<u8> { Some(9) }
+    fn is_tradable(&self, _mint: &str) -> Option<bool> { Some(true) }
+}
+
+fn rec(ts_ms: u64, im: &str, om: &str, amount: u64, out: u64) -> QuoteRecord {
+    QuoteRecord {
+        ts_ms, input_mint: im.into(), output_mint: om.into(), amount,
+        out_amount: Some(out), latency_ms: 0, error: None, quote: None,
+    }
+}
+
+/* 4 轮快照：A → SOL 分别 +2%、-0.1%、+3%、+1% */
+fn snapshots() -> Vec<Snapshot> {
+    const ONE: u64 = 1_000_000_000;
+    let mut recs = Vec::new();
+    for (k, back) in [1_020_000_000u64, 999_000_000, 1_030_000_000, 1_010_000_000].into_iter().enumerate() {
+        let t = k as u64 * 10_000;
+        recs.push(rec(t, "SOL", "A", ONE, ONE));
+        recs.push(rec(t + 5, "A", "SOL", ONE, back));
+    }
+    split_snapshots(recs, 1_000, ReplayMode::Nearest)
+}
+
+fn cfg(exec_delay: usize) -> BacktestConfig {
+    BacktestConfig {
+        base: "SOL".into(),
+        mids: vec!["A".into()],
+        amount: Amount::from_raw(1, 0),
+        hops: 2,
+        fees: FeeModel { fee_bps_per_hop: 10, slippage_bps: 5, tx_cost: 0 },
+        min_net_bps: Bps::from_int(50),
+        exec_delay,
+        concurrency: 2,
+    }
+}
+
+#[test]
+fn splits_records_into_rounds() {
+    let snaps = snapshots();
+    assert_eq!(snaps.len(), 4);
+    assert_eq!(snaps.iter().map(|s| s.ts_ms).collect::<Vec<_>>(), vec![0, 10_000, 20_000, 30_000]);
+    assert!(snaps.iter().all(|s| s.quoter.len() == 2));
+}
+
+#[tokio::test]
+async fn delayed_execution_exposes_false_positives() {
+    let rep = run_backtest(&DummyResolver, &snapshots(), &cfg(1)).await.unwrap();
+
+    // 第 0 轮发现 +1.7%，下一轮成交时已是 -0.4%；第 2、3 轮都赚 0.7%
+    assert_eq!((rep.snapshots, rep.hits, rep.executed, rep.false_positives), (4, 3, 3, 1));
+    assert_eq!(rep.pnl, -4_000_000 + 7_000_000 + 7_000_000);
+    assert_eq!(rep.max_drawdown, 4_000_000);
+    assert!((rep.false_positive_rate - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(rep.trades[0].realized_net_bps, Some(Bps::from_int(-40)));
+}
+
+#[tokio::test]
+async fn immediate_execution_matches_expectation() {
+    let rep = run_backtest(&DummyResolver, &snapshots(), &cfg(0)).await.unwrap();
+
+    assert_eq!((rep.hits, rep.false_positives, rep.max_drawdown), (3, 0, 0));
+    assert_eq!(rep.pnl, 17_000_000 + 27_000_000 + 7_000_000);
+    assert!(rep.trades.iter().all(|t| t.realized_net_bps == Some(t.expected_net_bps)));
+}
+
+#[tokio::test]
+async fn realized_includes_tx_cost() {
+    let mut cfg = cfg(0);
+    // 0.005 SOL 交易费 = 50 bps
+    cfg.fees.tx_cost = 5_000_000;
+    let rep = run_backtest(&DummyResolver, &snapshots(), &cfg).await.unwrap();
+
+    assert_eq!(rep.hits, 2);
+    assert!(rep.trades.iter().all(|t| t.realized_net_bps == Some(t.expected_net_bps)));
+    assert_eq!(rep.pnl, 12_000_000 + 22_000_000);
+}
@@ -0,0 +1,114 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::time::Duration;
+
+/* ---- Mock 报价器：固定输出 = amount * rate / 100，可延迟/失败/无路由/不可用 ---- */
+#[derive(Default)]
+struct Fixed { rate: u64, delay_ms: u64, fail: bool, no_route: bool, down: bool }
+impl QuoteProvider for Fixed {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        if self.delay_ms > 0 { tokio::time::sleep(Duration::from_millis(self.delay_ms)).await; }
+        if self.fail { anyhow::bail!("no route"); }
+        if self.no_route { return Err(NoRoute(format!("{} → {}", im, om)).into()); }
+        Ok(Quote::simple(im, om, amount, amount * self.rate / 100))
+    }
+    fn available(&self) -> bool { !self.down }
+}
+
+fn src(name: &str, f: Fixed) -> (String, Fixed) {
+    (name.to_string(), f)
+}
+
+async fn call<Q: QuoteProvider>(q: &Q) -> Result<Quote> {
+    q.quote("A".into(), "B".into(), 1_000_000).await
+}
+
+#[tokio::test(start_paused = true)]
+async fn picks_highest_output_and_records_spread() {
+    let best = BestQuote::new(vec![
+        src("jupiter", Fixed { rate: 99, delay_ms: 50, ..Default::default() }),
+        src("dex", Fixed { rate: 100, delay_ms: 10, ..Default::default() }),
+        src("replay", Fixed { fail: true, ..Default::default() }),
+    ], Duration::from_millis(500));
+
+    let q = call(&best).await.unwrap();
+    assert_eq!(q.out_amount, 1_000_000);
+
+    let c = &best.comparisons()[0];
+    assert_eq!(c.winner, "dex");
+    assert_eq!(c.outcomes[0], ("jupiter".into(), Outcome::Quoted(990_000)));
+    assert!(matches!(&c.outcomes[2].1, Outcome::Failed(e) if e.contains("no route")));
+    // 1_000_000 / 990_000 - 1 ≈ 101 bps
+    assert_eq!(c.spread.whole(), 101);
+
+    call(&best).await.unwrap();
+    let st = best.stats();
+    assert_eq!((st[1].wins, st[1].quoted, st[1].avg_shortfall()), (2, 2, Bps::ZERO));
+    assert_eq!((st[0].wins, st[0].quoted, st[0].avg_shortfall().whole()), (0, 2, 101));
+    assert_eq!(st[2].failed, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn deadline_returns_what_has_arrived() {
+    let best = BestQuote::new(vec![
+        src("fast", Fixed { rate: 98, delay_ms: 20, ..Default::default() }),
+        src("slow", Fixed { rate: 105, delay_ms: 2_000, ..Default::default() }),
+    ], Duration::from_millis(200));
+
+    let t0 = tokio::time::Instant::now();
+    let q = call(&best).await.unwrap();
+    assert_eq!(q.out_amount, 980_000);
+    assert_eq!(t0.elapsed(), Duration::from_millis(200));
+
+    let c = &best.comparisons()[0];
+    assert_eq!((c.winner.as_str(), &c.outcomes[1].1, c.spread), ("fast", &Outcome::TimedOut, Bps::ZERO));
+    assert_eq!(best.stats()[1].timed_out, 1);
+
+    // 全部返回时不必等到截止
+    let quick = BestQuote::new(vec![src("a", Fixed { rate: 100, delay_ms: 5, ..Default::default() })], Duration::from_secs(10));
+    let t0 = tokio::time::Instant::now();
+    call(&quick).await.unwrap();
+    assert_eq!(t0.elapsed(), Duration::from_millis(5));
+}
+
+#[tokio::test(start_paused = true)]
+async fn errors_when_nothing_answers() {
+    let best = BestQuote::new(vec![
+        src("down", Fixed { rate: 100, down: true, ..Default::default() }),
+        src("broken", Fixed { fail: true, ..Default::default() }),
+        src("slow", Fixed { rate: 100, delay_ms: 1_000, ..Default::default() }),
+    ], Duration::from_millis(100)).max_records(1);
+
+    let e = call(&best).await.unwrap_err().to_string();
+    assert!(e.contains("broken: no route") && e.contains("slow: TimedOut") && e.contains("down: Skipped"), "{}", e);
+    assert!(best.comparisons().is_empty());
+    let st = best.stats();
+    assert_eq!((st[0].skipped, st[1].failed, st[2].timed_out), (1, 1, 1));
+    assert!(best.available());
+
+    let all_down = BestQuote::new(vec![src("x", Fixed { down: true, ..Default::default() })], Duration::from_millis(100));
+    assert!(!all_down.available());
+}
+
+#[tokio::test(start_paused = true)]
+async fn no_route_only_when_every_source_says_so() {
+    let best = BestQuote::new(vec![
+        src("jupiter", Fixed { no_route: true, ..Default::default() }),
+        src("dex", Fixed { no_route: true, delay_ms: 10, ..Default::default() }),
+    ], Duration::from_millis(100));
+    let e = call(&best).await.unwrap_err();
+    assert!(e.downcast_ref::<NoRoute>().is_some(), "{}", e);
+    assert!(e.to_string().contains("dex: no route"), "{}", e);
+
+    // 有一个是真故障（或超时）就不算 NoRoute
+    let mixed = BestQuote::new(vec![
+        src("jupiter", Fixed { no_route: true, ..Default::default() }),
+        src("dex", Fixed { fail: true, ..Default::default() }),
+    ], Duration::from_millis(100));
+    assert!(call(&mixed).await.unwrap_err().downcast_ref::<NoRoute>().is_none());
+    let slow = BestQuote::new(vec![
+        src("jupiter", Fixed { no_route: true, ..Default::default() }),
+        src("dex", Fixed { rate: 100, delay_ms: 1_000, ..Default::default() }),
+    ], Duration::from_millis(100));
+    assert!(call(&slow).await.unwrap_err().downcast_ref::<NoRoute>().is_none());
+}
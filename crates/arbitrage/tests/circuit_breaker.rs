@@ -0,0 +1,112 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/* ---- Mock 报价器：可切换成功/失败，记录回源次数 ---- */
+#[derive(Default)]
+struct Switch { fail: AtomicBool, calls: AtomicUsize, delay_ms: u64 }
+impl QuoteProvider for Switch {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.delay_ms > 0 { tokio::time::sleep(Duration::from_millis(self.delay_ms)).await; }
+        if self.fail.load(Ordering::SeqCst) { anyhow::bail!("down"); }
+        Ok(Quote::simple(im, om, amount, amount))
+    }
+}
+
+fn cfg() -> BreakerConfig {
+    BreakerConfig { window: 10, min_calls: 4, max_failure_pct: 50, cooldown_ms: 1_000, half_open_probes: 1 }
+}
+
+async fn call<Q: QuoteProvider>(q: &Q) -> Result<Quote> {
+    q.quote("A".into(), "B".into(), 1).await
+}
+
+#[tokio::test]
+async fn opens_on_failure_rate_and_fails_fast() {
+    let clock = Arc::new(ManualClock::new(0));
+    let inner = Switch::default();
+    let cb = CircuitBreaker::with_clock(&inner, cfg(), clock.clone());
+
+    call(&cb).await.unwrap();
+    call(&cb).await.unwrap();
+    inner.fail.store(true, Ordering::SeqCst);
+    assert!(call(&cb).await.is_err());
+    assert_eq!(cb.state(), BreakerState::Closed);
+    assert!(call(&cb).await.is_err()); // 2/4 失败 → 打开
+    assert_eq!(cb.state(), BreakerState::Open);
+    assert!(!cb.available());
+
+    let e = call(&cb).await.unwrap_err();
+    assert!(e.downcast_ref::<CircuitOpen>().is_some());
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 4);
+    assert_eq!(cb.rejected(), 1);
+}
+
+#[tokio::test]
+async fn half_open_probe_closes_or_reopens() {
+    let clock = Arc::new(ManualClock::new(0));
+    let inner = Switch { fail: AtomicBool::new(true), ..Default::default() };
+    let cb = CircuitBreaker::with_clock(&inner, cfg(), clock.clone());
+    for _ in 0..4 { let _ = call(&cb).await; }
+    assert_eq!(cb.state(), BreakerState::Open);
+
+    // 冷却结束 → 半开，探测失败 → 重新打开
+    clock.advance(1_000);
+    assert_eq!(cb.state(), BreakerState::HalfOpen);
+    assert!(call(&cb).await.is_err());
+    assert_eq!(cb.state(), BreakerState::Open);
+
+    // 再冷却，探测成功 → 关闭
+    clock.advance(999);
+    assert_eq!(cb.state(), BreakerState::Open);
+    clock.advance(1);
+    inner.fail.store(false, Ordering::SeqCst);
+    call(&cb).await.unwrap();
+    assert_eq!(cb.state(), BreakerState::Closed);
+    assert!(cb.available());
+}
+
+#[tokio::test]
+async fn half_open_limits_concurrent_probes() {
+    let clock = Arc::new(ManualClock::new(0));
+    let inner = Switch { fail: AtomicBool::new(true), delay_ms: 20, ..Default::default() };
+    let cb = CircuitBreaker::with_clock(&inner, cfg(), clock.clone());
+    for _ in 0..4 { let _ = call(&cb).await; }
+
+    clock.advance(1_000);
+    inner.fail.store(false, Ordering::SeqCst);
+    let (a, b) = tokio::join!(call(&cb), call(&cb));
+    assert!(a.is_ok());
+    assert!(b.unwrap_err().downcast_ref::<CircuitOpen>().is_some());
+    assert_eq!(cb.state(), BreakerState::Closed);
+}
+
+#[tokio::test]
+async fn needs_min_calls_before_opening() {
+    let inner = Switch { fail: AtomicBool::new(true), ..Default::default() };
+    let cb = CircuitBreaker::new(&inner, cfg());
+    for _ in 0..3 { let _ = call(&cb).await; }
+    assert_eq!(cb.state(), BreakerState::Closed);
+}
+
+/* ---- 无路由是请求的问题，不算报价源故障 ---- */
+struct NoRoutes;
+impl QuoteProvider for NoRoutes {
+    async fn quote(&self, im: String, om: String, _amount: u64) -> Result<Quote> {
+        Err(NoRoute(format!("{} → {}", im, om)).into())
+    }
+}
+
+#[tokio::test]
+async fn no_route_does_not_open() {
+    let cb = CircuitBreaker::new(NoRoutes, cfg());
+    for _ in 0..10 {
+        let e = call(&cb).await.unwrap_err();
+        assert!(e.downcast_ref::<NoRoute>().is_some());
+    }
+    assert_eq!(cb.state(), BreakerState::Closed);
+    assert_eq!(cb.rejected(), 0);
+}
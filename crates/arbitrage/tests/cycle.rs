@@ -0,0 +1,127 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* ---- Mock 报价器（按倍数返回，并记录调用次数）---- */
+struct MapQuoter { m: HashMap<(String,String), f64>, calls: AtomicUsize }
+impl MapQuoter {
+    fn new() -> Self { Self { m: HashMap::new(), calls: AtomicUsize::new(0) } }
+    fn set(mut self, a:&str,b:&str,k:f64)->Self{ self.m.insert((a.into(),b.into()),k); self }
+}
+#[allow(async_fn_in_trait)]
+impl QuoteProvider for MapQuoter {
+    async fn quote(&self, im:String, om:String, amount:u64) -> Result<Quote> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        let k = *self.m.get(&(im.clone(),om.clone())).unwrap_or(&1.0);
+        Ok(Quote::simple(im, om, amount, ((amount as f64)*k).round() as u64))
+    }
+}
+
+/* 1 个 base（按 base 精度换算） */
+fn one() -> Amount { Amount::from_raw(1, 0) }
+
+/* ---- Mock Resolver：大写即 mint ---- */
+struct DummyResolver;
+impl MintResolver for DummyResolver {
+    fn get_mint(&self, sym: &str) -> anyhow::Result<&str> {
+        match sym.to_ascii_uppercase().as_str() {
+            "SOL" => Ok("SOL"), "A" => Ok("A"), "B" => Ok("B"), "C" => Ok("C"),
+            _ => anyhow::bail!("unknown"),
+        }
+    }
+    fn get_decimals(&self, _sym: &str) -> Option<u8> { Some(9) }
+    fn is_tradable(&self, _mint: &str) -> Option<bool> { Some(true) }
+}
+
+fn mids() -> Vec<String> { vec!["A".into(), "B".into(), "C".into()] }
+
+/* ---- 4 跳：只有 SOL → A → B → C → SOL 赚钱 ---- */
+#[tokio::test]
+async fn four_hop_cycle_found() {
+    let quoter = MapQuoter::new()
+        .set("A", "B", 1.01)
+        .set("B", "C", 1.01)
+        .set("C", "SOL", 1.01);
+
+    let rows = evaluate_cycles(&DummyResolver, &quoter, "SOL", &mids(), one(), &CycleSearch::exact(4, 4)).await;
+
+    // 3 个 mid 的有序排列 = 6 条 4 跳环路
+    assert_eq!(rows.len(), 6);
+    assert!(rows.iter().all(|r| r.path.split(" → ").count() == 5));
+
+    let best = rows.iter().max_by_key(|r| r.delta_bps).unwrap();
+    assert_eq!(best.path, "SOL → A → B → C → SOL");
+    assert!(best.delta_bps > Bps::from_int(300), "bps={}", best.delta_bps);
+}
+
+/* ---- up_to：2..=3 跳都返回 ---- */
+#[tokio::test]
+async fn up_to_covers_all_lengths() {
+    let quoter = MapQuoter::new();
+    let rows = evaluate_cycles(&DummyResolver, &quoter, "SOL", &mids(), one(), &CycleSearch::up_to(3, 4)).await;
+
+    // 2 跳 3 条 + 3 跳 6 条
+    assert_eq!(rows.len(), 9);
+}
+
+/* ---- 剪枝：A → B 亏 50%，所有经过 A → B 的前缀都不再扩展 ---- */
+#[tokio::test]
+async fn prefix_loss_prunes() {
+    let quoter = MapQuoter::new().set("A", "B", 0.5);
+
+    let cfg = CycleSearch { max_prefix_loss_bps: Some(Bps::from_int(100)), ..CycleSearch::exact(4, 4) };
+    let rows = evaluate_cycles(&DummyResolver, &quoter, "SOL", &mids(), one(), &cfg).await;
+
+    assert_eq!(rows.len(), 4);
+    assert!(rows.iter().all(|r| !r.path.contains("A → B")));
+}
+
+/* ---- 第一跳报价在各前缀之间共享 ---- */
+#[tokio::test]
+async fn first_leg_quoted_once_per_mid() {
+    let quoter = MapQuoter::new();
+    let rows = evaluate_3hop(&DummyResolver, &quoter, "SOL", &mids(), one(), 4, false).await;
+
+    assert_eq!(rows.len(), 6);
+    // 3 (BASE→X) + 6 (X→Y) + 6 (Y→BASE)
+    assert_eq!(quoter.calls.load(Ordering::Relaxed), 15);
+}
+
+/* ---- 逐跳明细：A → B 这一跳贡献了全部收益 ---- */
+#[tokio::test]
+async fn legs_pinpoint_profitable_hop() {
+    let quoter = MapQuoter::new().set("A", "B", 1.02);
+    let rows = evaluate_cycles(&DummyResolver, &quoter, "SOL", &mids(), one(), &CycleSearch::exact(3, 4)).await;
+
+    let r = rows.iter().find(|r| r.path == "SOL → A → B → SOL").unwrap();
+    assert_eq!(r.hops(), 3);
+    assert_eq!(r.mints(), vec!["SOL", "A", "B", "SOL"]);
+    assert_eq!((r.legs[1].from_symbol.as_str(), r.legs[1].to_symbol.as_str()), ("A", "B"));
+    assert_eq!(r.legs[1].amount_in, 1_000_000_000);
+    assert_eq!(r.legs[1].amount_out, 1_020_000_000);
+
+    let bps: Vec<Bps> = r.legs.iter().map(|l| l.leg_bps).collect();
+    assert_eq!(bps, vec![Bps::ZERO, Bps::from_int(200), Bps::ZERO]);
+}
+
+/* ---- 重新报价：只有变了的那一跳 bps 变化，尺寸结果清空 ---- */
+#[tokio::test]
+async fn requote_reflects_new_rates() {
+    let before = MapQuoter::new().set("A", "B", 1.01);
+    let rows = evaluate_cycles(&DummyResolver, &before, "SOL", &mids(), one(), &CycleSearch::exact(3, 4)).await;
+    let mut row = rows.into_iter().find(|r| r.path == "SOL → A → B → SOL").unwrap();
+    row.optimal_in = Some(1);
+
+    let after = MapQuoter::new().set("A", "B", 1.03);
+    let r = requote_cycle(&after, &row).await.unwrap();
+    assert_eq!(r.path, row.path);
+    assert_eq!(r.start, row.start);
+    assert_eq!(r.delta_bps, Bps::from_int(300));
+    assert_eq!(r.legs[1].amount_out, 1_030_000_000);
+    let bps: Vec<Bps> = r.legs.iter().map(|l| l.leg_bps).collect();
+    assert_eq!(bps, vec![Bps::ZERO, Bps::from_int(300), Bps::ZERO]);
+    assert_eq!(r.optimal_in, None);
+    // 首跳复用为 A 的基准，B 补报一次 SOL → B
+    assert_eq!(after.calls.load(Ordering::Relaxed), 4);
+}
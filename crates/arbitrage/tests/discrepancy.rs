@@ -0,0 +1,124 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/* ---- Mock 报价器：按倍数返回；depth 模拟单个池子的滑点（恒定乘积，储备 = depth） ---- */
+#[derive(Default)]
+struct Curve { m: HashMap<(String, String), f64>, depth: Option<f64> }
+impl Curve {
+    fn set(mut self, a: &str, b: &str, k: f64) -> Self { self.m.insert((a.into(), b.into()), k); self }
+    fn depth(mut self, d: f64) -> Self { self.depth = Some(d); self }
+}
+impl QuoteProvider for Curve {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        let Some(k) = self.m.get(&(im.clone(), om.clone())) else { anyhow::bail!("no route") };
+        let x = amount as f64;
+        let out = match self.depth { Some(d) => x * k * d / (d + x), None => x * k };
+        Ok(Quote::simple(im, om, amount, out.round() as u64))
+    }
+}
+
+/* ---- Mock Resolver：大写即 mint ---- */
+struct DummyResolver;
+impl MintResolver for DummyResolver {
+    fn get_mint(&self, sym: &str) -> anyhow::Result<&str> {
+        match sym.to_ascii_uppercase().as_str() {
+            "SOL" => Ok("SOL"), "A" => Ok("A"), "B" => Ok("B"),
+            _ => anyhow::bail!("unknown"),
+        }
+    }
+    fn get_decimals(&self, _sym: &str) -> Option<u8> { Some(9) }
+    fn is_tradable(&self, _mint: &str) -> Option<bool> { Some(true) }
+}
+
+fn sol(n: u64) -> Amount { Amount::from_raw(n as u128, 0) }
+
+fn scan(sizes: Vec<Amount>, min_bps: i64, reverse: bool) -> DiscrepancyScan {
+    DiscrepancyScan { sizes, min_spread: Bps::from_int(min_bps), reverse, concurrency: 4 }
+}
+
+/* ---- 只报告超过阈值的交易对，反向按正向最好的输出询价 ---- */
+#[tokio::test]
+async fn reports_pairs_over_threshold() {
+    let sources = vec![
+        ("jupiter".to_string(), Curve::default().set("SOL", "A", 1.0).set("A", "SOL", 1.0).set("SOL", "B", 2.0).set("B", "SOL", 0.5)),
+        ("dex".to_string(), Curve::default().set("SOL", "A", 1.005).set("A", "SOL", 0.99).set("SOL", "B", 2.0).set("B", "SOL", 0.5)),
+    ];
+    let mids = vec!["A".into(), "b".into(), "SOL".into(), "X".into()];
+
+    let found = find_discrepancies(&DummyResolver, &sources, "sol", &mids, &scan(vec![sol(1), sol(10)], 20, true)).await;
+    let got: Vec<(&str, u64, &str)> = found.iter().map(|d| (d.pair.as_str(), d.amount, d.best.as_str())).collect();
+    // A → SOL 差 ~101bps 排在前；SOL → B 两边一样不报告
+    assert_eq!(got, vec![
+        ("A→SOL", 1_005_000_000, "jupiter"),
+        ("A→SOL", 10_050_000_000, "jupiter"),
+        ("SOL→A", 1_000_000_000, "dex"),
+        ("SOL→A", 10_000_000_000, "dex"),
+    ]);
+    let d = &found[2];
+    assert_eq!((d.best_out, d.worst.as_str(), d.worst_out), (1_005_000_000, "jupiter", 1_000_000_000));
+    assert_eq!(d.spread, Bps::from_int(50));
+    assert_eq!(d.outs, vec![("jupiter".into(), Some(1_000_000_000)), ("dex".into(), Some(1_005_000_000))]);
+
+    // 阈值更高时只剩反向
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &mids, &scan(vec![sol(1)], 100, true)).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].pair, "A→SOL");
+    // 不查反向
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &mids, &scan(vec![sol(1)], 100, false)).await;
+    assert!(found.is_empty());
+}
+
+/* ---- mid ↔ mid 也比较，数量取 BASE → 输入代币的最好输出 ---- */
+#[tokio::test]
+async fn compares_every_pair_in_the_list() {
+    let sources = vec![
+        ("jupiter".to_string(), Curve::default().set("SOL", "A", 2.0).set("SOL", "B", 4.0).set("A", "B", 2.0).set("B", "A", 0.5)),
+        ("pool".to_string(), Curve::default().set("SOL", "A", 2.0).set("SOL", "B", 4.0).set("A", "B", 2.02).set("B", "A", 0.5)),
+    ];
+    let mids = vec!["A".into(), "B".into()];
+
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &mids, &scan(vec![sol(1)], 20, true)).await;
+    assert_eq!(found.len(), 1);
+    let d = &found[0];
+    assert_eq!((d.pair.as_str(), d.amount, d.best.as_str()), ("A→B", 2_000_000_000, "pool"));
+    assert_eq!(d.spread, Bps::from_int(100));
+
+    // 只比较一个方向时按列表顺序：A → B 在，B → A 不查
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &mids, &scan(vec![sol(1)], 0, false)).await;
+    let pairs: Vec<&str> = found.iter().map(|d| d.pair.as_str()).collect();
+    assert!(pairs.contains(&"A→B") && !pairs.contains(&"B→A") && !pairs.contains(&"A→SOL"), "{pairs:?}");
+}
+
+/* ---- 单个池子小额更好、大额因滑点变差：不同数量下胜者不同 ---- */
+#[tokio::test]
+async fn winner_depends_on_size() {
+    let sources = vec![
+        ("jupiter".to_string(), Curve::default().set("SOL", "A", 1.0)),
+        ("pool".to_string(), Curve::default().set("SOL", "A", 1.01).depth(1_000e9)),
+        ("broken".to_string(), Curve::default()),
+    ];
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &["A".into()], &scan(vec![sol(1), sol(100)], 0, false)).await;
+    assert_eq!(found.len(), 2);
+    let by_size = |n: u64| found.iter().find(|d| d.amount == n * 1_000_000_000).unwrap();
+    assert_eq!(by_size(1).best, "pool");
+    assert_eq!(by_size(100).best, "jupiter");
+    assert!(by_size(100).spread > by_size(1).spread);
+    assert_eq!(by_size(1).outs[2], ("broken".into(), None));
+
+    // 只有一个报价源成功时无从比较
+    let lonely = vec![("jupiter".to_string(), Curve::default().set("SOL", "A", 1.0)), ("broken".to_string(), Curve::default())];
+    assert!(find_discrepancies(&DummyResolver, &lonely, "SOL", &["A".into()], &scan(vec![sol(1)], 0, true)).await.is_empty());
+}
+
+/* ---- BASE → X 只有一个报价源成功：没有价差可报，但 X 出发的交易对照样按它的输出询价 ---- */
+#[tokio::test]
+async fn single_source_base_leg_still_sizes_mid_pairs() {
+    let sources = vec![
+        ("jupiter".to_string(), Curve::default().set("SOL", "A", 2.0).set("A", "B", 2.0)),
+        ("pool".to_string(), Curve::default().set("A", "B", 2.02)),
+    ];
+    let found = find_discrepancies(&DummyResolver, &sources, "SOL", &["A".into(), "B".into()], &scan(vec![sol(1)], 20, true)).await;
+    let got: Vec<(&str, u64, &str)> = found.iter().map(|d| (d.pair.as_str(), d.amount, d.best.as_str())).collect();
+    assert_eq!(got, vec![("A→B", 2_000_000_000, "pool")]);
+}
@@ -0,0 +1,80 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/* ---- Mock 报价器：记录回源次数，可设置延迟/失败 ---- */
+#[derive(Default)]
+struct Counting { calls: AtomicUsize, delay_ms: u64, fail_first: AtomicUsize }
+impl QuoteProvider for Counting {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.delay_ms > 0 { tokio::time::sleep(Duration::from_millis(self.delay_ms)).await; }
+        if self.fail_first.load(Ordering::SeqCst) > 0 {
+            self.fail_first.fetch_sub(1, Ordering::SeqCst);
+            anyhow::bail!("boom");
+        }
+        Ok(Quote::simple(im, om, amount, amount * 2))
+    }
+}
+
+fn q(c: &impl QuoteProvider, amount: u64) -> impl std::future::Future<Output = Result<Quote>> + '_ {
+    c.quote("SOL".into(), "USDC".into(), amount)
+}
+
+#[tokio::test]
+async fn hits_within_ttl_and_expires() {
+    let clock = Arc::new(ManualClock::new(0));
+    let inner = Counting::default();
+    let cache = QuoteCache::with_clock(&inner, 1_000, clock.clone());
+
+    assert_eq!(q(&cache, 10).await.unwrap().out_amount, 20);
+    clock.advance(999);
+    q(&cache, 10).await.unwrap();
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+    // 不同 amount 是不同的 key
+    q(&cache, 11).await.unwrap();
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+    clock.advance(1);
+    q(&cache, 10).await.unwrap();
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3, coalesced: 0 });
+}
+
+#[tokio::test]
+async fn concurrent_identical_requests_coalesce() {
+    let inner = Counting { delay_ms: 50, ..Default::default() };
+    let cache = QuoteCache::new(&inner, 10_000);
+
+    let outs = futures::future::join_all((0..8).map(|_| q(&cache, 7))).await;
+    assert!(outs.iter().all(|o| o.as_ref().unwrap().out_amount == 14));
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 1, coalesced: 7 });
+}
+
+#[tokio::test]
+async fn errors_are_not_cached() {
+    let inner = Counting { fail_first: AtomicUsize::new(1), ..Default::default() };
+    let cache = QuoteCache::new(&inner, 10_000);
+
+    assert!(q(&cache, 1).await.is_err());
+    assert!(q(&cache, 1).await.is_ok());
+    assert!(q(&cache, 1).await.is_ok());
+    assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn evicts_expired_entries_when_full() {
+    let clock = Arc::new(ManualClock::new(0));
+    let inner = Counting::default();
+    let cache = QuoteCache::with_clock(&inner, 100, clock.clone()).max_entries(2);
+
+    q(&cache, 1).await.unwrap();
+    q(&cache, 2).await.unwrap();
+    clock.advance(100);
+    q(&cache, 3).await.unwrap();
+    assert_eq!(cache.len(), 1);
+}
@@ -0,0 +1,119 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/* ---- Mock 报价器：前 fail 次返回指定错误 ---- */
+struct Flaky { calls: AtomicUsize, fail: usize, limited: Option<RateLimited> }
+impl Flaky {
+    fn new(fail: usize, limited: Option<RateLimited>) -> Self { Self { calls: AtomicUsize::new(0), fail, limited } }
+}
+impl QuoteProvider for Flaky {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        if n < self.fail {
+            return Err(match self.limited {
+                Some(rl) => rl.into(),
+                None => anyhow::anyhow!("boom"),
+            });
+        }
+        Ok(Quote::simple(im, om, amount, amount))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn limiter_is_shared_across_tasks() {
+    let limiter = RateLimiter::new(10, 1);
+    let t0 = Instant::now();
+    // 11 个并发任务共用一个桶：第 1 个立即通过，其余每 100ms 放行一个
+    futures::future::join_all((0..11).map(|_| {
+        let l = limiter.clone();
+        async move { l.acquire().await }
+    })).await;
+    let ms = t0.elapsed().as_millis();
+    assert!((1000..1100).contains(&ms), "elapsed={}ms", ms);
+}
+
+#[tokio::test(start_paused = true)]
+async fn uneven_qps_does_not_spin() {
+    // 1/3 秒补一个令牌，浮点累加会留下 0.999… 的残差
+    let limiter = RateLimiter::new(3, 1);
+    let t0 = Instant::now();
+    for _ in 0..31 { limiter.acquire().await; }
+    let ms = t0.elapsed().as_millis();
+    assert!((9_990..10_050).contains(&ms), "elapsed={}ms", ms);
+}
+
+#[tokio::test(start_paused = true)]
+async fn burst_passes_immediately() {
+    let limiter = RateLimiter::new(1, 5);
+    let t0 = Instant::now();
+    for _ in 0..5 { limiter.acquire().await; }
+    assert_eq!(t0.elapsed(), Duration::ZERO);
+    limiter.acquire().await;
+    assert!(t0.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_after_pauses_the_whole_bucket() {
+    let limiter = RateLimiter::new(100, 10);
+    let rl = RateLimited { retry_after: Some(Duration::from_secs(3)) };
+    let q = ThrottleRetry::new(Flaky::new(1, Some(rl)), limiter.clone(), 2);
+
+    let t0 = Instant::now();
+    q.quote("A".into(), "B".into(), 1).await.unwrap();
+    assert!(t0.elapsed() >= Duration::from_secs(3));
+    assert_eq!(q.inner().calls.load(Ordering::SeqCst), 2);
+
+    // 另一个共享该桶的请求也要等暂停结束
+    let t1 = Instant::now();
+    limiter.pause(Duration::from_secs(2));
+    limiter.acquire().await;
+    assert!(t1.elapsed() >= Duration::from_secs(2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_after_retries() {
+    let q = ThrottleRetry::new(Flaky::new(usize::MAX, None), RateLimiter::new(100, 1), 3);
+    assert!(q.quote("A".into(), "B".into(), 1).await.is_err());
+    assert_eq!(q.inner().calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn no_route_is_not_retried() {
+    struct NoRoutes(AtomicUsize);
+    impl QuoteProvider for NoRoutes {
+        async fn quote(&self, im: String, om: String, _amount: u64) -> Result<Quote> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(NoRoute(format!("{} → {}", im, om)).into())
+        }
+    }
+
+    let limiter = RateLimiter::new(1, 1);
+    let q = ThrottleRetry::new(NoRoutes(AtomicUsize::new(0)), limiter, 3);
+    let t0 = Instant::now();
+    let e = q.quote("A".into(), "B".into(), 1).await.unwrap_err();
+    assert!(e.downcast_ref::<NoRoute>().is_some());
+    assert_eq!(q.inner().0.load(Ordering::SeqCst), 1);
+    // 只取了一个令牌，也没有退避
+    assert_eq!(t0.elapsed(), Duration::ZERO);
+}
+
+#[test]
+fn backoff_is_bounded() {
+    let b = Backoff { base_ms: 100, max_ms: 1_000 };
+    for attempt in 0..10 {
+        let cap = (100u64 << attempt).min(1_000);
+        for _ in 0..50 {
+            assert!(b.delay(attempt) <= Duration::from_millis(cap));
+        }
+    }
+}
+
+#[test]
+fn parses_retry_after_header() {
+    assert_eq!(RateLimited::from_header(Some(" 7 ")).retry_after, Some(Duration::from_secs(7)));
+    assert_eq!(RateLimited::from_header(Some("Wed, 21 Oct 2015 07:28:00 GMT")).retry_after, None);
+    assert_eq!(RateLimited::from_header(None).retry_after, None);
+}
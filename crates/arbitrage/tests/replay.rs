@@ -0,0 +1,93 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+
+/* ---- Mock 报价器：out = amount * 2，amount == 13 时报错 ---- */
+struct Doubler;
+impl QuoteProvider for Doubler {
+    async fn quote(&self, im: String, om: String, amount: u64) -> Result<Quote> {
+        if amount == 13 { anyhow::bail!("unlucky"); }
+        let mut q = Quote::simple(im.clone(), om.clone(), amount, amount * 2);
+        q.route = vec![RouteLeg { amm_label: "Mock".into(), input_mint: im, output_mint: om, in_amount: amount, out_amount: amount * 2, fee_amount: amount / 100, percent: 100, ..Default::default() }];
+        Ok(q)
+    }
+}
+
+async fn record(path: &std::path::Path) {
+    let rec = Recorder::create(Doubler, path).unwrap();
+    rec.quote("SOL".into(), "USDC".into(), 1_000).await.unwrap();
+    rec.quote("SOL".into(), "USDC".into(), 3_000).await.unwrap();
+    assert!(rec.quote("SOL".into(), "USDC".into(), 13).await.is_err());
+}
+
+#[tokio::test]
+async fn recorded_ndjson_has_one_line_per_request() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("q.ndjson");
+    record(&path).await;
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let recs: Vec<QuoteRecord> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(recs.len(), 3);
+    assert_eq!(recs[0].out_amount, Some(2_000));
+    assert_eq!(recs[2].out_amount, None);
+    assert!(recs[2].error.as_deref().unwrap().contains("unlucky"));
+}
+
+#[tokio::test]
+async fn exact_replay_is_identical() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("q.ndjson");
+    record(&path).await;
+
+    let live = Doubler.quote("SOL".into(), "USDC".into(), 3_000).await.unwrap();
+    let replay = ReplayQuoter::load(&path, ReplayMode::Exact).unwrap();
+    let q = replay.quote("SOL".into(), "USDC".into(), 3_000).await.unwrap();
+    assert_eq!((q.out_amount, &q.route), (live.out_amount, &live.route));
+
+    // 录到的错误原样回放；没录到的报错
+    assert!(replay.quote("SOL".into(), "USDC".into(), 13).await.unwrap_err().to_string().contains("unlucky"));
+    assert!(replay.quote("SOL".into(), "USDC".into(), 2_000).await.is_err());
+    assert!(replay.quote("USDC".into(), "SOL".into(), 1_000).await.is_err());
+}
+
+#[tokio::test]
+async fn nearest_replay_scales_linearly() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("q.ndjson");
+    record(&path).await;
+
+    let replay = ReplayQuoter::load(&path, ReplayMode::Nearest).unwrap();
+    // 最近的是 3_000（失败记录 13 不参与）
+    let q = replay.quote("SOL".into(), "USDC".into(), 2_100).await.unwrap();
+    assert_eq!((q.in_amount, q.out_amount), (2_100, 4_200));
+    assert_eq!(q.route[0].fee_amount, 21);
+    let q = replay.quote("SOL".into(), "USDC".into(), 10).await.unwrap();
+    assert_eq!(q.out_amount, 20);
+}
+
+#[test]
+fn later_records_override_earlier() {
+    let rec = |out: u64| QuoteRecord {
+        ts_ms: out, input_mint: "A".into(), output_mint: "B".into(), amount: 1,
+        out_amount: Some(out), latency_ms: 0, error: None, quote: None,
+    };
+    let replay = ReplayQuoter::from_records([rec(5), rec(7)], ReplayMode::Exact);
+    assert_eq!(replay.len(), 1);
+    let q = futures::executor::block_on(replay.quote("A".into(), "B".into(), 1)).unwrap();
+    assert_eq!(q.out_amount, 7);
+}
+
+#[test]
+fn nearest_uses_records_without_quote() {
+    let rec = |amount: u64, out: Option<u64>, error: Option<&str>| QuoteRecord {
+        ts_ms: 0, input_mint: "A".into(), output_mint: "B".into(), amount,
+        out_amount: out, latency_ms: 0, error: error.map(Into::into), quote: None,
+    };
+    // 只有 out_amount 的旧格式记录；失败记录不参与
+    let replay = ReplayQuoter::from_records(
+        [rec(1_000, Some(1_500), None), rec(1_900, None, Some("boom"))],
+        ReplayMode::Nearest,
+    );
+    let q = futures::executor::block_on(replay.quote("A".into(), "B".into(), 2_000)).unwrap();
+    assert_eq!((q.in_amount, q.out_amount), (2_000, 3_000));
+}
@@ -0,0 +1,67 @@
+use arbitrage::prelude::*;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/* ---- Mock：恒定乘积池（x*y=k，不收费）---- */
+struct CpmmQuoter { pools: HashMap<(String,String), (u128,u128)> }
+impl CpmmQuoter {
+    fn new() -> Self { Self { pools: HashMap::new() } }
+    fn pool(mut self, a:&str, b:&str, ra:u128, rb:u128) -> Self {
+        self.pools.insert((a.into(), b.into()), (ra, rb)); self
+    }
+}
+#[allow(async_fn_in_trait)]
+impl QuoteProvider for CpmmQuoter {
+    async fn quote(&self, im:String, om:String, amount:u64) -> Result<Quote> {
+        let (x, y) = *self.pools.get(&(im.clone(), om.clone())).ok_or_else(|| anyhow::anyhow!("no pool"))?;
+        let dx = amount as u128;
+        Ok(Quote::simple(im, om, amount, (y * dx / (x + dx)) as u64))
+    }
+}
+
+fn cycle() -> Vec<String> { vec!["SOL".into(), "A".into(), "SOL".into()] }
+
+// SOL→A 价格 2.0，A→SOL 价格 0.55：小额有利，大额被价格冲击吃掉
+fn quoter() -> CpmmQuoter {
+    CpmmQuoter::new()
+        .pool("SOL", "A", 1_000_000_000_000, 2_000_000_000_000)
+        .pool("A", "SOL", 2_000_000_000_000, 1_100_000_000_000)
+}
+
+#[tokio::test]
+async fn finds_interior_optimum() {
+    let cfg = SizeSearch { min_in: 1_000_000, max_in: 500_000_000_000, probes: 24, cost_bps: Bps::ZERO, fixed_cost: 0 };
+    let res = optimize_size(&quoter(), &cycle(), &cfg).await.unwrap();
+
+    // 解析最优约 24.4 SOL；两端都明显更差
+    let best = res.best;
+    assert!(best.amount_in > 15_000_000_000 && best.amount_in < 35_000_000_000, "best={:?}", best);
+    assert!(best.profit > 0);
+    assert!(res.curve.len() <= 24);
+    assert!(res.curve.windows(2).all(|w| w[0].amount_in < w[1].amount_in));
+    assert!(res.curve.iter().all(|p| p.profit <= best.profit));
+}
+
+#[tokio::test]
+async fn fixed_cost_kills_small_sizes() {
+    let cfg = SizeSearch { min_in: 1_000, max_in: 10_000, probes: 8, cost_bps: Bps::ZERO, fixed_cost: 5_000 };
+    let res = optimize_size(&quoter(), &cycle(), &cfg).await.unwrap();
+    assert!(res.best.profit < 0);
+    assert_eq!(res.best.amount_in, 10_000);
+}
+
+#[tokio::test]
+async fn failed_path_returns_none() {
+    let cfg = SizeSearch { min_in: 1, max_in: 100, probes: 4, cost_bps: Bps::ZERO, fixed_cost: 0 };
+    let path = vec!["SOL".to_string(), "B".into(), "SOL".into()];
+    assert!(optimize_size(&quoter(), &path, &cfg).await.is_none());
+}
+
+#[tokio::test]
+async fn fractional_cost_bps_is_charged() {
+    let at = |cost_bps| SizeSearch { min_in: 1_000_000_000, max_in: 1_000_000_000, probes: 1, cost_bps, fixed_cost: 0 };
+    let free = optimize_size(&quoter(), &cycle(), &at(Bps::ZERO)).await.unwrap().best;
+    let half = optimize_size(&quoter(), &cycle(), &at("0.5".parse().unwrap())).await.unwrap().best;
+    // 0.5 bps × 1 SOL = 50_000 lamports，不能被取整成 0
+    assert_eq!(free.profit - half.profit, 50_000);
+}
@@ -10,12 +10,15 @@ impl MapQuoter {
 }
 #[allow(async_fn_in_trait)]
 impl QuoteProvider for MapQuoter {
-    async fn quote(&self, im:String, om:String, amount:u64) -> Result<u64> {
-        let k = *self.m.get(&(im,om)).unwrap_or(&1.0);
-        Ok(((amount as f64)*k).round() as u64)
+    async fn quote(&self, im:String, om:String, amount:u64) -> Result<Quote> {
+        let k = *self.m.get(&(im.clone(),om.clone())).unwrap_or(&1.0);
+        Ok(Quote::simple(im, om, amount, ((amount as f64)*k).round() as u64))
     }
 }
 
+/* 1 个 base（按 base 精度换算） */
+fn one() -> Amount { Amount::from_raw(1, 0) }
+
 /* ---- Mock Resolver ---- */
 struct DummyResolver;
 impl MintResolver for DummyResolver {
@@ -46,11 +49,11 @@ async fn two_hop_smoke() {
         .set(usdc, sol,  0.51);
 
     let mids = vec!["USDC".to_string()];
-    let rows = evaluate_2hop(&resolver, &quoter, "SOL", &mids, 1.0, i32::MIN, 4).await;
+    let rows = evaluate_2hop(&resolver, &quoter, "SOL", &mids, one(), Bps::MIN, 4).await;
 
     assert_eq!(rows.len(), 1);
-    assert!(rows[0].end > 1.0);
-    assert!(rows[0].delta_bps > 190.0);
+    assert!(rows[0].end.raw() > 1_000_000_000);
+    assert!(rows[0].delta_bps > Bps::from_int(190));
     assert_eq!(rows[0].path, "SOL → USDC → SOL");
 }
 
@@ -68,7 +71,7 @@ async fn three_hop_smoke() {
         .set(b, sol, 0.8);
 
     let mids = vec!["A".to_string(), "B".to_string()];
-    let rows = evaluate_3hop(&resolver, &quoter, "SOL", &mids, 1.0, 4, false).await;
+    let rows = evaluate_3hop(&resolver, &quoter, "SOL", &mids, one(), 4, false).await;
 
     assert!(!rows.is_empty());
     assert!(rows.iter().any(|r| r.path == "SOL → A → B → SOL"));
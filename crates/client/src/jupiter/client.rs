@@ -0,0 +1,143 @@
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use arbitrage::prelude::RateLimited;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use utils::{AppError, AppResult};
+use super::models::{parse_response, QuoteResponse};
+use super::swap::{SwapRequest, SwapResponse};
+use super::swap_instructions::SwapInstructionsResponse;
+
+/// quote / swap / swap-instructions 的默认地址
+pub const DEFAULT_BASE_URL: &str = "https://quote-api.jup.ag/v6";
+/// 代币列表的默认地址
+pub const DEFAULT_TOKENS_URL: &str = "https://lite-api.jup.ag/tokens/v1";
+
+/// Jupiter 接口配置
+#[derive(Debug, Clone)]
+pub struct JupiterConfig {
+    /// 如 https://quote-api.jup.ag/v6，或本地 mock 的 http://127.0.0.1:8080
+    pub base_url: String,
+    pub tokens_url: String,
+    /// 付费端点的 API key（以 x-api-key 头发送）
+    pub api_key: Option<String>,
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub user_agent: String,
+}
+
+impl Default for JupiterConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.into(),
+            tokens_url: DEFAULT_TOKENS_URL.into(),
+            api_key: None,
+            timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(3),
+            user_agent: concat!("solana-arb/", env!("CARGO_PKG_VERSION")).into(),
+        }
+    }
+}
+
+impl JupiterConfig {
+    /// 默认配置，按环境变量覆盖：JUPITER_BASE_URL / JUPITER_TOKENS_URL / JUPITER_API_KEY / JUPITER_TIMEOUT_MS
+    pub fn from_env() -> Self {
+        let env = |k: &str| std::env::var(k).ok().filter(|v| !v.trim().is_empty());
+        let mut cfg = Self::default();
+        if let Some(v) = env("JUPITER_BASE_URL") { cfg.base_url = v; }
+        if let Some(v) = env("JUPITER_TOKENS_URL") { cfg.tokens_url = v; }
+        cfg.api_key = env("JUPITER_API_KEY");
+        if let Some(ms) = env("JUPITER_TIMEOUT_MS").and_then(|v| v.parse().ok()) {
+            cfg.timeout = Duration::from_millis(ms);
+        }
+        cfg
+    }
+}
+
+/// 所有 Jupiter 请求的唯一出口：共享连接池，统一地址、鉴权、超时和 UA
+#[derive(Debug, Clone)]
+pub struct JupiterClient {
+    http: reqwest::Client,
+    cfg: Arc<JupiterConfig>,
+}
+
+impl JupiterClient {
+    pub fn new(cfg: JupiterConfig) -> AppResult<Self> {
+        let mut headers = HeaderMap::new();
+        if let Some(key) = &cfg.api_key {
+            let v = HeaderValue::from_str(key).map_err(|e| AppError::Custom(format!("无效的 API key: {}", e)))?;
+            headers.insert("x-api-key", v);
+        }
+        let http = reqwest::Client::builder()
+            .use_rustls_tls()
+            .default_headers(headers)
+            .user_agent(cfg.user_agent.clone())
+            .timeout(cfg.timeout)
+            .connect_timeout(cfg.connect_timeout)
+            .build()?;
+        Ok(Self { http, cfg: Arc::new(cfg) })
+    }
+
+    /// 进程内共享实例（配置来自环境变量），供自由函数使用；配置有误（如 API key 含非法字符）时每次调用都报错
+    pub fn shared() -> AppResult<&'static JupiterClient> {
+        static SHARED: OnceLock<Result<JupiterClient, String>> = OnceLock::new();
+        SHARED
+            .get_or_init(|| JupiterClient::new(JupiterConfig::from_env()).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| AppError::Custom(format!("Jupiter 客户端初始化失败: {}", e)))
+    }
+
+    pub fn config(&self) -> &JupiterConfig {
+        &self.cfg
+    }
+
+    fn url(base: &str, path: &str) -> String {
+        format!("{}/{}", base.trim_end_matches('/'), path)
+    }
+
+    /// GET /quote
+    pub async fn quote(&self, input_mint: &str, output_mint: &str, amount: u64, slippage_bps: u16) -> AppResult<QuoteResponse> {
+        let req = self.http.get(Self::url(&self.cfg.base_url, "quote")).query(&[
+            ("inputMint", input_mint),
+            ("outputMint", output_mint),
+            ("amount", &amount.to_string()),
+            ("slippageBps", &slippage_bps.to_string()),
+        ]);
+        send(req).await
+    }
+
+    /// POST /swap
+    pub async fn swap(&self, req: &SwapRequest) -> AppResult<SwapResponse> {
+        send(self.http.post(Self::url(&self.cfg.base_url, "swap")).json(req)).await
+    }
+
+    /// POST /swap-instructions
+    pub async fn swap_instructions(&self, req: &SwapRequest) -> AppResult<SwapInstructionsResponse> {
+        send(self.http.post(Self::url(&self.cfg.base_url, "swap-instructions")).json(req)).await
+    }
+
+    /// 可交易 mint 列表（接口直接返回 `Vec<String>`）
+    pub async fn tradable_mints(&self) -> AppResult<Vec<String>> {
+        send(self.http.get(Self::url(&self.cfg.tokens_url, "mints/tradable"))).await
+    }
+}
+
+/// 发请求并解析：429 → RateLimited；非 2xx 且不是 Jupiter 错误体 → External
+async fn send<T: DeserializeOwned>(req: RequestBuilder) -> AppResult<T> {
+    let res = req.send().await?;
+    let status = res.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let header = res.headers().get(RETRY_AFTER).and_then(|v| v.to_str().ok());
+        let RateLimited { retry_after } = RateLimited::from_header(header);
+        return Err(AppError::RateLimited { retry_after });
+    }
+
+    let text = res.text().await?;
+    match parse_response(&text) {
+        Err(AppError::ParseError(_)) if !status.is_success() => {
+            Err(AppError::External(format!("HTTP {}: {}", status, text)))
+        }
+        r => r,
+    }
+}
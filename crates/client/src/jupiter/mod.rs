@@ -1,5 +1,8 @@
+pub mod client;
+pub mod models;
 pub mod quote;
 pub mod swap;
+pub mod swap_instructions;
 pub mod arbitrage;
 pub mod quote_chain;
 pub mod token_list;
\ No newline at end of file
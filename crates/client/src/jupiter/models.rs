@@ -0,0 +1,205 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use arbitrage::prelude::{Quote, RouteLeg};
+use utils::{AppError, AppResult};
+
+/// v6 接口里的数量都是十进制字符串
+pub mod u64_str {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &u64, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(v)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StrOrNum { S(String), N(u64) }
+        match StrOrNum::deserialize(d)? {
+            StrOrNum::S(s) => s.parse().map_err(de::Error::custom),
+            StrOrNum::N(n) => Ok(n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SwapMode {
+    #[default]
+    ExactIn,
+    ExactOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformFee {
+    #[serde(with = "u64_str")]
+    pub amount: u64,
+    pub fee_bps: u16,
+}
+
+/// routePlan[i].swapInfo：某个 AMM 上的一段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInfo {
+    pub amm_key: String,
+    #[serde(default)]
+    pub label: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    #[serde(with = "u64_str")]
+    pub in_amount: u64,
+    #[serde(with = "u64_str")]
+    pub out_amount: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_amount: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_mint: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlanStep {
+    pub swap_info: SwapInfo,
+    /// 该段占整笔的百分比
+    pub percent: u8,
+}
+
+/// GET /quote 的响应；/swap、/swap-instructions 要求原样回传，未建模的字段保存在 extra 里
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+    pub input_mint: String,
+    #[serde(with = "u64_str")]
+    pub in_amount: u64,
+    pub output_mint: String,
+    #[serde(with = "u64_str")]
+    pub out_amount: u64,
+    /// 扣除滑点后的最少到手量（ExactOut 时为最多付出量）
+    #[serde(with = "u64_str")]
+    pub other_amount_threshold: u64,
+    pub swap_mode: SwapMode,
+    pub slippage_bps: u16,
+    #[serde(default)]
+    pub platform_fee: Option<PlatformFee>,
+    /// 百分比字符串，"0.12" = 0.12%
+    pub price_impact_pct: String,
+    pub route_plan: Vec<RoutePlanStep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_slot: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_taken: Option<f64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl QuoteResponse {
+    pub fn price_impact(&self) -> f64 {
+        self.price_impact_pct.parse().unwrap_or(0.0)
+    }
+
+    /// 路由标签，多段用 “+” 连接
+    pub fn label(&self) -> String {
+        self.route_plan.iter().map(|s| s.swap_info.label.as_str()).collect::<Vec<_>>().join("+")
+    }
+}
+
+/// 接口错误码（errorCode），未列出的保留原文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NoRoutesFound,
+    CouldNotFindAnyRoute,
+    RoutePlanDoesNotConsumeAllTheAmount,
+    CircularArbitrageIsDisabled,
+    TokenNotTradable,
+    MarketNotFound,
+    InvalidInputMint,
+    InvalidOutputMint,
+    AmountTooSmall,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl ErrorCode {
+    /// 没有可用路由（对报价方来说是“无报价”，不算接口故障）
+    pub fn is_no_route(&self) -> bool {
+        matches!(self, Self::NoRoutesFound | Self::CouldNotFindAnyRoute | Self::RoutePlanDoesNotConsumeAllTheAmount)
+    }
+
+    /// 请求本身报不出价（代币不可交易、mint 无效、数量太小等），接口是正常的
+    pub fn is_rejection(&self) -> bool {
+        matches!(
+            self,
+            Self::CircularArbitrageIsDisabled
+                | Self::TokenNotTradable
+                | Self::MarketNotFound
+                | Self::InvalidInputMint
+                | Self::InvalidOutputMint
+                | Self::AmountTooSmall
+        )
+    }
+}
+
+/// 接口错误体：`{"error": "...", "errorCode": "..."}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiError {
+    pub error: String,
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
+}
+
+/// 解析接口返回：先识别错误体（含 error 字段的对象），再按 T 解析；无路由映射为 NoQuoteFound，请求被拒映射为 QuoteRejected
+pub fn parse_response<T: DeserializeOwned>(text: &str) -> AppResult<T> {
+    let parse_err = |e: serde_json::Error| AppError::ParseError(format!("❌ Jupiter JSON 解析失败: {}", e));
+    let v: Value = serde_json::from_str(text).map_err(parse_err)?;
+    if v.get("error").is_some() {
+        if let Ok(e) = serde_json::from_value::<ApiError>(v.clone()) {
+            return Err(match e.error_code {
+                Some(c) if c.is_no_route() => AppError::NoQuoteFound,
+                Some(c) if c.is_rejection() => AppError::QuoteRejected(format!("[{:?}] {}", c, e.error)),
+                Some(c) => AppError::External(format!("Jupiter 返回错误 [{:?}]: {}", c, e.error)),
+                None => AppError::External(format!("Jupiter 返回错误: {}", e.error)),
+            });
+        }
+    }
+    serde_json::from_value(v).map_err(parse_err)
+}
+
+/* ---------------- 转成策略层报价 ---------------- */
+
+fn fee_amount(s: &Option<String>) -> u64 {
+    s.as_deref().and_then(|s| s.parse().ok()).unwrap_or(0)
+}
+
+impl From<&SwapInfo> for RouteLeg {
+    fn from(s: &SwapInfo) -> Self {
+        RouteLeg {
+            amm_label: s.label.clone(),
+            amm_key: s.amm_key.clone(),
+            input_mint: s.input_mint.clone(),
+            output_mint: s.output_mint.clone(),
+            in_amount: s.in_amount,
+            out_amount: s.out_amount,
+            fee_amount: fee_amount(&s.fee_amount),
+            fee_mint: s.fee_mint.clone().unwrap_or_default(),
+            percent: 100,
+        }
+    }
+}
+
+impl From<&QuoteResponse> for Quote {
+    fn from(r: &QuoteResponse) -> Self {
+        let mut q = Quote::simple(r.input_mint.clone(), r.output_mint.clone(), r.in_amount, r.out_amount);
+        q.min_out_amount = r.other_amount_threshold;
+        q.price_impact_pct = r.price_impact();
+        q.slot = r.context_slot;
+        q.route = r
+            .route_plan
+            .iter()
+            .map(|st| RouteLeg { percent: st.percent, ..RouteLeg::from(&st.swap_info) })
+            .collect();
+        q
+    }
+}
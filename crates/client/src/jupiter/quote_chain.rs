@@ -1,4 +1,4 @@
-use crate::jupiter::quote::fetch_jupiter_quote;
+use crate::jupiter::quote::fetch_quote_response;
 use utils::{resolve_mint_address, AppError, AppResult};
 
 #[derive(Debug)]
@@ -43,22 +43,21 @@ pub async fn fetch_chain_quotes(
             .ok_or_else(|| AppError::Custom(format!("❌ 无法识别币种: {}", to)))?;
 
         // 传递 slippage_bps
-        let quote = fetch_jupiter_quote(
+        let quote = fetch_quote_response(
             input_mint.as_str(),
             output_mint.as_str(),
             current_amount,
             slippage_bps,
         ).await?;
 
-        let out_amount: u64 = quote.out_amount.parse()
-            .map_err(|_| AppError::Custom(format!("❌ 无法解析 out_amount: {}", quote.out_amount)))?;
+        let out_amount = quote.out_amount;
 
         steps.push(ChainQuoteStep {
             from,
             to,
             input_amount: current_amount,
             output_amount: out_amount,
-            label: quote.label,
+            label: quote.label(),
         });
 
         current_amount = out_amount;
@@ -1,56 +1,117 @@
-use reqwest::Client;
-use serde_json::Value;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{json, Value};
+use solana_sdk::transaction::VersionedTransaction;
 use tracing::{info, error};
 use utils::{AppError, AppResult};
+use super::client::JupiterClient;
+use super::models::QuoteResponse;
 
-/// Jupiter swap 接口：返回交易体 base64（用于执行 swap）
-pub async fn fetch_jupiter_swap_tx_safe(
-    input_mint: &str,
-    output_mint: &str,
-    amount: f64,
-    input_decimals: u8,
-    user_pubkey: &str,
-) -> AppResult<String> {
-    if amount <= 0.0 {
-        return Err(AppError::Custom("❌ amount 必须大于 0".into()));
+/// prioritizationFeeLamports：`"auto"` / 固定 lamports / 按档位估算并封顶
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrioritizationFee {
+    Auto,
+    Lamports(u64),
+    /// priorityLevel: "medium" | "high" | "veryHigh"
+    Level { level: String, max_lamports: u64 },
+}
+
+impl Serialize for PrioritizationFee {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Auto => s.serialize_str("auto"),
+            Self::Lamports(n) => s.serialize_u64(*n),
+            Self::Level { level, max_lamports } => json!({
+                "priorityLevelWithMaxLamports": { "maxLamports": max_lamports, "priorityLevel": level }
+            })
+            .serialize(s),
+        }
     }
+}
 
-    let amount_u64 = (amount * 10f64.powi(input_decimals as i32)) as u64;
+/// POST /swap 请求体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapRequest {
+    pub user_public_key: String,
+    pub quote_response: QuoteResponse,
+    pub wrap_and_unwrap_sol: bool,
+    /// 让 Jupiter 按模拟结果设置 CU 上限
+    pub dynamic_compute_unit_limit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prioritization_fee_lamports: Option<PrioritizationFee>,
+    /// 输出代币的接收账户（默认用户 ATA）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_token_account: Option<String>,
+}
 
-    info!(
-        "💱 请求 Jupiter Swap: {} {} -> {} by {}",
-        amount, input_mint, output_mint, user_pubkey
-    );
+impl SwapRequest {
+    pub fn new(user_public_key: impl Into<String>, quote_response: QuoteResponse) -> Self {
+        Self {
+            user_public_key: user_public_key.into(),
+            quote_response,
+            wrap_and_unwrap_sol: true,
+            dynamic_compute_unit_limit: true,
+            prioritization_fee_lamports: None,
+            destination_token_account: None,
+        }
+    }
+
+    pub fn priority_fee(mut self, fee: PrioritizationFee) -> Self {
+        self.prioritization_fee_lamports = Some(fee);
+        self
+    }
 
-    let url = "https://quote-api.jup.ag/v6/swap";
-    let body = serde_json::json!({
-        "inputMint": input_mint,
-        "outputMint": output_mint,
-        "amount": amount_u64.to_string(),
-        "slippageBps": 50,
-        "userPublicKey": user_pubkey,
-        "wrapUnwrapSol": true,
-        "feeBps": 0
-    });
-
-    let client = Client::new();
-    let resp = client.post(url).json(&body).send().await?;
-    let text = resp.text().await?;
-
-    if text.contains("\"error\"") {
-        error!("🚫 Jupiter swap 请求失败，原始返回: {}", text);
-        return Err(AppError::External(format!("Jupiter 返回错误: {}", text)));
+    pub fn wrap_and_unwrap_sol(mut self, on: bool) -> Self {
+        self.wrap_and_unwrap_sol = on;
+        self
     }
 
-    let json: Value = serde_json::from_str(&text)
-        .map_err(|e| AppError::ParseError(format!("❌ swap JSON 解析失败: {}", e)))?;
+    pub fn destination_token_account(mut self, account: impl Into<String>) -> Self {
+        self.destination_token_account = Some(account.into());
+        self
+    }
+}
 
-    info!("🔃 Jupiter swap 响应 JSON: {}", json);
+/// POST /swap 响应
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapResponse {
+    /// base64(bincode(VersionedTransaction))，未签名
+    pub swap_transaction: String,
+    pub last_valid_block_height: u64,
+    #[serde(default)]
+    pub prioritization_fee_lamports: Option<u64>,
+    #[serde(default)]
+    pub compute_unit_limit: Option<u32>,
+    #[serde(default)]
+    pub simulation_error: Option<Value>,
+}
+
+impl SwapResponse {
+    /// 解码交易体（签名位为空，需调用方签名）
+    pub fn transaction(&self) -> AppResult<VersionedTransaction> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.swap_transaction)
+            .map_err(|e| AppError::ParseError(format!("❌ swapTransaction base64 解码失败: {}", e)))?;
+        bincode::deserialize(&bytes)
+            .map_err(|e| AppError::ParseError(format!("❌ swapTransaction 反序列化失败: {}", e)))
+    }
+}
 
-    let tx = json
-        .get("swapTransaction")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| AppError::Custom("❌ Jupiter 未返回 swapTransaction 字段".into()))?;
+/// Jupiter swap 接口：用报价换取待签名交易
+pub async fn fetch_jupiter_swap(req: &SwapRequest) -> AppResult<SwapResponse> {
+    let q = &req.quote_response;
+    info!(
+        "💱 请求 Jupiter Swap: {} {} -> {} by {}",
+        q.in_amount, q.input_mint, q.output_mint, req.user_public_key
+    );
 
-    Ok(tx.to_string())
+    let swap = JupiterClient::shared()?.swap(req).await.inspect_err(|e| {
+        error!("🚫 Jupiter swap 请求失败: {}", e);
+    })?;
+    if let Some(e) = &swap.simulation_error {
+        return Err(AppError::External(format!("Jupiter 模拟失败: {}", e)));
+    }
+    Ok(swap)
 }
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+use base64::Engine as _;
+use serde::Deserialize;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use tracing::{info, error};
+use utils::{AppError, AppResult};
+use super::client::JupiterClient;
+use super::swap::SwapRequest;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// 接口里的单条指令：账户为 base58，data 为 base64
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupInstruction {
+    pub program_id: String,
+    pub accounts: Vec<JupAccountMeta>,
+    pub data: String,
+}
+
+impl JupInstruction {
+    pub fn to_instruction(&self) -> AppResult<Instruction> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|a| Ok(AccountMeta { pubkey: pubkey(&a.pubkey)?, is_signer: a.is_signer, is_writable: a.is_writable }))
+            .collect::<AppResult<Vec<_>>>()?;
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|e| AppError::ParseError(format!("❌ 指令 data base64 解码失败: {}", e)))?;
+        Ok(Instruction { program_id: pubkey(&self.program_id)?, accounts, data })
+    }
+}
+
+/// POST /swap-instructions 响应
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInstructionsResponse {
+    #[serde(default)]
+    pub token_ledger_instruction: Option<JupInstruction>,
+    #[serde(default)]
+    pub compute_budget_instructions: Vec<JupInstruction>,
+    #[serde(default)]
+    pub setup_instructions: Vec<JupInstruction>,
+    pub swap_instruction: JupInstruction,
+    #[serde(default)]
+    pub cleanup_instruction: Option<JupInstruction>,
+    #[serde(default)]
+    pub other_instructions: Vec<JupInstruction>,
+    #[serde(default)]
+    pub address_lookup_table_addresses: Vec<String>,
+    #[serde(default)]
+    pub prioritization_fee_lamports: Option<u64>,
+}
+
+impl SwapInstructionsResponse {
+    pub fn lookup_tables(&self) -> AppResult<Vec<Pubkey>> {
+        self.address_lookup_table_addresses.iter().map(|s| pubkey(s)).collect()
+    }
+
+    /// tokenLedger（只在 useTokenLedger 时出现）在前，其余 setup 指令在后
+    pub fn setup(&self) -> AppResult<Vec<Instruction>> {
+        self.token_ledger_instruction.iter().chain(&self.setup_instructions).map(JupInstruction::to_instruction).collect()
+    }
+}
+
+fn pubkey(s: &str) -> AppResult<Pubkey> {
+    Pubkey::from_str(s).map_err(|e| AppError::ParseError(format!("❌ 无效公钥 {}: {}", s, e)))
+}
+
+/// Jupiter swap-instructions 接口：返回拆开的指令，用于把多跳拼进一笔交易
+pub async fn fetch_jupiter_swap_instructions(req: &SwapRequest) -> AppResult<SwapInstructionsResponse> {
+    let q = &req.quote_response;
+    info!(
+        "🧩 请求 Jupiter swap-instructions: {} {} -> {} by {}",
+        q.in_amount, q.input_mint, q.output_mint, req.user_public_key
+    );
+
+    JupiterClient::shared()?.swap_instructions(req).await.inspect_err(|e| {
+        error!("🚫 Jupiter swap-instructions 请求失败: {}", e);
+    })
+}
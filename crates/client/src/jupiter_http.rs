@@ -1,22 +1,23 @@
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use async_trait::async_trait;
-use arbitrage::prelude::QuoteProvider as StratQuoteProvider; // 策略层接口
-use crate::jupiter::quote::fetch_jupiter_quote;              // 你刚写的HTTP函数
-use crate::types::QuoteInfo;
+use arbitrage::prelude::{Quote, NoRoute, RateLimited, QuoteProvider as StratQuoteProvider}; // 策略层接口
+use utils::AppError;
+use crate::jupiter::client::{JupiterClient, JupiterConfig};
 
 #[derive(Clone)]
 pub struct JupiterHttp {
-    pub client: reqwest::Client,
-    pub base_url: String,      // e.g. https://quote-api.jup.ag/v6
+    pub client: JupiterClient,
     pub slippage_bps: u16,     // 50 = 0.5%
 }
 impl JupiterHttp {
-    pub fn new(base_url: impl Into<String>, slippage_bps: u16) -> Self {
-        let client = reqwest::Client::builder()
-            .use_rustls_tls()
-            .timeout(std::time::Duration::from_secs(5))
-            .build().unwrap();
-        Self { client, base_url: base_url.into(), slippage_bps }
+    /// base_url 如 https://quote-api.jup.ag/v6；其余配置取自环境变量
+    pub fn new(base_url: impl Into<String>, slippage_bps: u16) -> Result<Self> {
+        let cfg = JupiterConfig { base_url: base_url.into(), ..JupiterConfig::from_env() };
+        Ok(Self::with_client(JupiterClient::new(cfg)?, slippage_bps))
+    }
+
+    pub fn with_client(client: JupiterClient, slippage_bps: u16) -> Self {
+        Self { client, slippage_bps }
     }
 }
 
@@ -32,27 +33,32 @@ pub trait HttpQuoteProvider: Send + Sync {
     async fn quote(&self, req: QuoteReq) -> Result<QuoteResp>;
 }
 
+impl JupiterHttp {
+    /// 完整报价；429 转成 RateLimited 交给限速中间件
+    async fn typed_quote(&self, input: &str, output: &str, amount: u64) -> Result<Quote> {
+        match self.client.quote(input, output, amount, self.slippage_bps).await {
+            Ok(r) => Ok(Quote::from(&r)),
+            Err(AppError::RateLimited { retry_after }) => Err(RateLimited { retry_after }.into()),
+            // 无路由/被拒：不算报价源故障（熔断不计）
+            Err(e @ (AppError::NoQuoteFound | AppError::QuoteRejected(_))) => Err(NoRoute(e.to_string()).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
 #[async_trait]
 impl HttpQuoteProvider for JupiterHttp {
     async fn quote(&self, req: QuoteReq) -> Result<QuoteResp> {
-        // 复用你写的 fetch_jupiter_quote（返回 QuoteInfo，含字符串 out_amount）
-        let QuoteInfo { out_amount, label } = fetch_jupiter_quote(
-            &req.input_mint, &req.output_mint, req.amount, self.slippage_bps
-        ).await?;
-
-        let out = out_amount.parse::<u64>()
-            .map_err(|_| anyhow!("bad outAmount: {}", out_amount))?;
-
-        Ok(QuoteResp { out_amount: out, label })
+        let q = self.typed_quote(&req.input_mint, &req.output_mint, req.amount).await?;
+        Ok(QuoteResp { out_amount: q.out_amount, label: q.label() })
     }
 }
 
-/* ---------- 策略层 trait（适配为 u64） ---------- */
+/* ---------- 策略层 trait（适配为 Quote） ---------- */
 #[allow(async_fn_in_trait)]
 impl StratQuoteProvider for JupiterHttp {
-    async fn quote(&self, input: String, output: String, amount: u64) -> Result<u64> {
-        let r = QuoteReq { input_mint: input, output_mint: output, amount };
-        Ok(<JupiterHttp as HttpQuoteProvider>::quote(self, r).await?.out_amount)
+    async fn quote(&self, input: String, output: String, amount: u64) -> Result<Quote> {
+        self.typed_quote(&input, &output, amount).await
     }
 }
 
@@ -11,3 +11,9 @@ pub use jupiter::quote_chain::fetch_chain_quotes;
 pub use jupiter::token_list::fetch_supported_tokens;
 pub use types::QuoteInfo;
 pub use types::QuoteRoute;
+pub use jupiter::quote::fetch_quote_response;
+pub use jupiter::models::QuoteResponse;
+pub use jupiter::swap::{fetch_jupiter_swap, PrioritizationFee, SwapRequest, SwapResponse};
+pub use jupiter::swap_instructions::{fetch_jupiter_swap_instructions, JupInstruction, SwapInstructionsResponse};
+pub use jupiter::client::{JupiterClient, JupiterConfig};
+pub use jupiter_http::JupiterHttp;
@@ -0,0 +1,176 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use client::{JupiterClient, JupiterConfig, PrioritizationFee, SwapRequest};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use utils::AppError;
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// 收到的请求：请求行 + 小写请求头 + 请求体
+#[derive(Debug, Clone)]
+struct Seen {
+    line: String,
+    head: String,
+    body: String,
+}
+
+/* ---- Jupiter 替身：按路径返回 (状态行, 额外头, 响应体) ---- */
+async fn serve(route: fn(&str) -> (&'static str, &'static str, String)) -> (String, Arc<Mutex<Vec<Seen>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let log = seen.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let log = log.clone();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head_end, len) = loop {
+                    let n = sock.read(&mut chunk).await.unwrap();
+                    if n == 0 { return; }
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&buf[..i]).to_ascii_lowercase();
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                            .unwrap_or(0);
+                        break (i + 4, len);
+                    }
+                };
+                while buf.len() < head_end + len {
+                    let n = sock.read(&mut chunk).await.unwrap();
+                    if n == 0 { return; }
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let raw = String::from_utf8_lossy(&buf[..head_end]).to_string();
+                let line = raw.lines().next().unwrap_or_default().to_string();
+                let (status, extra, body) = route(&line);
+                log.lock().unwrap().push(Seen {
+                    line,
+                    head: raw.to_ascii_lowercase(),
+                    body: String::from_utf8_lossy(&buf[head_end..head_end + len]).to_string(),
+                });
+                let resp = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\n{}content-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    extra,
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+            });
+        }
+    });
+    (format!("http://{}", addr), seen)
+}
+
+fn jupiter(base: &str, api_key: Option<&str>) -> JupiterClient {
+    JupiterClient::new(JupiterConfig {
+        base_url: format!("{}/v6/", base),
+        tokens_url: format!("{}/tokens/v1", base),
+        api_key: api_key.map(Into::into),
+        timeout: Duration::from_secs(2),
+        user_agent: "arb-test/1".into(),
+        ..JupiterConfig::default()
+    })
+    .unwrap()
+}
+
+fn ok_routes(line: &str) -> (&'static str, &'static str, String) {
+    if line.starts_with("GET /v6/quote?") {
+        ("200 OK", "", fixture("quote_sol_usdc.json"))
+    } else if line.starts_with("POST /v6/swap ") {
+        ("200 OK", "", fixture("swap_response.json"))
+    } else if line.starts_with("GET /tokens/v1/mints/tradable") {
+        ("200 OK", "", r#"["So11111111111111111111111111111111111111112","EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"]"#.into())
+    } else {
+        ("404 Not Found", "", String::new())
+    }
+}
+
+/* ---- 所有请求都走配置的地址，并带上 API key 和 UA ---- */
+#[tokio::test]
+async fn requests_use_configured_endpoint_and_headers() {
+    let (base, seen) = serve(ok_routes).await;
+    let jup = jupiter(&base, Some("secret-key"));
+
+    let q = jup
+        .quote("So11111111111111111111111111111111111111112", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 1_000_000_000, 30)
+        .await
+        .unwrap();
+    assert_eq!(q.out_amount, 151_234_567);
+
+    let req = SwapRequest::new("GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB", q).priority_fee(PrioritizationFee::Auto);
+    let swap = jup.swap(&req).await.unwrap();
+    assert_eq!(swap.last_valid_block_height, 276_543_210);
+
+    let mints = jup.tradable_mints().await.unwrap();
+    assert_eq!(mints.len(), 2);
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 3);
+    assert!(seen[0].line.contains("amount=1000000000") && seen[0].line.contains("slippageBps=30"), "{}", seen[0].line);
+    assert!(seen.iter().all(|s| s.head.contains("x-api-key: secret-key")));
+    assert!(seen.iter().all(|s| s.head.contains("user-agent: arb-test/1")));
+
+    let body: serde_json::Value = serde_json::from_str(&seen[1].body).unwrap();
+    assert_eq!(body["prioritizationFeeLamports"], "auto");
+    assert_eq!(body["quoteResponse"]["contextSlot"], 298_765_432);
+}
+
+/* ---- 未配置 API key 时不发该头 ---- */
+#[tokio::test]
+async fn no_api_key_header_by_default() {
+    let (base, seen) = serve(ok_routes).await;
+    jupiter(&base, None).tradable_mints().await.unwrap();
+    assert!(!seen.lock().unwrap()[0].head.contains("x-api-key"));
+}
+
+/* ---- 429 带 Retry-After；其他非 2xx 转成 External / 错误码 ---- */
+#[tokio::test]
+async fn http_errors_are_classified() {
+    let (base, _) = serve(|line| {
+        if line.starts_with("GET /v6/quote?") {
+            ("429 Too Many Requests", "retry-after: 3\r\n", r#"{"message":"slow down"}"#.into())
+        } else if line.starts_with("POST /v6/swap-instructions") {
+            ("400 Bad Request", "", r#"{"error":"Could not find any route","errorCode":"COULD_NOT_FIND_ANY_ROUTE"}"#.into())
+        } else {
+            ("502 Bad Gateway", "", "<html>bad gateway</html>".into())
+        }
+    })
+    .await;
+    let jup = jupiter(&base, None);
+
+    match jup.quote("a", "b", 1, 50).await {
+        Err(AppError::RateLimited { retry_after }) => assert_eq!(retry_after, Some(Duration::from_secs(3))),
+        other => panic!("unexpected: {:?}", other.map(|q| q.out_amount)),
+    }
+
+    let q = serde_json::from_str(&fixture("quote_sol_usdc.json")).unwrap();
+    let req = SwapRequest::new("GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB", q);
+    assert!(matches!(jup.swap_instructions(&req).await, Err(AppError::NoQuoteFound)));
+
+    match jup.tradable_mints().await {
+        Err(AppError::External(msg)) => assert!(msg.contains("502"), "{msg}"),
+        other => panic!("unexpected: {:?}", other),
+    }
+}
+
+/* ---- 共享实例配置有误时报错而不是 panic ---- */
+#[tokio::test]
+async fn bad_api_key_is_an_error_not_a_panic() {
+    // 本文件只有这里读环境变量
+    std::env::set_var("JUPITER_API_KEY", "bad\nkey");
+    match JupiterClient::shared() {
+        Err(AppError::Custom(msg)) => assert!(msg.contains("API key"), "{msg}"),
+        other => panic!("unexpected: {:?}", other.map(|c| c.config().base_url.clone())),
+    }
+    assert!(client::fetch_supported_tokens().await.is_err());
+}
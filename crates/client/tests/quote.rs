@@ -0,0 +1,71 @@
+use arbitrage::prelude::Quote;
+use client::jupiter::models::{parse_response, ApiError, ErrorCode, QuoteResponse};
+use utils::{AppError, AppResult};
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn quote(name: &str) -> AppResult<QuoteResponse> {
+    parse_response(&fixture(name))
+}
+
+/* ---- 拆单路由：两段各占一部分 ---- */
+#[test]
+fn split_route_fixture() {
+    let q = quote("quote_sol_usdc.json").unwrap();
+    assert_eq!(q.out_amount, 151_234_567);
+    assert_eq!(q.context_slot, Some(298_765_432));
+    assert_eq!(q.time_taken, Some(0.012345));
+    assert!((q.price_impact() - 0.0012).abs() < 1e-12);
+
+    let legs: Vec<(u8, u64)> = q.route_plan.iter().map(|s| (s.percent, s.swap_info.in_amount)).collect();
+    assert_eq!(legs, vec![(60, 600_000_000), (40, 400_000_000)]);
+    assert_eq!(q.route_plan[0].swap_info.fee_amount.as_deref(), Some("1500000"));
+}
+
+/* ---- 多跳路由：新版响应不带 feeAmount/feeMint，带 platformFee ---- */
+#[test]
+fn multi_hop_fixture_to_strategy_quote() {
+    let r = quote("quote_usdc_bonk.json").unwrap();
+    assert_eq!(r.platform_fee.as_ref().map(|f| (f.amount, f.fee_bps)), Some((21_746_923, 2)));
+    assert!(r.route_plan[0].swap_info.fee_amount.is_none());
+
+    let q = Quote::from(&r);
+    assert_eq!(q.in_amount, 250_000_000);
+    assert_eq!(q.out_amount, 10_873_461_902_311);
+    assert_eq!(q.min_out_amount, 10_819_094_592_799);
+    assert_eq!(q.slot, Some(298_765_490));
+    assert_eq!(q.label(), "Orca (Whirlpools)+Raydium CLMM");
+    assert_eq!(q.route[1].input_mint, "So11111111111111111111111111111111111111112");
+    assert_eq!(q.route[1].in_amount, q.route[0].out_amount);
+    assert!(q.route.iter().all(|l| l.percent == 100 && l.fee_amount == 0));
+}
+
+/* ---- 错误码：无路由 → NoQuoteFound，请求被拒 → QuoteRejected，其余 → External ---- */
+#[test]
+fn error_codes() {
+    assert!(matches!(quote("quote_no_route.json"), Err(AppError::NoQuoteFound)));
+
+    match quote("quote_not_tradable.json") {
+        Err(AppError::QuoteRejected(msg)) => assert!(msg.contains("TokenNotTradable"), "{msg}"),
+        other => panic!("unexpected: {:?}", other.map(|q| q.out_amount)),
+    }
+
+    let e: ApiError = serde_json::from_str(r#"{"error":"boom","errorCode":"SOMETHING_NEW"}"#).unwrap();
+    assert_eq!(e.error_code, Some(ErrorCode::Other("SOMETHING_NEW".into())));
+    assert!(!ErrorCode::TokenNotTradable.is_no_route());
+    assert!(ErrorCode::TokenNotTradable.is_rejection() && !ErrorCode::Other("SOMETHING_NEW".into()).is_rejection());
+    assert!(matches!(parse_response::<QuoteResponse>(r#"{"error":"boom","errorCode":"SOMETHING_NEW"}"#), Err(AppError::External(_))));
+}
+
+/* ---- 缺字段/坏数量报 ParseError ---- */
+#[test]
+fn malformed_quote_is_parse_error() {
+    let mut v: serde_json::Value = serde_json::from_str(&fixture("quote_sol_usdc.json")).unwrap();
+    v["outAmount"] = "12x".into();
+    assert!(matches!(parse_response::<QuoteResponse>(&v.to_string()), Err(AppError::ParseError(_))));
+
+    v.as_object_mut().unwrap().remove("outAmount");
+    assert!(matches!(parse_response::<QuoteResponse>(&v.to_string()), Err(AppError::ParseError(_))));
+}
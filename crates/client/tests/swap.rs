@@ -0,0 +1,72 @@
+use client::jupiter::models::{parse_response, SwapMode};
+use client::{PrioritizationFee, QuoteResponse, SwapRequest, SwapResponse};
+use serde_json::{json, Value};
+use solana_sdk::message::VersionedMessage;
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn quote() -> QuoteResponse {
+    serde_json::from_str(&fixture("quote_sol_usdc.json")).unwrap()
+}
+
+/* ---- 报价：数量按字符串解析，未建模字段原样保留 ---- */
+#[test]
+fn quote_fixture_roundtrips() {
+    let q = quote();
+    assert_eq!(q.in_amount, 1_000_000_000);
+    assert_eq!(q.other_amount_threshold, 150_478_394);
+    assert_eq!(q.swap_mode, SwapMode::ExactIn);
+    assert_eq!(q.route_plan.len(), 2);
+    assert_eq!(q.label(), "Raydium+Whirlpool");
+    assert!(q.extra.contains_key("scoreReport"));
+
+    let original: Value = serde_json::from_str(&fixture("quote_sol_usdc.json")).unwrap();
+    assert_eq!(serde_json::to_value(&q).unwrap(), original);
+}
+
+/* ---- 请求体字段名与 v6 接口一致 ---- */
+#[test]
+fn swap_request_shape() {
+    let req = SwapRequest::new("GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB", quote())
+        .priority_fee(PrioritizationFee::Level { level: "veryHigh".into(), max_lamports: 1_000_000 })
+        .destination_token_account("7woc3ajaGMMXczFYjxon4aQoHH3j126fMUR9c58eHRsK");
+    let v = serde_json::to_value(&req).unwrap();
+
+    assert_eq!(v["userPublicKey"], "GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB");
+    assert_eq!(v["wrapAndUnwrapSol"], true);
+    assert_eq!(v["dynamicComputeUnitLimit"], true);
+    assert_eq!(v["destinationTokenAccount"], "7woc3ajaGMMXczFYjxon4aQoHH3j126fMUR9c58eHRsK");
+    assert_eq!(
+        v["prioritizationFeeLamports"],
+        json!({ "priorityLevelWithMaxLamports": { "maxLamports": 1_000_000, "priorityLevel": "veryHigh" } })
+    );
+    assert_eq!(v["quoteResponse"]["outAmount"], "151234567");
+
+    let auto = SwapRequest::new("x", quote()).priority_fee(PrioritizationFee::Auto).wrap_and_unwrap_sol(false);
+    let v = serde_json::to_value(&auto).unwrap();
+    assert_eq!(v["prioritizationFeeLamports"], "auto");
+    assert_eq!(v["wrapAndUnwrapSol"], false);
+    assert!(v.get("destinationTokenAccount").is_none());
+
+    let fixed = serde_json::to_value(PrioritizationFee::Lamports(5000)).unwrap();
+    assert_eq!(fixed, json!(5000));
+}
+
+/* ---- 响应解码为 v0 VersionedTransaction ---- */
+#[test]
+fn swap_response_decodes_versioned_tx() {
+    let resp: SwapResponse = parse_response(&fixture("swap_response.json")).unwrap();
+    assert_eq!(resp.last_valid_block_height, 276_543_210);
+    assert_eq!(resp.prioritization_fee_lamports, Some(20_000));
+
+    let tx = resp.transaction().unwrap();
+    assert!(matches!(tx.message, VersionedMessage::V0(_)));
+    assert_eq!(tx.signatures.len(), 1);
+    assert_eq!(
+        tx.message.static_account_keys()[0].to_string(),
+        "GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB"
+    );
+    assert_eq!(tx.message.instructions().len(), 3);
+}
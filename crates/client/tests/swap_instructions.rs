@@ -0,0 +1,38 @@
+use client::SwapInstructionsResponse;
+use client::jupiter::models::parse_response;
+use solana_sdk::pubkey::Pubkey;
+use utils::AppError;
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn response() -> SwapInstructionsResponse {
+    parse_response(&fixture("swap_ix_sol_usdc.json")).unwrap()
+}
+
+/* ---- 录制响应 → solana 指令 ---- */
+#[test]
+fn decodes_instructions() {
+    let r = response();
+    let jup: Pubkey = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".parse().unwrap();
+    let swap = r.swap_instruction.to_instruction().unwrap();
+    assert_eq!(swap.program_id, jup);
+    assert_eq!(swap.accounts.len(), 22);
+    let user: Pubkey = "GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB".parse().unwrap();
+    assert!(swap.accounts.iter().any(|a| a.pubkey == user && a.is_signer));
+    assert_eq!(r.setup().unwrap().len(), 4);
+    assert_eq!(r.lookup_tables().unwrap(), vec!["9M7mZTiBaBZFWSFfU7J3V8uGaQpA7Voem7HBrSYiRdfC".parse::<Pubkey>().unwrap()]);
+}
+
+/* ---- 坏数据报 ParseError，不 panic ---- */
+#[test]
+fn invalid_pubkey_is_parse_error() {
+    let mut r = response();
+    r.swap_instruction.program_id = "not-a-key".into();
+    assert!(matches!(r.swap_instruction.to_instruction(), Err(AppError::ParseError(_))));
+
+    let mut r = response();
+    r.swap_instruction.data = "%%%".into();
+    assert!(matches!(r.swap_instruction.to_instruction(), Err(AppError::ParseError(_))));
+}
@@ -0,0 +1,117 @@
+#![allow(async_fn_in_trait)]
+use std::collections::HashMap;
+use std::sync::Mutex;
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+/// 链上账户里报价需要的部分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawAccount {
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// 账户数据来源（RPC、内存、文件……）
+pub trait AccountSource: Send + Sync {
+    /// 按 keys 顺序返回，不存在的账户为 None
+    async fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>>;
+}
+
+impl<S: AccountSource + ?Sized> AccountSource for &S {
+    async fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>> {
+        (**self).accounts(keys).await
+    }
+}
+
+/* ---------------- 内存实现（测试/离线） ---------------- */
+
+#[derive(Debug, Default)]
+pub struct MemoryAccounts {
+    map: Mutex<HashMap<Pubkey, RawAccount>>,
+}
+
+impl MemoryAccounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, key: Pubkey, account: RawAccount) {
+        self.map.lock().unwrap().insert(key, account);
+    }
+
+    pub fn remove(&self, key: &Pubkey) {
+        self.map.lock().unwrap().remove(key);
+    }
+
+    /// 改写 SPL Token 账户的 amount（模拟金库余额变化）
+    pub fn set_token_amount(&self, key: &Pubkey, amount: u64) {
+        if let Some(a) = self.map.lock().unwrap().get_mut(key) {
+            if a.data.len() >= 72 {
+                a.data[64..72].copy_from_slice(&amount.to_le_bytes());
+            }
+        }
+    }
+}
+
+impl AccountSource for MemoryAccounts {
+    async fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>> {
+        let map = self.map.lock().unwrap();
+        Ok(keys.iter().map(|k| map.get(k).cloned()).collect())
+    }
+}
+
+/* ---------------- JSON-RPC 实现：getMultipleAccounts ---------------- */
+
+/// 单次 getMultipleAccounts 最多 100 个账户
+const MAX_KEYS_PER_CALL: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct HttpAccounts {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpAccounts {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), url: url.into() }
+    }
+
+    async fn chunk(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>> {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [keys, { "encoding": "base64", "commitment": "confirmed" }],
+        });
+        let v: Value = self.client.post(&self.url).json(&body).send().await?.error_for_status()?.json().await?;
+        if let Some(e) = v.get("error") {
+            return Err(anyhow!("getMultipleAccounts: {}", e));
+        }
+        let list = v["result"]["value"].as_array().context("getMultipleAccounts: missing result.value")?;
+        list.iter().map(parse_account).collect()
+    }
+}
+
+/// `{ owner, data: [base64, "base64"], ... }` 或 null
+pub(crate) fn parse_account(v: &Value) -> Result<Option<RawAccount>> {
+    if v.is_null() {
+        return Ok(None);
+    }
+    let owner = v["owner"].as_str().context("account missing owner")?.parse()?;
+    let data = v["data"][0].as_str().context("account missing data")?;
+    let data = base64::engine::general_purpose::STANDARD.decode(data)?;
+    Ok(Some(RawAccount { owner, data }))
+}
+
+impl AccountSource for HttpAccounts {
+    async fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>> {
+        let mut out = Vec::with_capacity(keys.len());
+        for c in keys.chunks(MAX_KEYS_PER_CALL) {
+            out.extend(self.chunk(c).await?);
+        }
+        Ok(out)
+    }
+}
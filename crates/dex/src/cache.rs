@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use tracing::warn;
+use crate::account::RawAccount;
+use crate::pools::{token_amount, Pool, PoolState};
+use crate::stream::AccountUpdate;
+
+/// 某个池子的报价状态变了
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolChange {
+    pub pool: Pubkey,
+    pub mints: [Pubkey; 2],
+    pub slot: u64,
+    /// 变化后缓存的版本号
+    pub version: u64,
+}
+
+impl PoolChange {
+    /// mint 路径里是否有一跳正好是这个池子的交易对（任一方向）
+    pub fn touches(&self, path: &[String]) -> bool {
+        let [a, b] = self.mints.map(|m| m.to_string());
+        path.windows(2).any(|w| (w[0] == a && w[1] == b) || (w[0] == b && w[1] == a))
+    }
+}
+
+/// `PoolCache::apply` 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Applied {
+    /// 不是缓存里的账户，或者比已有数据旧
+    Ignored,
+    Changed(PoolChange),
+    /// 价格移到了新的 tick array，先拉到这些账户再 `fill`
+    Missing { pool: Pubkey, keys: Vec<Pubkey> },
+}
+
+struct Entry {
+    state: PoolState,
+    /// 池子账户原始数据，附属账户变化时据此重新解码
+    raw: RawAccount,
+    /// extra_accounts 对应的账户（None = 链上不存在）
+    extra: HashMap<Pubkey, Option<RawAccount>>,
+    /// 每个账户最后一次更新的 slot，旧于此的推送直接丢弃
+    slots: HashMap<Pubkey, u64>,
+}
+
+/// 带版本号的池子缓存：池子账户 + 金库 + 附属账户（tick array、AmmConfig）
+///
+/// 任一账户更新都能定位到所属池子（AmmConfig 等账户可被多个池子共用）；每次池子状态变化全局版本号 +1
+#[derive(Default)]
+pub struct PoolCache {
+    pools: HashMap<Pubkey, Entry>,
+    /// 账户 → 用到它的池子
+    owners: HashMap<Pubkey, Vec<Pubkey>>,
+    version: u64,
+}
+
+/// 解码池子并填入附属账户；缺账户时返回缺的 keys
+fn build(key: &Pubkey, raw: &RawAccount, extra: &HashMap<Pubkey, Option<RawAccount>>) -> Result<std::result::Result<Pool, Vec<Pubkey>>> {
+    let mut pool = Pool::decode(raw)?;
+    let keys = pool.extra_accounts(key);
+    let missing: Vec<Pubkey> = keys.iter().filter(|k| !extra.contains_key(k)).copied().collect();
+    if !missing.is_empty() {
+        return Ok(Err(missing));
+    }
+    let accounts: Vec<Option<RawAccount>> = keys.iter().map(|k| extra[k].clone()).collect();
+    pool.apply_extra(&accounts)?;
+    Ok(Ok(pool))
+}
+
+impl PoolCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 放入或替换一个池子；extra 须覆盖 `Pool::extra_accounts` 的全部账户
+    ///
+    /// 替换时各账户的 slot 不会回退：已有更新的推送，之后更旧的推送照样丢弃
+    pub fn insert(
+        &mut self,
+        key: Pubkey,
+        raw: RawAccount,
+        extra: Vec<(Pubkey, Option<RawAccount>)>,
+        vault: [u64; 2],
+        slot: u64,
+    ) -> Result<PoolChange> {
+        let extra: HashMap<Pubkey, Option<RawAccount>> = extra.into_iter().collect();
+        let pool = match build(&key, &raw, &extra)? {
+            Ok(p) => p,
+            Err(missing) => bail!("pool {}: missing {} extra accounts", key, missing.len()),
+        };
+        let old = self.pools.get(&key);
+        let slot_of = |k: &Pubkey| old.and_then(|e| e.slots.get(k)).map_or(slot, |&s| s.max(slot));
+        let slots: HashMap<Pubkey, u64> =
+            std::iter::once(key).chain(pool.vaults()).chain(extra.keys().copied()).map(|k| (k, slot_of(&k))).collect();
+        let slot = old.map_or(slot, |e| e.state.slot.max(slot));
+        self.version += 1;
+        let state = PoolState { key, pool, vault, slot, version: self.version };
+        self.pools.insert(key, Entry { state, raw, extra, slots });
+        self.index(&key);
+        Ok(self.change(&key))
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Option<&PoolState> {
+        self.pools.get(key).map(|e| &e.state)
+    }
+
+    pub fn states(&self) -> impl Iterator<Item = &PoolState> {
+        self.pools.values().map(|e| &e.state)
+    }
+
+    pub fn keys(&self) -> Vec<Pubkey> {
+        self.pools.keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// 需要订阅的全部账户：池子、金库、附属账户
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        self.owners.keys().copied().collect()
+    }
+
+    /// 应用一次账户推送，用到该账户的每个池子各得一个结果；不认识的账户返回空
+    ///
+    /// 某个池子出错只记日志，不影响共用这个账户的其它池子
+    pub fn apply(&mut self, u: &AccountUpdate) -> Vec<Applied> {
+        let pools = self.owners.get(&u.key).cloned().unwrap_or_default();
+        pools
+            .into_iter()
+            .filter_map(|pool| {
+                self.apply_to(&pool, u).inspect_err(|e| warn!("pool {}: account {} @{}: {:#}", pool, u.key, u.slot, e)).ok()
+            })
+            .collect()
+    }
+
+    fn apply_to(&mut self, &pool: &Pubkey, u: &AccountUpdate) -> Result<Applied> {
+        let e = self.pools.get_mut(&pool).context("owner index out of sync")?;
+        if e.slots.get(&u.key).is_some_and(|&s| s > u.slot) {
+            return Ok(Applied::Ignored);
+        }
+        e.slots.insert(u.key, u.slot);
+
+        if u.key == pool {
+            let Some(raw) = &u.account else {
+                warn!("pool {} closed at slot {}, dropping", pool, u.slot);
+                self.remove(&pool);
+                return Ok(Applied::Ignored);
+            };
+            e.raw = raw.clone();
+        } else if let Some(i) = e.state.pool.vaults().iter().position(|v| *v == u.key) {
+            let Some(acc) = &u.account else {
+                warn!("vault {} closed at slot {}", u.key, u.slot);
+                return Ok(Applied::Ignored);
+            };
+            e.state.vault[i] = token_amount(&acc.data).with_context(|| format!("vault {}", u.key))?;
+            return Ok(self.bump(&pool, u.slot));
+        } else {
+            e.extra.insert(u.key, u.account.clone());
+        }
+        self.rebuild(&pool, u.slot)
+    }
+
+    /// 补上 `Applied::Missing` 要的账户后重新解码
+    pub fn fill(&mut self, pool: &Pubkey, accounts: Vec<(Pubkey, Option<RawAccount>)>, slot: u64) -> Result<Applied> {
+        let Some(e) = self.pools.get_mut(pool) else {
+            return Ok(Applied::Ignored);
+        };
+        for (k, a) in accounts {
+            e.slots.insert(k, slot);
+            e.extra.insert(k, a);
+        }
+        self.rebuild(pool, slot)
+    }
+
+    pub fn remove(&mut self, pool: &Pubkey) -> Option<PoolState> {
+        let e = self.pools.remove(pool)?;
+        self.unindex(pool);
+        Some(e.state)
+    }
+
+    fn rebuild(&mut self, pool: &Pubkey, slot: u64) -> Result<Applied> {
+        let e = self.pools.get_mut(pool).context("pool vanished")?;
+        match build(pool, &e.raw, &e.extra)? {
+            Ok(p) => {
+                // 只保留当前还需要的附属账户
+                let keep = p.extra_accounts(pool);
+                e.extra.retain(|k, _| keep.contains(k));
+                let vaults = p.vaults();
+                e.slots.retain(|k, _| k == pool || vaults.contains(k) || keep.contains(k));
+                e.state.pool = p;
+                self.index(pool);
+                Ok(self.bump(pool, slot))
+            }
+            Err(keys) => Ok(Applied::Missing { pool: *pool, keys }),
+        }
+    }
+
+    fn bump(&mut self, pool: &Pubkey, slot: u64) -> Applied {
+        self.version += 1;
+        if let Some(e) = self.pools.get_mut(pool) {
+            e.state.version = self.version;
+            e.state.slot = e.state.slot.max(slot);
+        }
+        Applied::Changed(self.change(pool))
+    }
+
+    fn change(&self, pool: &Pubkey) -> PoolChange {
+        let s = &self.pools[pool].state;
+        PoolChange { pool: *pool, mints: s.pool.mints(), slot: s.slot, version: s.version }
+    }
+
+    /// 重建某个池子的 账户 → 池子 索引
+    fn index(&mut self, pool: &Pubkey) {
+        self.unindex(pool);
+        let Some(e) = self.pools.get(pool) else { return };
+        for k in std::iter::once(*pool).chain(e.state.pool.vaults()).chain(e.extra.keys().copied()) {
+            self.owners.entry(k).or_default().push(*pool);
+        }
+    }
+
+    /// 从索引里去掉某个池子；别的池子还在用的账户保留
+    fn unindex(&mut self, pool: &Pubkey) {
+        self.owners.retain(|_, ps| {
+            ps.retain(|p| p != pool);
+            !ps.is_empty()
+        });
+    }
+}
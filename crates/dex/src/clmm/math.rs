@@ -0,0 +1,249 @@
+//! Q64.64 集中流动性数学（与 Whirlpool / Raydium CLMM 相同的定点约定）
+//!
+//! 中间结果用 256 位整数，输入方向向上取整、输出方向向下取整。
+
+use std::cmp::Ordering;
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+/// 1.0 的 Q64.64 表示
+pub const Q64: u128 = 1 << 64;
+/// 手续费率分母（fee_rate 以百万分之一计）
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/* ---------------- 256 位无符号整数（只实现需要的运算） ---------------- */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct U256 {
+    hi: u128,
+    lo: u128,
+}
+
+impl U256 {
+    pub const ZERO: U256 = U256 { hi: 0, lo: 0 };
+
+    pub const fn from_u128(x: u128) -> Self {
+        Self { hi: 0, lo: x }
+    }
+
+    /// x << 64
+    pub const fn shl64(x: u128) -> Self {
+        Self { hi: x >> 64, lo: x << 64 }
+    }
+
+    pub fn mul(a: u128, b: u128) -> Self {
+        let (a1, a0) = (a >> 64, a & u64::MAX as u128);
+        let (b1, b0) = (b >> 64, b & u64::MAX as u128);
+        let p00 = a0 * b0;
+        let p01 = a0 * b1;
+        let p10 = a1 * b0;
+        let p11 = a1 * b1;
+        // 中间两项之和可能超过 128 位，拆开累加
+        let mid = (p00 >> 64) + (p01 & u64::MAX as u128) + (p10 & u64::MAX as u128);
+        let lo = (p00 & u64::MAX as u128) | (mid << 64);
+        let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+        Self { hi, lo }
+    }
+
+    pub fn checked_add(self, o: Self) -> Option<Self> {
+        let (lo, c) = self.lo.overflowing_add(o.lo);
+        let hi = self.hi.checked_add(o.hi)?.checked_add(c as u128)?;
+        Some(Self { hi, lo })
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.hi == 0 && self.lo == 0
+    }
+
+    pub fn to_u128(self) -> Option<u128> {
+        (self.hi == 0).then_some(self.lo)
+    }
+
+    fn shl1(self) -> Self {
+        Self { hi: (self.hi << 1) | (self.lo >> 127), lo: self.lo << 1 }
+    }
+
+    fn sub(self, o: Self) -> Self {
+        let (lo, b) = self.lo.overflowing_sub(o.lo);
+        Self { hi: self.hi - o.hi - b as u128, lo }
+    }
+
+    fn bit(self, i: u32) -> bool {
+        if i >= 128 { (self.hi >> (i - 128)) & 1 == 1 } else { (self.lo >> i) & 1 == 1 }
+    }
+
+    /// (商, 余数)；除数为 0 返回 None
+    pub fn div_rem(self, d: Self) -> Option<(Self, Self)> {
+        if d.is_zero() {
+            return None;
+        }
+        if self < d {
+            return Some((Self::ZERO, self));
+        }
+        let mut q = Self::ZERO;
+        let mut r = Self::ZERO;
+        for i in (0..256).rev() {
+            r = r.shl1();
+            if self.bit(i) {
+                r.lo |= 1;
+            }
+            q = q.shl1();
+            if r.cmp(&d) != Ordering::Less {
+                r = r.sub(d);
+                q.lo |= 1;
+            }
+        }
+        Some((q, r))
+    }
+
+    /// self / d，可选向上取整
+    pub fn div(self, d: Self, round_up: bool) -> Option<Self> {
+        let (q, r) = self.div_rem(d)?;
+        if round_up && !r.is_zero() { q.checked_add(Self::from_u128(1)) } else { Some(q) }
+    }
+}
+
+/// a * b / d（256 位中间结果），结果超出 u128 返回 None
+pub fn mul_div(a: u128, b: u128, d: u128, round_up: bool) -> Option<u128> {
+    U256::mul(a, b).div(U256::from_u128(d), round_up)?.to_u128()
+}
+
+/* ---------------- tick ↔ sqrt price ---------------- */
+
+/// 2^64 / sqrt(1.0001)^(2^k)，k = 0..19
+const TICK_RATIOS: [u128; 19] = [
+    0xfffcb933bd6fad37, 0xfff97272373d4132, 0xfff2e50f5f656932, 0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159, 0xff973b41fa98c081, 0xff2ea16466c96a38, 0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae, 0xf987a7253ac41317, 0xf3392b0822b70005, 0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8, 0xa9f746462d870fdf, 0x70d869a156d2a1b8, 0x31be135f97d08fd9,
+    0x9aa508b5b7a84e1, 0x5d6af8dedb8119, 0x2216e584f5fa,
+];
+
+/// sqrt(1.0001^tick) 的 Q64.64 表示
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs = tick.unsigned_abs();
+    let mut ratio = Q64;
+    for (k, c) in TICK_RATIOS.iter().enumerate() {
+        if abs & (1 << k) != 0 {
+            ratio = (ratio * c) >> 64;
+        }
+    }
+    // 正 tick 取倒数
+    Some(if tick > 0 { u128::MAX / ratio } else { ratio })
+}
+
+pub fn min_sqrt_price() -> u128 {
+    sqrt_price_at_tick(MIN_TICK).unwrap()
+}
+
+pub fn max_sqrt_price() -> u128 {
+    sqrt_price_at_tick(MAX_TICK).unwrap()
+}
+
+/* ---------------- 数量 ↔ 价格区间 ---------------- */
+
+/// 区间 [lo, hi] 内 L 对应的 token A 数量：L * (hi - lo) / (hi * lo) * 2^64
+pub fn delta_a(lo: u128, hi: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let (lo, hi) = (lo.min(hi), lo.max(hi));
+    if lo == 0 {
+        return None;
+    }
+    let t = mul_div(liquidity, hi - lo, hi, round_up)?;
+    mul_div(t, Q64, lo, round_up)
+}
+
+/// 区间 [lo, hi] 内 L 对应的 token B 数量：L * (hi - lo) / 2^64
+pub fn delta_b(lo: u128, hi: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let (lo, hi) = (lo.min(hi), lo.max(hi));
+    mul_div(liquidity, hi - lo, Q64, round_up)
+}
+
+/// 输入 amount 后的新价格：A 换 B 价格下降（向上取整），B 换 A 价格上升（向下取整）
+pub fn next_sqrt_price_from_input(sqrt_price: u128, liquidity: u128, amount: u128, a_to_b: bool) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price);
+    }
+    if liquidity == 0 {
+        return None;
+    }
+    if a_to_b {
+        // P' = L·2^64 / (L·2^64 / P + amount)
+        let num = U256::shl64(liquidity);
+        let den = num.div(U256::from_u128(sqrt_price), false)?.checked_add(U256::from_u128(amount))?;
+        num.div(den, true)?.to_u128()
+    } else {
+        // P' = P + amount·2^64 / L
+        let d = U256::shl64(amount).div(U256::from_u128(liquidity), false)?.to_u128()?;
+        sqrt_price.checked_add(d)
+    }
+}
+
+/// 单步兑换结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+/// 在当前流动性下向 target 价格推进，最多消耗 amount_remaining（含手续费）
+pub fn swap_step(
+    sqrt_price: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    a_to_b: bool,
+) -> Option<SwapStep> {
+    let fee_den = FEE_RATE_DENOMINATOR as u128;
+    let fee_rate = fee_rate as u128;
+    let less_fee = amount_remaining as u128 * (fee_den - fee_rate) / fee_den;
+
+    let to_target = if a_to_b {
+        delta_a(sqrt_price_target, sqrt_price, liquidity, true)?
+    } else {
+        delta_b(sqrt_price, sqrt_price_target, liquidity, true)?
+    };
+
+    let (next, amount_in) = if less_fee >= to_target {
+        (sqrt_price_target, to_target)
+    } else {
+        let next = next_sqrt_price_from_input(sqrt_price, liquidity, less_fee, a_to_b)?;
+        let used = if a_to_b {
+            delta_a(next, sqrt_price, liquidity, true)?
+        } else {
+            delta_b(sqrt_price, next, liquidity, true)?
+        };
+        (next, used)
+    };
+
+    let amount_out = if a_to_b {
+        delta_b(next, sqrt_price, liquidity, false)?
+    } else {
+        delta_a(sqrt_price, next, liquidity, false)?
+    };
+
+    // 没走到目标价：剩余的都算手续费；走到了：按费率反推
+    let fee = if next != sqrt_price_target {
+        amount_remaining as u128 - amount_in.min(amount_remaining as u128)
+    } else {
+        (amount_in * fee_rate).div_ceil(fee_den - fee_rate)
+    };
+
+    Some(SwapStep {
+        sqrt_price_next: next,
+        amount_in: u64::try_from(amount_in).ok()?,
+        amount_out: u64::try_from(amount_out).ok()?,
+        fee: u64::try_from(fee).ok()?,
+    })
+}
+
+/// Q64.64 sqrt price → 价格（B/A，最小单位之比），仅用于展示
+pub fn price_from_sqrt(sqrt_price: u128) -> f64 {
+    let p = sqrt_price as f64 / Q64 as f64;
+    p * p
+}
@@ -0,0 +1,120 @@
+pub mod math;
+
+use anyhow::{anyhow, bail, Result};
+use solana_sdk::pubkey::Pubkey;
+use crate::math::price_impact_pct;
+use math::{max_sqrt_price, min_sqrt_price, price_from_sqrt, sqrt_price_at_tick, swap_step, MAX_TICK, MIN_TICK};
+
+/// 一个已初始化的 tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tick {
+    pub index: i32,
+    /// 从左往右穿过该 tick 时流动性的变化量
+    pub liquidity_net: i128,
+}
+
+/// 集中流动性池子的报价所需状态（Whirlpool 与 Raydium CLMM 共用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClmmPool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub tick_spacing: u16,
+    /// 百万分之一
+    pub fee_rate: u32,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+    /// 已加载的 tick 数据（按 index 升序，只含已初始化的）
+    pub ticks: Vec<Tick>,
+    /// 已加载 tick array 覆盖的 tick 区间 [lo, hi)；超出该区间无法模拟
+    pub covered: Option<(i32, i32)>,
+}
+
+/// 一次集中流动性兑换的模拟结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClmmSwap {
+    pub amount_out: u64,
+    pub fee: u64,
+    pub sqrt_price_after: u128,
+    pub ticks_crossed: usize,
+    /// 成交均价相对兑换前现价的偏离（百分比）
+    pub price_impact_pct: f64,
+}
+
+impl ClmmPool {
+    /// 合并一个 tick array 的数据：start 起共 len 个 tick
+    pub fn add_tick_array(&mut self, start: i32, len: i32, ticks: impl IntoIterator<Item = Tick>) {
+        let end = start + len * self.tick_spacing as i32;
+        self.ticks.retain(|t| t.index < start || t.index >= end);
+        self.ticks.extend(ticks);
+        self.ticks.sort_by_key(|t| t.index);
+        self.covered = Some(match self.covered {
+            Some((lo, hi)) => (lo.min(start), hi.max(end)),
+            None => (start, end),
+        });
+    }
+
+    /// 沿 tick 逐段模拟 exact-in 兑换
+    pub fn swap(&self, a_to_b: bool, amount_in: u64) -> Result<ClmmSwap> {
+        let (lo, hi) = self.covered.ok_or_else(|| anyhow!("clmm: no tick arrays loaded"))?;
+        let mut remaining = amount_in;
+        let mut out = 0u64;
+        let mut fee = 0u64;
+        let mut sqrt_price = self.sqrt_price;
+        let mut tick = self.tick_current;
+        let mut liquidity = self.liquidity;
+        let mut crossed = 0;
+
+        while remaining > 0 {
+            // 下一个已初始化 tick；没有就走到已加载区间的边界
+            let next = if a_to_b {
+                self.ticks.iter().rev().find(|t| t.index <= tick).copied()
+            } else {
+                self.ticks.iter().find(|t| t.index > tick).copied()
+            };
+            let boundary = if a_to_b { lo.max(MIN_TICK) } else { hi.min(MAX_TICK) };
+            let target_tick = match next {
+                Some(t) if (a_to_b && t.index >= boundary) || (!a_to_b && t.index <= boundary) => t.index,
+                _ => boundary,
+            };
+            let target = sqrt_price_at_tick(target_tick).ok_or_else(|| anyhow!("clmm: bad tick {}", target_tick))?;
+            let target = if a_to_b { target.max(min_sqrt_price()) } else { target.min(max_sqrt_price()) };
+
+            let step = swap_step(sqrt_price, target, liquidity, remaining, self.fee_rate, a_to_b)
+                .ok_or_else(|| anyhow!("clmm: swap math overflow"))?;
+            remaining -= (step.amount_in + step.fee).min(remaining);
+            out = out.checked_add(step.amount_out).ok_or_else(|| anyhow!("clmm: output overflows u64"))?;
+            fee += step.fee;
+            sqrt_price = step.sqrt_price_next;
+
+            if sqrt_price != target {
+                break;
+            }
+            // 到达目标 tick
+            match next {
+                Some(t) if t.index == target_tick => {
+                    let net = if a_to_b { -t.liquidity_net } else { t.liquidity_net };
+                    liquidity = liquidity
+                        .checked_add_signed(net)
+                        .ok_or_else(|| anyhow!("clmm: liquidity underflow at tick {}", t.index))?;
+                    crossed += 1;
+                    tick = if a_to_b { t.index - 1 } else { t.index };
+                }
+                _ if remaining > 0 => bail!("clmm: swap runs past loaded tick arrays (tick {})", target_tick),
+                _ => break,
+            }
+        }
+
+        let spot = price_from_sqrt(self.sqrt_price);
+        let spot = if a_to_b { spot } else if spot > 0.0 { 1.0 / spot } else { 0.0 };
+        Ok(ClmmSwap {
+            amount_out: out,
+            fee,
+            sqrt_price_after: sqrt_price,
+            ticks_crossed: crossed,
+            price_impact_pct: price_impact_pct(amount_in.saturating_sub(fee), out, spot),
+        })
+    }
+}
@@ -0,0 +1,16 @@
+//! 直连 DEX 的报价源：解码池子账户，本地计算兑换结果（不走 HTTP 报价接口）
+
+pub mod account;
+pub mod cache;
+pub mod clmm;
+pub mod math;
+pub mod pools;
+pub mod quoter;
+pub mod stream;
+
+pub use account::{AccountSource, HttpAccounts, MemoryAccounts, RawAccount};
+pub use cache::{Applied, PoolCache, PoolChange};
+pub use clmm::{ClmmPool, ClmmSwap, Tick};
+pub use pools::{Pool, PoolState, Swap};
+pub use quoter::DexQuoter;
+pub use stream::{AccountStream, AccountUpdate, FileAccounts, WsAccounts};
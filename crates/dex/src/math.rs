@@ -0,0 +1,104 @@
+//! 兑换数学，全部为整数运算，取整方向对 LP 有利（与链上程序一致）
+
+/// `ceil(amount * num / den)`；den 为 0 时返回 None
+pub fn fee_ceil(amount: u64, num: u64, den: u64) -> Option<u64> {
+    if den == 0 {
+        return None;
+    }
+    let x = amount as u128 * num as u128;
+    u64::try_from(x.div_ceil(den as u128)).ok()
+}
+
+/// SPL token-swap 的手续费：向下取整，但非零费率至少收 1
+pub fn fee_floor_min_one(amount: u64, num: u64, den: u64) -> Option<u64> {
+    if num == 0 || amount == 0 {
+        return Some(0);
+    }
+    if den == 0 {
+        return None;
+    }
+    let fee = (amount as u128 * num as u128 / den as u128) as u64;
+    Some(fee.max(1))
+}
+
+/// 恒定乘积：`floor(reserve_out * in / (reserve_in + in))`（已扣费的输入）
+pub fn constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return None;
+    }
+    let num = reserve_out as u128 * amount_in as u128;
+    let den = reserve_in as u128 + amount_in as u128;
+    u64::try_from(num / den).ok()
+}
+
+/// 牛顿迭代最多几轮
+const MAX_ITERATIONS: usize = 64;
+const N_COINS: u128 = 2;
+
+/// 两币 StableSwap 不变量 D（leverage = amp * n）
+pub fn stable_d(amp: u64, x: u128, y: u128) -> Option<u128> {
+    let sum = x.checked_add(y)?;
+    if sum == 0 {
+        return Some(0);
+    }
+    if x == 0 || y == 0 {
+        return None;
+    }
+    let ann = amp as u128 * N_COINS;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = D^3 / (n^n * x * y)
+        let d_p = d.checked_mul(d)? / (x * N_COINS);
+        let d_p = d_p.checked_mul(d)? / (y * N_COINS);
+        let prev = d;
+        let num = (ann.checked_mul(sum)?.checked_add(d_p.checked_mul(N_COINS)?)?).checked_mul(d)?;
+        let den = (ann - 1).checked_mul(d)?.checked_add((N_COINS + 1).checked_mul(d_p)?)?;
+        d = num / den;
+        if d.abs_diff(prev) <= 1 {
+            return Some(d);
+        }
+    }
+    Some(d)
+}
+
+/// 给定一侧新余额 x 和不变量 D，求另一侧余额 y
+pub fn stable_y(amp: u64, x: u128, d: u128) -> Option<u128> {
+    if x == 0 {
+        return None;
+    }
+    let ann = amp as u128 * N_COINS;
+    // c = D^3 / (n^n * x * Ann)，b = x + D / Ann
+    let c = d.checked_mul(d)? / (x * N_COINS);
+    let c = c.checked_mul(d)? / (ann * N_COINS);
+    let b = x + d / ann;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let prev = y;
+        let den = (2 * y + b).checked_sub(d)?;
+        y = (y.checked_mul(y)?.checked_add(c)?) / den;
+        if y.abs_diff(prev) <= 1 {
+            return Some(y);
+        }
+    }
+    Some(y)
+}
+
+/// StableSwap 输出（已扣费的输入），多减 1 防止取整对池子不利
+pub fn stable_out(amp: u64, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    if amp == 0 {
+        return None;
+    }
+    let (x, y) = (reserve_in as u128, reserve_out as u128);
+    let d = stable_d(amp, x, y)?;
+    let new_y = stable_y(amp, x + amount_in as u128, d)?;
+    let out = y.checked_sub(new_y)?.saturating_sub(1);
+    u64::try_from(out).ok()
+}
+
+/// 成交价相对现价（spot = 每单位输入可得的输出）的偏离，百分比；仅用于展示
+pub fn price_impact_pct(net_in: u64, amount_out: u64, spot: f64) -> f64 {
+    if net_in == 0 || spot <= 0.0 {
+        return 0.0;
+    }
+    ((1.0 - amount_out as f64 / (net_in as f64 * spot)) * 100.0).max(0.0)
+}
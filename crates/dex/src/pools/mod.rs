@@ -0,0 +1,236 @@
+pub mod raydium;
+pub mod raydium_clmm;
+pub mod token_swap;
+pub mod whirlpool;
+
+use anyhow::{anyhow, bail, Result};
+use solana_sdk::pubkey::Pubkey;
+use crate::account::RawAccount;
+use crate::clmm::ClmmPool;
+pub use raydium::{RaydiumAmmV4, RAYDIUM_AMM_V4_PROGRAM_ID};
+pub use raydium_clmm::{RaydiumClmm, RAYDIUM_CLMM_PROGRAM_ID};
+pub use whirlpool::WHIRLPOOL_PROGRAM_ID;
+pub use token_swap::{Curve, TokenSwap, ORCA_TOKEN_SWAP_V1_PROGRAM_ID, ORCA_TOKEN_SWAP_V2_PROGRAM_ID, SPL_TOKEN_SWAP_PROGRAM_ID};
+
+/// SPL Token 账户长度 / amount 偏移
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+
+/// CLMM 池子在当前 tick array 两侧各加载几个
+pub const TICK_ARRAYS_PER_SIDE: i32 = 3;
+
+fn bytes_at<const N: usize>(d: &[u8], off: usize) -> Result<[u8; N]> {
+    let b = d.get(off..off + N).ok_or_else(|| anyhow!("account too short for {} bytes at {}", N, off))?;
+    Ok(b.try_into().unwrap())
+}
+
+pub(crate) fn u16_at(d: &[u8], off: usize) -> Result<u16> {
+    bytes_at(d, off).map(u16::from_le_bytes)
+}
+
+pub(crate) fn u32_at(d: &[u8], off: usize) -> Result<u32> {
+    bytes_at(d, off).map(u32::from_le_bytes)
+}
+
+pub(crate) fn i32_at(d: &[u8], off: usize) -> Result<i32> {
+    bytes_at(d, off).map(i32::from_le_bytes)
+}
+
+pub(crate) fn u128_at(d: &[u8], off: usize) -> Result<u128> {
+    bytes_at(d, off).map(u128::from_le_bytes)
+}
+
+pub(crate) fn i128_at(d: &[u8], off: usize) -> Result<i128> {
+    bytes_at(d, off).map(i128::from_le_bytes)
+}
+
+pub(crate) fn u64_at(d: &[u8], off: usize) -> Result<u64> {
+    bytes_at(d, off).map(u64::from_le_bytes)
+}
+
+pub(crate) fn pubkey_at(d: &[u8], off: usize) -> Result<Pubkey> {
+    let b = d.get(off..off + 32).ok_or_else(|| anyhow!("account too short for pubkey at {}", off))?;
+    Ok(Pubkey::new_from_array(b.try_into().unwrap()))
+}
+
+/// SPL Token 账户余额
+pub fn token_amount(data: &[u8]) -> Result<u64> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        bail!("not a token account ({} bytes)", data.len());
+    }
+    u64_at(data, TOKEN_AMOUNT_OFFSET)
+}
+
+/// 一次兑换的结果（最小单位）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swap {
+    pub amount_out: u64,
+    /// 按输入币计的手续费
+    pub fee: u64,
+    /// 扣费后的成交价相对现价的偏离（百分比）
+    pub price_impact_pct: f64,
+}
+
+/// 支持的池子类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pool {
+    RaydiumAmmV4(RaydiumAmmV4),
+    TokenSwap(TokenSwap),
+    /// Orca Whirlpool（fee_rate 直接在池子账户里）
+    Whirlpool(ClmmPool),
+    RaydiumClmm(RaydiumClmm),
+}
+
+impl Pool {
+    /// 按 owner 程序选择解码器
+    pub fn decode(account: &RawAccount) -> Result<Self> {
+        let owner = account.owner;
+        if owner == RAYDIUM_AMM_V4_PROGRAM_ID {
+            Ok(Pool::RaydiumAmmV4(RaydiumAmmV4::decode(&account.data)?))
+        } else if [ORCA_TOKEN_SWAP_V2_PROGRAM_ID, ORCA_TOKEN_SWAP_V1_PROGRAM_ID, SPL_TOKEN_SWAP_PROGRAM_ID].contains(&owner) {
+            Ok(Pool::TokenSwap(TokenSwap::decode(&account.data)?))
+        } else if owner == WHIRLPOOL_PROGRAM_ID {
+            Ok(Pool::Whirlpool(whirlpool::decode(&account.data)?))
+        } else if owner == RAYDIUM_CLMM_PROGRAM_ID {
+            Ok(Pool::RaydiumClmm(raydium_clmm::decode(&account.data)?))
+        } else {
+            bail!("unsupported pool program {}", owner)
+        }
+    }
+
+    /// [mint_a, mint_b]
+    pub fn mints(&self) -> [Pubkey; 2] {
+        match self {
+            Pool::RaydiumAmmV4(p) => [p.coin_mint, p.pc_mint],
+            Pool::TokenSwap(p) => [p.mint_a, p.mint_b],
+            Pool::Whirlpool(p) | Pool::RaydiumClmm(RaydiumClmm { clmm: p, .. }) => [p.mint_a, p.mint_b],
+        }
+    }
+
+    /// 与 mints 一一对应的金库账户
+    pub fn vaults(&self) -> [Pubkey; 2] {
+        match self {
+            Pool::RaydiumAmmV4(p) => [p.coin_vault, p.pc_vault],
+            Pool::TokenSwap(p) => [p.vault_a, p.vault_b],
+            Pool::Whirlpool(p) | Pool::RaydiumClmm(RaydiumClmm { clmm: p, .. }) => [p.vault_a, p.vault_b],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pool::RaydiumAmmV4(_) => "Raydium",
+            Pool::TokenSwap(p) => match p.curve {
+                Curve::ConstantProduct => "Orca",
+                Curve::Stable { .. } => "Orca Stable",
+            },
+            Pool::Whirlpool(_) => "Whirlpool",
+            Pool::RaydiumClmm(_) => "Raydium CLMM",
+        }
+    }
+
+    /// 报价前还需加载的账户（池子地址为 key）：CLMM 的 tick array、Raydium CLMM 的 AmmConfig
+    pub fn extra_accounts(&self, key: &Pubkey) -> Vec<Pubkey> {
+        match self {
+            Pool::RaydiumAmmV4(_) | Pool::TokenSwap(_) => vec![],
+            Pool::Whirlpool(p) => tick_array_starts(p, whirlpool::TICK_ARRAY_SIZE, whirlpool::tick_array_start)
+                .into_iter()
+                .map(|s| whirlpool::tick_array_address(key, s))
+                .collect(),
+            Pool::RaydiumClmm(p) => std::iter::once(p.amm_config)
+                .chain(
+                    tick_array_starts(&p.clmm, raydium_clmm::TICK_ARRAY_SIZE, raydium_clmm::tick_array_start)
+                        .into_iter()
+                        .map(|s| raydium_clmm::tick_array_address(key, s)),
+                )
+                .collect(),
+        }
+    }
+
+    /// 填入 `extra_accounts` 拉到的账户（顺序一致）；不存在的 tick array 视为没有已初始化的 tick
+    pub fn apply_extra(&mut self, accounts: &[Option<RawAccount>]) -> Result<()> {
+        match self {
+            Pool::RaydiumAmmV4(_) | Pool::TokenSwap(_) => Ok(()),
+            Pool::Whirlpool(p) => {
+                let starts = tick_array_starts(p, whirlpool::TICK_ARRAY_SIZE, whirlpool::tick_array_start);
+                for (start, acc) in starts.into_iter().zip(accounts) {
+                    let ticks = match acc {
+                        Some(a) => whirlpool::decode_tick_array(&a.data, p.tick_spacing)?.1,
+                        None => vec![],
+                    };
+                    p.add_tick_array(start, whirlpool::TICK_ARRAY_SIZE, ticks);
+                }
+                Ok(())
+            }
+            Pool::RaydiumClmm(p) => {
+                let (config, arrays) = accounts.split_first().ok_or_else(|| anyhow!("raydium clmm: amm config missing"))?;
+                let config = config.as_ref().ok_or_else(|| anyhow!("raydium clmm: amm config {} not found", p.amm_config))?;
+                p.clmm.fee_rate = raydium_clmm::decode_fee_rate(&config.data)?;
+                let starts = tick_array_starts(&p.clmm, raydium_clmm::TICK_ARRAY_SIZE, raydium_clmm::tick_array_start);
+                for (start, acc) in starts.into_iter().zip(arrays) {
+                    let ticks = match acc {
+                        Some(a) => raydium_clmm::decode_tick_array(&a.data)?.1,
+                        None => vec![],
+                    };
+                    p.clmm.add_tick_array(start, raydium_clmm::TICK_ARRAY_SIZE, ticks);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// a_to_b = 输入为 mints()[0]；vault 为两个金库的余额
+    pub fn swap(&self, a_to_b: bool, amount_in: u64, vault: [u64; 2]) -> Result<Swap> {
+        match self {
+            Pool::RaydiumAmmV4(p) => p.swap(a_to_b, amount_in, vault),
+            Pool::TokenSwap(p) => p.swap(a_to_b, amount_in, vault),
+            Pool::Whirlpool(p) | Pool::RaydiumClmm(RaydiumClmm { clmm: p, .. }) => {
+                let s = p.swap(a_to_b, amount_in)?;
+                let available = if a_to_b { vault[1] } else { vault[0] };
+                if s.amount_out > available {
+                    bail!("clmm: output {} exceeds vault balance {}", s.amount_out, available);
+                }
+                Ok(Swap { amount_out: s.amount_out, fee: s.fee, price_impact_pct: s.price_impact_pct })
+            }
+        }
+    }
+}
+
+/// 当前 tick array 及两侧各 TICK_ARRAYS_PER_SIDE 个的起点
+fn tick_array_starts(p: &ClmmPool, size: i32, start_of: fn(i32, u16) -> i32) -> Vec<i32> {
+    let span = size * p.tick_spacing as i32;
+    let current = start_of(p.tick_current, p.tick_spacing);
+    (-TICK_ARRAYS_PER_SIDE..=TICK_ARRAYS_PER_SIDE)
+        .map(|i| current + i * span)
+        .filter(|s| *s + span > crate::clmm::math::MIN_TICK && *s <= crate::clmm::math::MAX_TICK)
+        .collect()
+}
+
+/// 已加载的池子：解码结果 + 金库余额
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolState {
+    pub key: Pubkey,
+    pub pool: Pool,
+    pub vault: [u64; 2],
+    /// 最近一次更新所在的 slot（通过 RPC 批量加载时为 0）
+    pub slot: u64,
+    /// 最近一次更新时 `PoolCache` 的版本号
+    pub version: u64,
+}
+
+impl PoolState {
+    /// 输入 input_mint 时的方向；池子不含这对 mint 返回 None
+    pub fn direction(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Option<bool> {
+        match self.pool.mints() {
+            [a, b] if a == *input_mint && b == *output_mint => Some(true),
+            [a, b] if b == *input_mint && a == *output_mint => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn quote(&self, input_mint: &Pubkey, output_mint: &Pubkey, amount_in: u64) -> Result<Swap> {
+        let a_to_b = self
+            .direction(input_mint, output_mint)
+            .ok_or_else(|| anyhow!("pool {} does not trade {} → {}", self.key, input_mint, output_mint))?;
+        self.pool.swap(a_to_b, amount_in, self.vault)
+    }
+}
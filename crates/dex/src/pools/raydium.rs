@@ -0,0 +1,94 @@
+use anyhow::{anyhow, bail, Result};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::math::{constant_product_out, fee_ceil, price_impact_pct};
+use super::{pubkey_at, u64_at, Swap};
+
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// AmmInfo 账户长度
+pub const AMM_INFO_LEN: usize = 752;
+
+/* AmmInfo 字段偏移（全部 u64 / Pubkey，小端） */
+const STATUS: usize = 0;
+const COIN_DECIMALS: usize = 32;
+const PC_DECIMALS: usize = 40;
+const SWAP_FEE_NUMERATOR: usize = 176;
+const SWAP_FEE_DENOMINATOR: usize = 184;
+const NEED_TAKE_PNL_COIN: usize = 192;
+const NEED_TAKE_PNL_PC: usize = 200;
+const COIN_VAULT: usize = 336;
+const PC_VAULT: usize = 368;
+const COIN_MINT: usize = 400;
+const PC_MINT: usize = 432;
+const LP_MINT: usize = 464;
+const OPEN_ORDERS: usize = 496;
+
+/// 可以 swap 的状态：Initialized / SwapOnly / WaitingTrade
+const SWAPPABLE_STATUS: [u64; 3] = [1, 6, 7];
+
+/// Raydium AMM v4 池子（恒定乘积，手续费从输入扣）
+///
+/// 储备 = 金库余额 - 待提取的协议收益（need_take_pnl）；OpenBook 挂单部分不计入
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaydiumAmmV4 {
+    pub status: u64,
+    pub coin_decimals: u8,
+    pub pc_decimals: u8,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+}
+
+impl RaydiumAmmV4 {
+    pub fn decode(d: &[u8]) -> Result<Self> {
+        if d.len() != AMM_INFO_LEN {
+            bail!("raydium amm v4: expected {} bytes, got {}", AMM_INFO_LEN, d.len());
+        }
+        Ok(Self {
+            status: u64_at(d, STATUS)?,
+            coin_decimals: u64_at(d, COIN_DECIMALS)? as u8,
+            pc_decimals: u64_at(d, PC_DECIMALS)? as u8,
+            swap_fee_numerator: u64_at(d, SWAP_FEE_NUMERATOR)?,
+            swap_fee_denominator: u64_at(d, SWAP_FEE_DENOMINATOR)?,
+            need_take_pnl_coin: u64_at(d, NEED_TAKE_PNL_COIN)?,
+            need_take_pnl_pc: u64_at(d, NEED_TAKE_PNL_PC)?,
+            coin_vault: pubkey_at(d, COIN_VAULT)?,
+            pc_vault: pubkey_at(d, PC_VAULT)?,
+            coin_mint: pubkey_at(d, COIN_MINT)?,
+            pc_mint: pubkey_at(d, PC_MINT)?,
+            lp_mint: pubkey_at(d, LP_MINT)?,
+            open_orders: pubkey_at(d, OPEN_ORDERS)?,
+        })
+    }
+
+    /// 扣除待提取收益后的 [coin, pc] 储备
+    pub fn reserves(&self, vault: [u64; 2]) -> [u64; 2] {
+        [
+            vault[0].saturating_sub(self.need_take_pnl_coin),
+            vault[1].saturating_sub(self.need_take_pnl_pc),
+        ]
+    }
+
+    pub fn swap(&self, coin_to_pc: bool, amount_in: u64, vault: [u64; 2]) -> Result<Swap> {
+        if !SWAPPABLE_STATUS.contains(&self.status) {
+            bail!("raydium pool status {} does not allow swaps", self.status);
+        }
+        let [coin, pc] = self.reserves(vault);
+        let (r_in, r_out) = if coin_to_pc { (coin, pc) } else { (pc, coin) };
+
+        let fee = fee_ceil(amount_in, self.swap_fee_numerator, self.swap_fee_denominator)
+            .ok_or_else(|| anyhow!("raydium: bad fee {}/{}", self.swap_fee_numerator, self.swap_fee_denominator))?;
+        let net = amount_in.saturating_sub(fee);
+        let amount_out = constant_product_out(net, r_in, r_out).ok_or_else(|| anyhow!("raydium: empty reserves"))?;
+        let price_impact_pct = price_impact_pct(net, amount_out, r_out as f64 / r_in as f64);
+        Ok(Swap { amount_out, fee, price_impact_pct })
+    }
+}
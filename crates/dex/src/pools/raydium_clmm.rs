@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::clmm::{ClmmPool, Tick};
+use super::{i128_at, i32_at, pubkey_at, u128_at, u16_at, u32_at};
+
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+
+/// PoolState 账户长度（含 8 字节 discriminator）
+pub const POOL_STATE_LEN: usize = 1544;
+/// AmmConfig 账户长度
+pub const AMM_CONFIG_LEN: usize = 117;
+/// 每个 tick array 的 tick 数
+pub const TICK_ARRAY_SIZE: i32 = 60;
+pub const TICK_ARRAY_LEN: usize = 10240;
+const TICK_LEN: usize = 168;
+
+/* PoolState 字段偏移 */
+const AMM_CONFIG: usize = 9;
+const MINT_0: usize = 73;
+const MINT_1: usize = 105;
+const VAULT_0: usize = 137;
+const VAULT_1: usize = 169;
+const TICK_SPACING: usize = 235;
+const LIQUIDITY: usize = 237;
+const SQRT_PRICE: usize = 253;
+const TICK_CURRENT: usize = 269;
+
+/* AmmConfig 偏移 */
+const TRADE_FEE_RATE: usize = 47;
+
+/* TickArrayState 偏移；tick 内：index(i32) / liquidity_net(i128) / liquidity_gross(u128) */
+const START_TICK: usize = 40;
+const TICKS: usize = 44;
+
+/// Raydium CLMM 池子：费率在单独的 AmmConfig 账户里
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaydiumClmm {
+    pub amm_config: Pubkey,
+    pub clmm: ClmmPool,
+}
+
+/// 解码 PoolState；fee_rate 先置 0，加载 AmmConfig 后再填
+pub fn decode(d: &[u8]) -> Result<RaydiumClmm> {
+    if d.len() != POOL_STATE_LEN {
+        bail!("raydium clmm: expected {} bytes, got {}", POOL_STATE_LEN, d.len());
+    }
+    let tick_spacing = u16_at(d, TICK_SPACING)?;
+    if tick_spacing == 0 {
+        bail!("raydium clmm: zero tick spacing");
+    }
+    let pool = ClmmPool {
+        mint_a: pubkey_at(d, MINT_0)?,
+        mint_b: pubkey_at(d, MINT_1)?,
+        vault_a: pubkey_at(d, VAULT_0)?,
+        vault_b: pubkey_at(d, VAULT_1)?,
+        tick_spacing,
+        fee_rate: 0,
+        liquidity: u128_at(d, LIQUIDITY)?,
+        sqrt_price: u128_at(d, SQRT_PRICE)?,
+        tick_current: i32_at(d, TICK_CURRENT)?,
+        ticks: vec![],
+        covered: None,
+    };
+    Ok(RaydiumClmm { amm_config: pubkey_at(d, AMM_CONFIG)?, clmm: pool })
+}
+
+/// AmmConfig.trade_fee_rate（百万分之一）
+pub fn decode_fee_rate(d: &[u8]) -> Result<u32> {
+    if d.len() != AMM_CONFIG_LEN {
+        bail!("raydium amm config: expected {} bytes, got {}", AMM_CONFIG_LEN, d.len());
+    }
+    u32_at(d, TRADE_FEE_RATE)
+}
+
+pub fn tick_array_start(tick: i32, tick_spacing: u16) -> i32 {
+    let span = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(span) * span
+}
+
+/// tick array PDA：["tick_array", pool, start 的大端字节]
+pub fn tick_array_address(pool: &Pubkey, start: i32) -> Pubkey {
+    Pubkey::find_program_address(&[b"tick_array", pool.as_ref(), &start.to_be_bytes()], &RAYDIUM_CLMM_PROGRAM_ID).0
+}
+
+/// 解码 TickArrayState：liquidity_gross 非 0 的 tick 视为已初始化
+pub fn decode_tick_array(d: &[u8]) -> Result<(i32, Vec<Tick>)> {
+    if d.len() != TICK_ARRAY_LEN {
+        bail!("raydium clmm tick array: expected {} bytes, got {}", TICK_ARRAY_LEN, d.len());
+    }
+    let start = i32_at(d, START_TICK)?;
+    let mut ticks = Vec::new();
+    for i in 0..TICK_ARRAY_SIZE as usize {
+        let off = TICKS + i * TICK_LEN;
+        if u128_at(d, off + 20)? != 0 {
+            ticks.push(Tick { index: i32_at(d, off)?, liquidity_net: i128_at(d, off + 4)? });
+        }
+    }
+    Ok((start, ticks))
+}
@@ -0,0 +1,100 @@
+use anyhow::{anyhow, bail, Result};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::math::{constant_product_out, fee_floor_min_one, price_impact_pct, stable_out};
+use super::{pubkey_at, u64_at, Swap};
+
+pub const ORCA_TOKEN_SWAP_V2_PROGRAM_ID: Pubkey = pubkey!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+pub const ORCA_TOKEN_SWAP_V1_PROGRAM_ID: Pubkey = pubkey!("DjVE6JNiYqPL2QXyCUUh8rNjHrbz9hXHNYt99MQ59qw1");
+pub const SPL_TOKEN_SWAP_PROGRAM_ID: Pubkey = pubkey!("SwaPpA9LAaLfeLi3a68M4DjnLqgtticKg6CnyNwgAC8");
+
+/// SwapV1 账户长度（含 1 字节版本号）
+pub const SWAP_V1_LEN: usize = 324;
+
+/* SwapV1 字段偏移 */
+const IS_INITIALIZED: usize = 1;
+const TOKEN_A: usize = 35;
+const TOKEN_B: usize = 67;
+const POOL_MINT: usize = 99;
+const MINT_A: usize = 131;
+const MINT_B: usize = 163;
+const TRADE_FEE_NUMERATOR: usize = 227;
+const TRADE_FEE_DENOMINATOR: usize = 235;
+const OWNER_TRADE_FEE_NUMERATOR: usize = 243;
+const OWNER_TRADE_FEE_DENOMINATOR: usize = 251;
+const CURVE_TYPE: usize = 291;
+const CURVE_PARAMS: usize = 292;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    ConstantProduct,
+    /// amp 为放大系数
+    Stable { amp: u64 },
+}
+
+/// SPL token-swap 布局的池子（Orca v1/v2 及官方 token-swap）
+///
+/// 手续费 = 交易费 + 协议费，均从输入扣，各自向下取整但非零费率至少收 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSwap {
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub pool_mint: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub trade_fee: (u64, u64),
+    pub owner_trade_fee: (u64, u64),
+    pub curve: Curve,
+}
+
+impl TokenSwap {
+    pub fn decode(d: &[u8]) -> Result<Self> {
+        if d.len() != SWAP_V1_LEN {
+            bail!("token-swap: expected {} bytes, got {}", SWAP_V1_LEN, d.len());
+        }
+        if d[IS_INITIALIZED] != 1 {
+            bail!("token-swap: pool not initialized");
+        }
+        let curve = match d[CURVE_TYPE] {
+            0 => Curve::ConstantProduct,
+            2 => Curve::Stable { amp: u64_at(d, CURVE_PARAMS)? },
+            t => bail!("token-swap: unsupported curve type {}", t),
+        };
+        Ok(Self {
+            vault_a: pubkey_at(d, TOKEN_A)?,
+            vault_b: pubkey_at(d, TOKEN_B)?,
+            pool_mint: pubkey_at(d, POOL_MINT)?,
+            mint_a: pubkey_at(d, MINT_A)?,
+            mint_b: pubkey_at(d, MINT_B)?,
+            trade_fee: (u64_at(d, TRADE_FEE_NUMERATOR)?, u64_at(d, TRADE_FEE_DENOMINATOR)?),
+            owner_trade_fee: (u64_at(d, OWNER_TRADE_FEE_NUMERATOR)?, u64_at(d, OWNER_TRADE_FEE_DENOMINATOR)?),
+            curve,
+        })
+    }
+
+    pub fn swap(&self, a_to_b: bool, amount_in: u64, vault: [u64; 2]) -> Result<Swap> {
+        let bad_fee = || anyhow!("token-swap: bad fee");
+        let fee = fee_floor_min_one(amount_in, self.trade_fee.0, self.trade_fee.1).ok_or_else(bad_fee)?
+            + fee_floor_min_one(amount_in, self.owner_trade_fee.0, self.owner_trade_fee.1).ok_or_else(bad_fee)?;
+        let net = amount_in.checked_sub(fee).ok_or_else(|| anyhow!("token-swap: amount below fee"))?;
+
+        let (r_in, r_out) = if a_to_b { (vault[0], vault[1]) } else { (vault[1], vault[0]) };
+        let amount_out = match self.curve {
+            Curve::ConstantProduct => constant_product_out(net, r_in, r_out),
+            Curve::Stable { amp } => stable_out(amp, net, r_in, r_out),
+        }
+        .ok_or_else(|| anyhow!("token-swap: cannot compute output (reserves {}/{})", r_in, r_out))?;
+        Ok(Swap { amount_out, fee, price_impact_pct: price_impact_pct(net, amount_out, self.spot(r_in, r_out)) })
+    }
+
+    /// 边际价格；stable 曲线用极小的一笔试算
+    fn spot(&self, r_in: u64, r_out: u64) -> f64 {
+        match self.curve {
+            Curve::ConstantProduct => r_out as f64 / r_in as f64,
+            Curve::Stable { amp } => {
+                let probe = (r_in / 1_000_000).max(1);
+                stable_out(amp, probe, r_in, r_out).map_or(0.0, |o| o as f64 / probe as f64)
+            }
+        }
+    }
+}
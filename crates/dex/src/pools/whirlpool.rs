@@ -0,0 +1,88 @@
+use anyhow::{bail, Result};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::clmm::{ClmmPool, Tick};
+use super::{i128_at, i32_at, pubkey_at, u128_at, u16_at};
+
+pub const WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Whirlpool 账户长度（含 8 字节 discriminator）
+pub const WHIRLPOOL_LEN: usize = 653;
+/// 每个 tick array 的 tick 数
+pub const TICK_ARRAY_SIZE: i32 = 88;
+/// 定长 TickArray 账户长度
+pub const TICK_ARRAY_LEN: usize = 9988;
+const TICK_LEN: usize = 113;
+
+/* Whirlpool 字段偏移 */
+const TICK_SPACING: usize = 41;
+const FEE_RATE: usize = 45;
+const LIQUIDITY: usize = 49;
+const SQRT_PRICE: usize = 65;
+const TICK_CURRENT: usize = 81;
+const MINT_A: usize = 101;
+const VAULT_A: usize = 133;
+const MINT_B: usize = 181;
+const VAULT_B: usize = 213;
+
+/* TickArray 偏移 */
+const START_TICK: usize = 8;
+const TICKS: usize = 12;
+
+/// 解码 Whirlpool 账户（fee_rate 单位即百万分之一）
+pub fn decode(d: &[u8]) -> Result<ClmmPool> {
+    if d.len() != WHIRLPOOL_LEN {
+        bail!("whirlpool: expected {} bytes, got {}", WHIRLPOOL_LEN, d.len());
+    }
+    let tick_spacing = u16_at(d, TICK_SPACING)?;
+    if tick_spacing == 0 {
+        bail!("whirlpool: zero tick spacing");
+    }
+    Ok(ClmmPool {
+        mint_a: pubkey_at(d, MINT_A)?,
+        mint_b: pubkey_at(d, MINT_B)?,
+        vault_a: pubkey_at(d, VAULT_A)?,
+        vault_b: pubkey_at(d, VAULT_B)?,
+        tick_spacing,
+        fee_rate: u16_at(d, FEE_RATE)? as u32,
+        liquidity: u128_at(d, LIQUIDITY)?,
+        sqrt_price: u128_at(d, SQRT_PRICE)?,
+        tick_current: i32_at(d, TICK_CURRENT)?,
+        ticks: vec![],
+        covered: None,
+    })
+}
+
+/// 包含 tick 的 tick array 起点
+pub fn tick_array_start(tick: i32, tick_spacing: u16) -> i32 {
+    let span = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(span) * span
+}
+
+/// tick array PDA：["tick_array", whirlpool, start 的十进制字符串]
+pub fn tick_array_address(whirlpool: &Pubkey, start: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start.to_string().as_bytes()],
+        &WHIRLPOOL_PROGRAM_ID,
+    )
+    .0
+}
+
+/// 解码 TickArray：返回 (start, 已初始化的 tick)
+pub fn decode_tick_array(d: &[u8], tick_spacing: u16) -> Result<(i32, Vec<Tick>)> {
+    if d.len() != TICK_ARRAY_LEN {
+        bail!("whirlpool tick array: expected {} bytes, got {}", TICK_ARRAY_LEN, d.len());
+    }
+    let start = i32_at(d, START_TICK)?;
+    let mut ticks = Vec::new();
+    for i in 0..TICK_ARRAY_SIZE as usize {
+        let off = TICKS + i * TICK_LEN;
+        if d[off] == 1 {
+            ticks.push(Tick {
+                index: start + i as i32 * tick_spacing as i32,
+                liquidity_net: i128_at(d, off + 1)?,
+            });
+        }
+    }
+    Ok((start, ticks))
+}
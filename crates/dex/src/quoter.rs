@@ -0,0 +1,199 @@
+#![allow(async_fn_in_trait)]
+use std::collections::HashSet;
+use std::sync::RwLock;
+use anyhow::{anyhow, Context, Result};
+use arbitrage::prelude::{NoRoute, Quote, QuoteProvider, RouteLeg};
+use solana_sdk::pubkey::Pubkey;
+use tracing::warn;
+use tokio::sync::mpsc;
+use crate::account::AccountSource;
+use crate::cache::{Applied, PoolCache, PoolChange};
+use crate::pools::{token_amount, Pool, PoolState, Swap};
+use crate::stream::{AccountStream, AccountUpdate};
+
+/// 直连 DEX 报价器：池子和金库余额加载到内存后，报价只做本地计算
+///
+/// 同一对 mint 有多个池子时取输出最多的那个；CLMM 池子额外加载当前价格附近的 tick array，
+/// 超出已加载区间的大额兑换直接报错而不是给出错误的数量。
+/// 状态可以 `refresh` 整体重拉，也可以用 `watch` 订阅账户推送增量更新
+pub struct DexQuoter<S> {
+    source: S,
+    cache: RwLock<PoolCache>,
+}
+
+impl<S: AccountSource> DexQuoter<S> {
+    pub fn new(source: S) -> Self {
+        Self { source, cache: RwLock::new(PoolCache::new()) }
+    }
+
+    /// 拉取并解码池子账户、附属账户及金库；返回成功加载的数量（解码失败的池子会被跳过）
+    pub async fn add_pools(&self, keys: &[Pubkey]) -> Result<usize> {
+        let accounts = self.source.accounts(keys).await?;
+        let mut decoded = Vec::new();
+        for (key, acc) in keys.iter().zip(accounts) {
+            let Some(acc) = acc else {
+                warn!("pool {} not found", key);
+                continue;
+            };
+            match Pool::decode(&acc) {
+                Ok(pool) => decoded.push((*key, acc, pool.extra_accounts(key), pool.vaults())),
+                Err(e) => warn!("skip pool {}: {}", key, e),
+            }
+        }
+
+        // tick array / AmmConfig 和金库各一次性拉取，再按池子切开
+        let extra_keys: Vec<Pubkey> = decoded.iter().flat_map(|d| d.2.iter().copied()).collect();
+        let mut extra = if extra_keys.is_empty() { vec![] } else { self.source.accounts(&extra_keys).await? }.into_iter();
+        let vault_keys: Vec<Pubkey> = decoded.iter().flat_map(|d| d.3).collect();
+        let balances = self.vault_balances(&vault_keys).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let mut n = 0;
+        for ((key, raw, keys, _), vault) in decoded.into_iter().zip(balances.chunks(2)) {
+            let accs = keys.iter().copied().zip(extra.by_ref().take(keys.len())).collect();
+            match cache.insert(key, raw, accs, [vault[0], vault[1]], 0) {
+                Ok(_) => n += 1,
+                Err(e) => warn!("skip pool {}: {}", key, e),
+            }
+        }
+        Ok(n)
+    }
+
+    /// 重新拉取所有池子（CLMM 的价格/流动性/tick 都在池子账户里，只刷金库不够）
+    pub async fn refresh(&self) -> Result<()> {
+        let keys = self.cache.read().unwrap().keys();
+        self.add_pools(&keys).await?;
+        Ok(())
+    }
+
+    async fn vault_balances(&self, keys: &[Pubkey]) -> Result<Vec<u64>> {
+        let accounts = self.source.accounts(keys).await?;
+        keys.iter()
+            .zip(accounts)
+            .map(|(k, a)| {
+                let a = a.with_context(|| format!("vault {} not found", k))?;
+                token_amount(&a.data).with_context(|| format!("vault {}", k))
+            })
+            .collect()
+    }
+
+    /// 应用一次账户推送，返回状态变了的池子（共用账户时可能有多个）；
+    /// 价格移到未加载的 tick array 时顺带从 source 补拉
+    pub async fn apply_update(&self, u: &AccountUpdate) -> Result<Vec<PoolChange>> {
+        let applied = self.cache.write().unwrap().apply(u);
+        let mut changes = Vec::new();
+        for a in applied {
+            let a = match a {
+                Applied::Missing { pool, keys } => {
+                    let accs = self.source.accounts(&keys).await?;
+                    self.cache.write().unwrap().fill(&pool, keys.into_iter().zip(accs).collect(), u.slot)?
+                }
+                a => a,
+            };
+            if let Applied::Changed(c) = a {
+                changes.push(c);
+            }
+        }
+        Ok(changes)
+    }
+
+    /// 订阅缓存里所有账户，把推送应用到缓存，每次池子变化发到 changes
+    ///
+    /// 新用到的 tick array 会追加订阅；推送流结束或 changes 的接收端关闭后返回
+    pub async fn watch<T: AccountStream>(&self, stream: &T, changes: mpsc::Sender<PoolChange>) -> Result<()> {
+        let (tx, mut rx) = mpsc::channel(1024);
+        let mut subscribed: HashSet<Pubkey> = self.accounts().into_iter().collect();
+        // 只留弱引用：所有订阅结束后 rx 才能收到 None
+        let weak = tx.downgrade();
+        stream.subscribe(&subscribed.iter().copied().collect::<Vec<_>>(), tx).await?;
+
+        while let Some(u) = rx.recv().await {
+            let changed = match self.apply_update(&u).await {
+                Ok(c) if c.is_empty() => continue,
+                Ok(c) => c,
+                Err(e) => {
+                    warn!("account {} @{}: {}", u.key, u.slot, e);
+                    continue;
+                }
+            };
+            let new: Vec<Pubkey> = self.accounts().into_iter().filter(|k| !subscribed.contains(k)).collect();
+            if !new.is_empty() {
+                if let Some(tx) = weak.upgrade() {
+                    stream.subscribe(&new, tx).await?;
+                }
+                subscribed.extend(new);
+            }
+            for c in changed {
+                if changes.send(c).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 缓存涉及的所有账户（池子、金库、附属账户）
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        self.cache.read().unwrap().accounts()
+    }
+
+    pub fn pool(&self, key: &Pubkey) -> Option<PoolState> {
+        self.cache.read().unwrap().get(key).cloned()
+    }
+
+    /// 缓存版本号，每次池子状态变化 +1
+    pub fn version(&self) -> u64 {
+        self.cache.read().unwrap().version()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 所有池子里输出最多的一个
+    fn best(&self, input: &Pubkey, output: &Pubkey, amount: u64) -> Result<(PoolState, Swap)> {
+        let cache = self.cache.read().unwrap();
+        let mut best: Option<(PoolState, Swap)> = None;
+        let mut last_err = None;
+        for p in cache.states().filter(|p| p.direction(input, output).is_some()) {
+            match p.quote(input, output, amount) {
+                Ok(s) if best.as_ref().is_none_or(|b| s.amount_out > b.1.amount_out) => best = Some((p.clone(), s)),
+                Ok(_) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match (best, last_err) {
+            (Some(b), _) => Ok(b),
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(NoRoute(format!("no pool for {} → {}", input, output)).into()),
+        }
+    }
+}
+
+impl<S: AccountSource> QuoteProvider for DexQuoter<S> {
+    async fn quote(&self, input_mint: String, output_mint: String, amount: u64) -> Result<Quote> {
+        let input: Pubkey = input_mint.parse().map_err(|_| anyhow!("bad mint {}", input_mint))?;
+        let output: Pubkey = output_mint.parse().map_err(|_| anyhow!("bad mint {}", output_mint))?;
+        let (pool, swap) = self.best(&input, &output, amount)?;
+        let (out, fee) = (swap.amount_out, swap.fee);
+
+        let mut q = Quote::simple(input_mint.clone(), output_mint.clone(), amount, out);
+        q.price_impact_pct = swap.price_impact_pct;
+        q.route = vec![RouteLeg {
+            amm_label: pool.pool.label().to_string(),
+            amm_key: pool.key.to_string(),
+            input_mint: input_mint.clone(),
+            output_mint,
+            in_amount: amount,
+            out_amount: out,
+            fee_amount: fee,
+            fee_mint: input_mint,
+            percent: 100,
+        }];
+        Ok(q)
+    }
+}
@@ -0,0 +1,214 @@
+#![allow(async_fn_in_trait)]
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use anyhow::{anyhow, Context, Result};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use crate::account::{parse_account, RawAccount};
+
+/// 一次账户变更；account 为 None 表示账户被关闭
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountUpdate {
+    pub key: Pubkey,
+    pub slot: u64,
+    pub account: Option<RawAccount>,
+}
+
+/// 账户变更推送（websocket、文件回放……），与按需拉取的 `AccountSource` 配套使用
+pub trait AccountStream: Send + Sync {
+    /// 订阅 keys 的变更并推送到 tx；可多次调用追加订阅，tx 的接收端关闭后后台任务退出
+    async fn subscribe(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()>;
+}
+
+impl<S: AccountStream + ?Sized> AccountStream for &S {
+    async fn subscribe(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        (**self).subscribe(keys, tx).await
+    }
+}
+
+/* ---------------- 文件回放（测试/离线） ---------------- */
+
+/// 从 NDJSON 文件按顺序回放账户变更，每行 `{ slot, pubkey, owner, data: [base64, "base64"] }`，
+/// 没有 data 表示账户被关闭；只推送已订阅的账户，回放完即结束
+#[derive(Debug, Clone)]
+pub struct FileAccounts {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl FileAccounts {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf(), interval: Duration::ZERO }
+    }
+
+    /// 相邻两条更新之间的间隔（模拟出块节奏）
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    fn read(&self) -> Result<Vec<AccountUpdate>> {
+        let text = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("read account updates {}", self.path.display()))?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| parse_line(l).with_context(|| format!("{}:{}", self.path.display(), i + 1)))
+            .collect()
+    }
+}
+
+fn parse_line(line: &str) -> Result<AccountUpdate> {
+    let v: Value = serde_json::from_str(line)?;
+    let key = v["pubkey"].as_str().context("missing pubkey")?.parse()?;
+    let slot = v["slot"].as_u64().context("missing slot")?;
+    let account = if v.get("data").is_some_and(|d| !d.is_null()) { parse_account(&v)? } else { None };
+    Ok(AccountUpdate { key, slot, account })
+}
+
+impl AccountStream for FileAccounts {
+    async fn subscribe(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        let wanted: HashSet<Pubkey> = keys.iter().copied().collect();
+        let updates: Vec<AccountUpdate> = self.read()?.into_iter().filter(|u| wanted.contains(&u.key)).collect();
+        let interval = self.interval;
+        tokio::spawn(async move {
+            for u in updates {
+                if !interval.is_zero() {
+                    tokio::time::sleep(interval).await;
+                }
+                if tx.send(u).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/* ---------------- websocket：accountSubscribe ---------------- */
+
+/// 断线重连的最长等待
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// 每条连接最多订阅几个账户，超出的分到新连接
+const MAX_SUBSCRIPTIONS_PER_CONN: usize = 100;
+
+/// Solana RPC websocket（`accountSubscribe`），断线后自动重连并重新订阅
+#[derive(Debug, Clone)]
+pub struct WsAccounts {
+    url: String,
+}
+
+impl WsAccounts {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+type WsConn = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// 连接并为每个 key 发一条 accountSubscribe（请求 id = key 的下标）
+async fn connect(url: &str, keys: &[Pubkey]) -> Result<WsConn> {
+    let (mut ws, _) = tokio_tungstenite::connect_async(url).await.with_context(|| format!("connect {}", url))?;
+    for (i, k) in keys.iter().enumerate() {
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": i,
+            "method": "accountSubscribe",
+            "params": [k.to_string(), { "encoding": "base64", "commitment": "confirmed" }],
+        });
+        ws.send(Message::Text(req.to_string())).await?;
+    }
+    Ok(ws)
+}
+
+/// 读到连接断开为止；Ok(true) = 接收端已关闭，不必重连
+async fn pump(ws: &mut WsConn, keys: &[Pubkey], tx: &mpsc::Sender<AccountUpdate>) -> Result<bool> {
+    // 订阅 id → key
+    let mut subs: HashMap<u64, Pubkey> = HashMap::new();
+    while let Some(msg) = ws.next().await {
+        let text = match msg? {
+            Message::Text(t) => t,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let v: Value = serde_json::from_str(&text)?;
+
+        if let Some(id) = v.get("id").and_then(Value::as_u64) {
+            let key = keys.get(id as usize).ok_or_else(|| anyhow!("unexpected response id {}", id))?;
+            match v.get("result").and_then(Value::as_u64) {
+                Some(sub) => {
+                    subs.insert(sub, *key);
+                }
+                None => warn!("accountSubscribe {} failed: {}", key, v["error"]),
+            }
+            continue;
+        }
+        if v["method"] != "accountNotification" {
+            debug!("ignored ws message: {}", text);
+            continue;
+        }
+        let params = &v["params"];
+        let Some(key) = params["subscription"].as_u64().and_then(|s| subs.get(&s)) else {
+            continue;
+        };
+        let update = AccountUpdate {
+            key: *key,
+            slot: params["result"]["context"]["slot"].as_u64().context("notification missing slot")?,
+            account: parse_account(&params["result"]["value"])?,
+        };
+        if tx.send(update).await.is_err() {
+            return Ok(true);
+        }
+    }
+    Ok(tx.is_closed())
+}
+
+impl WsAccounts {
+    /// 第一次连接失败直接报错；之后的断线在后台重连
+    async fn spawn(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        let mut ws = connect(&self.url, keys).await?;
+        let (url, keys) = (self.url.clone(), keys.to_vec());
+        tokio::spawn(async move {
+            let mut delay = Duration::from_secs(1);
+            loop {
+                match pump(&mut ws, &keys, &tx).await {
+                    Ok(true) => return,
+                    Ok(false) => warn!("ws {} closed, reconnecting", url),
+                    Err(e) => warn!("ws {}: {}, reconnecting", url, e),
+                }
+                loop {
+                    tokio::time::sleep(delay).await;
+                    if tx.is_closed() {
+                        return;
+                    }
+                    match connect(&url, &keys).await {
+                        Ok(c) => {
+                            ws = c;
+                            delay = Duration::from_secs(1);
+                            break;
+                        }
+                        Err(e) => {
+                            warn!("ws reconnect failed: {}", e);
+                            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                        }
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl AccountStream for WsAccounts {
+    async fn subscribe(&self, keys: &[Pubkey], tx: mpsc::Sender<AccountUpdate>) -> Result<()> {
+        for chunk in keys.chunks(MAX_SUBSCRIPTIONS_PER_CONN) {
+            self.spawn(chunk, tx.clone()).await?;
+        }
+        Ok(())
+    }
+}
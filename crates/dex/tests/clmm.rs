@@ -0,0 +1,199 @@
+use anyhow::Result;
+use arbitrage::prelude::QuoteProvider;
+use base64::Engine as _;
+use dex::clmm::math::{sqrt_price_at_tick, MAX_TICK, MIN_TICK, Q64};
+use dex::pools::{whirlpool, RaydiumClmm};
+use dex::{AccountSource, ClmmPool, DexQuoter, MemoryAccounts, Pool, RawAccount};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const WHIRLPOOL: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+const RAYDIUM_CLMM: &str = "2QdhepnKRTLjjSqPL1PtKNwqrUkoLee5Gqs8bvZhRdMv";
+
+/* ---- fixture：[池子, 金库 A, 金库 B, 附属账户…]，格式同 getAccountInfo(base64) ----
+ * 手工构造，不是主网快照：池子/AmmConfig/tick array 按程序布局编码，地址借用主网池子，
+ * 价格、流动性和 tick 分布是编出来的，期望值来自虚拟储备公式和区间断言，不代表链上 amount_out */
+#[derive(Deserialize)]
+struct Fixture { pubkey: String, owner: String, data: (String, String) }
+
+fn pk(s: &str) -> Pubkey { s.parse().unwrap() }
+
+fn fixture(name: &str) -> Vec<(Pubkey, RawAccount)> {
+    let text = std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    let v: Vec<Fixture> = serde_json::from_str(&text).unwrap();
+    v.into_iter()
+        .map(|f| {
+            let data = base64::engine::general_purpose::STANDARD.decode(f.data.0).unwrap();
+            (pk(&f.pubkey), RawAccount { owner: pk(&f.owner), data })
+        })
+        .collect()
+}
+
+fn source(names: &[&str]) -> MemoryAccounts {
+    let src = MemoryAccounts::default();
+    for name in names {
+        for (k, a) in fixture(name) {
+            src.insert(k, a);
+        }
+    }
+    src
+}
+
+/// 按 extra_accounts 从 fixture 里取账户，得到可报价的池子
+async fn load(name: &str) -> (Pool, [u64; 2]) {
+    let src = source(&[name]);
+    let accs = fixture(name);
+    let mut pool = Pool::decode(&accs[0].1).unwrap();
+    let extra = src.accounts(&pool.extra_accounts(&accs[0].0)).await.unwrap();
+    pool.apply_extra(&extra).unwrap();
+    let vault = [accs[1].1.data.clone(), accs[2].1.data.clone()].map(|d| dex::pools::token_amount(&d).unwrap());
+    (pool, vault)
+}
+
+fn clmm(pool: &Pool) -> &ClmmPool {
+    match pool {
+        Pool::Whirlpool(p) | Pool::RaydiumClmm(RaydiumClmm { clmm: p, .. }) => p,
+        _ => panic!("not a clmm pool"),
+    }
+}
+
+/* ---- tick → sqrt price：与 1.0001^(t/2) 的浮点值一致，且单调 ---- */
+#[test]
+fn sqrt_price_matches_float() {
+    for t in [MIN_TICK, -200_000, -18_973, -1, 0, 1, 64, 18_973, 200_000, MAX_TICK] {
+        let got = sqrt_price_at_tick(t).unwrap() as f64 / Q64 as f64;
+        let want = 1.0001f64.powf(t as f64 / 2.0);
+        assert!((got / want - 1.0).abs() < 1e-10, "tick {}: {} vs {}", t, got, want);
+    }
+    assert_eq!(sqrt_price_at_tick(0), Some(Q64));
+    assert!(sqrt_price_at_tick(-5) < sqrt_price_at_tick(-4));
+    assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), None);
+}
+
+/* ---- Whirlpool 解码 + tick array PDA ---- */
+#[tokio::test]
+async fn whirlpool_decode() {
+    let accs = fixture("synthetic_whirlpool_sol_usdc.json");
+    let (pool, _) = load("synthetic_whirlpool_sol_usdc.json").await;
+    let p = clmm(&pool);
+    assert_eq!((p.mint_a, p.mint_b), (pk(SOL), pk(USDC)));
+    assert_eq!((p.vault_a, p.vault_b), (accs[1].0, accs[2].0));
+    assert_eq!((p.tick_spacing, p.fee_rate, p.tick_current), (64, 3000, -18_973));
+    assert_eq!(p.liquidity, 4_000_000_000_000);
+
+    // fixture 里的 tick array 正是当前 tick 所在的那个
+    let start = whirlpool::tick_array_start(p.tick_current, p.tick_spacing);
+    assert_eq!(start, -22_528);
+    assert_eq!(whirlpool::tick_array_address(&pk(WHIRLPOOL), start), accs[3].0);
+    assert_eq!(p.ticks.iter().map(|t| t.index).collect::<Vec<_>>(), vec![-20_480, -19_200, -18_752, -17_408]);
+    // 前后各 3 个 array（缺失的也算已覆盖）
+    assert_eq!(p.covered, Some((start - 3 * 5632, start + 4 * 5632)));
+}
+
+/* ---- 不跨 tick：与虚拟储备上的恒定乘积一致 ---- */
+#[tokio::test]
+async fn swap_within_range_matches_constant_product() {
+    let (pool, vault) = load("synthetic_whirlpool_sol_usdc.json").await;
+    let p = clmm(&pool);
+
+    // 虚拟储备 x = L / √P，y = L·√P
+    let x = p.liquidity * Q64 / p.sqrt_price;
+    let y = (p.liquidity * (p.sqrt_price >> 32)) >> 32;
+    let amount = 1_000_000_000u64;
+    let s = pool.swap(true, amount, vault).unwrap();
+    let net = (amount - s.fee) as u128;
+    let cp = y * net / (x + net);
+
+    // 没走到目标价时手续费 = 剩余 - 向上取整的输入，可能比费率算的少 1
+    assert!(s.fee.abs_diff(3_000_000) <= 1, "{}", s.fee);
+    assert!(s.amount_out.abs_diff(cp as u64) <= 2, "{} vs {}", s.amount_out, cp);
+    // 约 149.5 USDC
+    assert!((149_000_000..150_000_000).contains(&s.amount_out), "{}", s.amount_out);
+    assert!(s.price_impact_pct < 0.05, "{}", s.price_impact_pct);
+
+    let r = p.swap(true, amount).unwrap();
+    assert_eq!(r.ticks_crossed, 0);
+    assert!(r.sqrt_price_after < p.sqrt_price);
+}
+
+/* ---- 跨 tick：流动性减少后滑点变大，反方向同理 ---- */
+#[tokio::test]
+async fn swap_crosses_ticks() {
+    let (pool, _) = load("synthetic_whirlpool_sol_usdc.json").await;
+    let p = clmm(&pool);
+
+    // 200 SOL 会把价格压过 -19200，只剩外侧 1e12 的流动性
+    let big = p.swap(true, 200_000_000_000).unwrap();
+    assert_eq!(big.ticks_crossed, 1);
+    assert!(big.sqrt_price_after < sqrt_price_at_tick(-19_200).unwrap());
+    let no_cross = ClmmPool { ticks: vec![], covered: Some((MIN_TICK, MAX_TICK)), ..p.clone() };
+    assert!(big.amount_out < no_cross.swap(true, 200_000_000_000).unwrap().amount_out);
+
+    // 单价随数量变差，price impact 随数量增大
+    let small = p.swap(true, 1_000_000_000).unwrap();
+    assert!(big.amount_out / 200 < small.amount_out);
+    assert!(big.price_impact_pct > small.price_impact_pct + 1.0, "{}", big.price_impact_pct);
+
+    // 3 万 USDC → SOL 向上穿过 -18752
+    let up = p.swap(false, 30_000_000_000).unwrap();
+    assert_eq!(up.ticks_crossed, 1);
+}
+
+/* ---- 超出已加载 tick array 的兑换报错，而不是给出错误数量 ---- */
+#[tokio::test]
+async fn swap_past_loaded_arrays_errors() {
+    let (pool, vault) = load("synthetic_raydium_clmm_sol_usdc.json").await;
+    let e = pool.swap(true, 10_000_000_000_000, vault).unwrap_err();
+    assert!(e.to_string().contains("past loaded tick arrays"), "{}", e);
+
+    // 只加载一个 array 时，中等数量就已经越界
+    let mut narrow = clmm(&pool).clone();
+    narrow.covered = Some((-19_200, -18_600));
+    narrow.ticks.retain(|t| (-19_200..-18_600).contains(&t.index));
+    assert!(narrow.swap(true, 5_000_000_000_000).is_err());
+}
+
+/* ---- Raydium CLMM：费率来自 AmmConfig，tick array 用大端 PDA ---- */
+#[tokio::test]
+async fn raydium_clmm_decode_and_swap() {
+    let accs = fixture("synthetic_raydium_clmm_sol_usdc.json");
+    let (pool, vault) = load("synthetic_raydium_clmm_sol_usdc.json").await;
+    let Pool::RaydiumClmm(r) = &pool else { panic!("not raydium clmm") };
+    assert_eq!(r.amm_config, accs[3].0);
+    assert_eq!((r.clmm.tick_spacing, r.clmm.fee_rate), (10, 500));
+    // 5 个 tick array 都找到了
+    let extra = pool.extra_accounts(&pk(RAYDIUM_CLMM));
+    assert_eq!(accs[4..].iter().filter(|(k, _)| extra.contains(k)).count(), 5);
+
+    let s = pool.swap(true, 1_000_000_000, vault).unwrap();
+    assert!(s.fee.abs_diff(500_000) <= 1, "{}", s.fee);
+    // 约 150.9 USDC
+    assert!((150_500_000..151_000_000).contains(&s.amount_out), "{}", s.amount_out);
+
+    // 缺少 AmmConfig 的池子无法报价
+    let mut bare = Pool::decode(&accs[0].1).unwrap();
+    assert!(bare.apply_extra(&[None]).is_err());
+}
+
+/* ---- 报价器：CLMM 与其它池子一起比价，附带 price impact ---- */
+#[tokio::test]
+async fn quoter_picks_clmm_pool() -> Result<()> {
+    let src = source(&["synthetic_whirlpool_sol_usdc.json", "synthetic_raydium_clmm_sol_usdc.json", "synthetic_raydium_sol_usdc.json"]);
+    let quoter = DexQuoter::new(&src);
+    let pools = [WHIRLPOOL, RAYDIUM_CLMM, "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"].map(pk);
+    assert_eq!(quoter.add_pools(&pools).await?, 3);
+
+    // Raydium CLMM 价格 151 且费率最低
+    let q = quoter.quote(SOL.into(), USDC.into(), 1_000_000_000).await?;
+    assert_eq!(q.label(), "Raydium CLMM");
+    assert_eq!(q.route[0].amm_key, RAYDIUM_CLMM);
+    assert!(q.route[0].fee_amount.abs_diff(500_000) <= 1);
+    assert!(q.price_impact_pct > 0.0 && q.price_impact_pct < 0.01, "{}", q.price_impact_pct);
+
+    // refresh 后 tick 状态重新加载，报价不变
+    quoter.refresh().await?;
+    assert_eq!(quoter.quote(SOL.into(), USDC.into(), 1_000_000_000).await?.out_amount, q.out_amount);
+    Ok(())
+}
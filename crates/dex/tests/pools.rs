@@ -0,0 +1,202 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use anyhow::Result;
+use arbitrage::prelude::{NoRoute, QuoteProvider};
+use base64::Engine as _;
+use dex::pools::{Curve, RaydiumAmmV4, TokenSwap};
+use dex::{AccountSource, DexQuoter, MemoryAccounts, Pool, RawAccount};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+const RAYDIUM_POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+const ORCA_POOL: &str = "EGZ7tiLeH62TPV1gL8WwbXGzEPa9zmcpVnnkPKKnrE2U";
+
+/* ---- 池子账户 fixture：[池子, 金库 A, 金库 B]，格式同 getAccountInfo(base64) ----
+ * 手工构造，不是主网快照：账户布局按程序定义编码，地址借用主网池子，
+ * 储备/费率/pnl 都是编出来的整数，期望值由本文件的独立公式核对，不代表链上报价 */
+#[derive(Deserialize)]
+struct Fixture { pubkey: String, owner: String, data: (String, String) }
+
+fn pk(s: &str) -> Pubkey { s.parse().unwrap() }
+
+fn fixture(name: &str) -> Vec<(Pubkey, RawAccount)> {
+    let text = std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    let v: Vec<Fixture> = serde_json::from_str(&text).unwrap();
+    v.into_iter()
+        .map(|f| {
+            let data = base64::engine::general_purpose::STANDARD.decode(f.data.0).unwrap();
+            (pk(&f.pubkey), RawAccount { owner: pk(&f.owner), data })
+        })
+        .collect()
+}
+
+const FIXTURES: [&str; 3] = ["synthetic_raydium_sol_usdc.json", "synthetic_orca_sol_usdc.json", "synthetic_orca_usdc_usdt_stable.json"];
+
+/* ---- 记录调用次数的账户源 ---- */
+#[derive(Default)]
+struct Counting { inner: MemoryAccounts, calls: AtomicUsize }
+
+impl AccountSource for Counting {
+    async fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<RawAccount>>> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.inner.accounts(keys).await
+    }
+}
+
+fn source() -> (Counting, Vec<Pubkey>) {
+    let src = Counting::default();
+    let mut pools = Vec::new();
+    for name in FIXTURES {
+        let accs = fixture(name);
+        pools.push(accs[0].0);
+        for (k, a) in accs {
+            src.inner.insert(k, a);
+        }
+    }
+    (src, pools)
+}
+
+/// 独立实现的恒定乘积，用来核对
+fn cp(net_in: u64, r_in: u64, r_out: u64) -> u64 {
+    (r_out as u128 * net_in as u128 / (r_in as u128 + net_in as u128)) as u64
+}
+
+/* ---- Raydium AMM v4：解码 + 扣 pnl 的储备 + 进位收费 ---- */
+#[test]
+fn raydium_decode_and_swap() {
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let Pool::RaydiumAmmV4(p) = Pool::decode(&accs[0].1).unwrap() else { panic!("not raydium") };
+    assert_eq!((p.coin_mint, p.pc_mint), (pk(SOL), pk(USDC)));
+    assert_eq!((p.coin_decimals, p.pc_decimals), (9, 6));
+    assert_eq!((p.swap_fee_numerator, p.swap_fee_denominator), (25, 10_000));
+    assert_eq!((p.coin_vault, p.pc_vault), (accs[1].0, accs[2].0));
+
+    let vault = [50_000_000_000_000, 7_500_000_000_000];
+    let [coin, pc] = p.reserves(vault);
+    assert_eq!(coin, vault[0] - 12_345_678);
+    assert_eq!(pc, vault[1] - 1_234_567);
+
+    // 1 SOL：fee = ceil(1e9 * 25 / 1e4) = 2_500_000
+    let s = p.swap(true, 1_000_000_000, vault).unwrap();
+    assert_eq!(s.fee, 2_500_000);
+    assert_eq!(s.amount_out, cp(997_500_000, coin, pc));
+    // 约 149.6 USDC
+    assert!((149_000_000..150_000_000).contains(&s.amount_out), "{}", s.amount_out);
+
+    // 反方向：1 USDC（fee 向上取整到 2_500）
+    let s = p.swap(false, 1_000_000, vault).unwrap();
+    assert_eq!(s.fee, 2_500);
+    assert_eq!(s.amount_out, cp(997_500, pc, coin));
+
+    // 状态不允许 swap
+    let disabled = RaydiumAmmV4 { status: 2, ..p };
+    assert!(disabled.swap(true, 1_000, vault).is_err());
+}
+
+/* ---- Orca 恒定乘积：交易费 + 协议费，各自至少 1 ---- */
+#[test]
+fn orca_constant_product() {
+    let accs = fixture("synthetic_orca_sol_usdc.json");
+    let p = TokenSwap::decode(&accs[0].1.data).unwrap();
+    assert_eq!(p.curve, Curve::ConstantProduct);
+    assert_eq!((p.mint_a, p.mint_b), (pk(SOL), pk(USDC)));
+    assert_eq!((p.trade_fee, p.owner_trade_fee), ((25, 10_000), (5, 10_000)));
+
+    let vault = [10_000_000_000_000, 1_510_000_000_000];
+    let s = p.swap(true, 1_000_000_000, vault).unwrap();
+    assert_eq!(s.fee, 3_000_000);
+    assert_eq!(s.amount_out, cp(997_000_000, vault[0], vault[1]));
+
+    // 极小数量：两项费用各收 1
+    assert_eq!(p.swap(true, 100, vault).unwrap().fee, 2);
+    assert!(p.swap(true, 1, vault).is_err());
+}
+
+/* ---- Orca 稳定池：接近 1:1，比同储备的恒定乘积好得多 ---- */
+#[test]
+fn orca_stable_swap() {
+    let accs = fixture("synthetic_orca_usdc_usdt_stable.json");
+    let p = TokenSwap::decode(&accs[0].1.data).unwrap();
+    assert_eq!(p.curve, Curve::Stable { amp: 100 });
+
+    let vault = [2_000_000_000_000, 2_100_000_000_000];
+    let amount = 100_000_000_000; // 10 万 USDC
+    let s = p.swap(true, amount, vault).unwrap();
+    let net = amount - s.fee;
+    // 储备 USDT 更多，USDC → USDT 略有溢价
+    assert!(s.amount_out > net, "{} <= {}", s.amount_out, net);
+    assert!(s.amount_out < net + net / 100, "{}", s.amount_out);
+
+    // 储备相等时，同样数量的滑点远小于恒定乘积
+    let even = [2_000_000_000_000, 2_000_000_000_000];
+    let stable = p.swap(true, amount, even).unwrap().amount_out;
+    assert!(stable > cp(net, even[0], even[1]) + net / 100, "{}", stable);
+
+    // 反方向略亏
+    let back = p.swap(false, amount, vault).unwrap();
+    assert!(back.amount_out < amount - back.fee);
+
+    // 不变量 D 不减少（取整对池子有利）
+    let d0 = dex::math::stable_d(100, vault[0] as u128, vault[1] as u128).unwrap();
+    let d1 = dex::math::stable_d(100, (vault[0] + net) as u128, (vault[1] - s.amount_out) as u128).unwrap();
+    assert!(d1 >= d0, "{} < {}", d1, d0);
+}
+
+/* ---- 坏数据：长度不对 / 不支持的程序 ---- */
+#[test]
+fn decode_rejects_bad_accounts() {
+    let mut raydium = fixture("synthetic_raydium_sol_usdc.json").remove(0).1;
+    raydium.data.truncate(700);
+    assert!(Pool::decode(&raydium).is_err());
+
+    let vault = fixture("synthetic_orca_sol_usdc.json").remove(1).1;
+    assert!(Pool::decode(&vault).is_err());
+}
+
+/* ---- 报价器：加载后报价不再访问账户源，同一对 mint 取最优池 ---- */
+#[tokio::test]
+async fn quoter_loads_once_and_picks_best_pool() {
+    let (src, pools) = source();
+    let quoter = DexQuoter::new(&src);
+    assert_eq!(quoter.add_pools(&pools).await.unwrap(), 3);
+    let loads = src.calls.load(Ordering::Relaxed);
+
+    let q = quoter.quote(SOL.into(), USDC.into(), 1_000_000_000).await.unwrap();
+    let raydium = quoter.pool(&pk(RAYDIUM_POOL)).unwrap().quote(&pk(SOL), &pk(USDC), 1_000_000_000).unwrap();
+    let orca = quoter.pool(&pk(ORCA_POOL)).unwrap().quote(&pk(SOL), &pk(USDC), 1_000_000_000).unwrap();
+    // Orca 池子价格 151，优于 Raydium 的 150
+    assert!(orca.amount_out > raydium.amount_out);
+    assert_eq!(q.out_amount, orca.amount_out);
+    assert_eq!(q.route[0].amm_key, ORCA_POOL);
+    assert_eq!(q.route[0].amm_label, "Orca");
+    assert_eq!(q.route[0].fee_amount, 3_000_000);
+
+    let q = quoter.quote(USDT.into(), USDC.into(), 1_000_000).await.unwrap();
+    assert_eq!(q.label(), "Orca Stable");
+    // 没有池子：NoRoute，熔断/重试不把它当作报价源故障
+    let e = quoter.quote(SOL.into(), USDT.into(), 1_000).await.unwrap_err();
+    assert!(e.downcast_ref::<NoRoute>().is_some(), "{}", e);
+
+    assert_eq!(src.calls.load(Ordering::Relaxed), loads, "quotes must not hit the account source");
+}
+
+/* ---- refresh：金库余额变化后报价随之变化 ---- */
+#[tokio::test]
+async fn refresh_updates_reserves() {
+    let (src, pools) = source();
+    let quoter = DexQuoter::new(&src);
+    quoter.add_pools(&pools).await.unwrap();
+    let before = quoter.quote(USDC.into(), SOL.into(), 1_000_000_000).await.unwrap().out_amount;
+
+    // 两个 SOL/USDC 池子的 SOL 金库都减半
+    for f in ["synthetic_raydium_sol_usdc.json", "synthetic_orca_sol_usdc.json"] {
+        let accs = fixture(f);
+        let amount = dex::pools::token_amount(&accs[1].1.data).unwrap();
+        src.inner.set_token_amount(&accs[1].0, amount / 2);
+    }
+    quoter.refresh().await.unwrap();
+    let after = quoter.quote(USDC.into(), SOL.into(), 1_000_000_000).await.unwrap().out_amount;
+    assert!(after < before * 51 / 100, "{} vs {}", after, before);
+}
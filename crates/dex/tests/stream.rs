@@ -0,0 +1,288 @@
+use std::io::Write as _;
+use std::time::Duration;
+use arbitrage::prelude::QuoteProvider;
+use base64::Engine as _;
+use dex::clmm::math::sqrt_price_at_tick;
+use dex::pools::whirlpool;
+use dex::{AccountStream, AccountUpdate, Applied, DexQuoter, FileAccounts, MemoryAccounts, PoolCache, RawAccount, WsAccounts};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+#[derive(Deserialize)]
+struct Fixture { pubkey: String, owner: String, data: (String, String) }
+
+fn pk(s: &str) -> Pubkey { s.parse().unwrap() }
+
+fn fixture(name: &str) -> Vec<(Pubkey, RawAccount)> {
+    let text = std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    let v: Vec<Fixture> = serde_json::from_str(&text).unwrap();
+    v.into_iter()
+        .map(|f| {
+            let data = base64::engine::general_purpose::STANDARD.decode(f.data.0).unwrap();
+            (pk(&f.pubkey), RawAccount { owner: pk(&f.owner), data })
+        })
+        .collect()
+}
+
+fn source(names: &[&str]) -> MemoryAccounts {
+    let src = MemoryAccounts::new();
+    for name in names {
+        for (k, a) in fixture(name) {
+            src.insert(k, a);
+        }
+    }
+    src
+}
+
+fn b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// 推送文件的一行（account 为 None = 账户关闭）
+fn line(slot: u64, key: &Pubkey, account: Option<&RawAccount>) -> String {
+    let mut v = json!({ "slot": slot, "pubkey": key.to_string() });
+    if let Some(a) = account {
+        v["owner"] = json!(a.owner.to_string());
+        v["data"] = json!([b64(&a.data), "base64"]);
+    }
+    v.to_string()
+}
+
+fn with_amount(token_account: &RawAccount, amount: u64) -> RawAccount {
+    let mut a = token_account.clone();
+    a.data[64..72].copy_from_slice(&amount.to_le_bytes());
+    a
+}
+
+fn write_updates(lines: &[String]) -> tempfile::NamedTempFile {
+    let mut f = tempfile::NamedTempFile::new().unwrap();
+    for l in lines {
+        writeln!(f, "{}", l).unwrap();
+    }
+    f
+}
+
+async fn collect(stream: &impl AccountStream, keys: &[Pubkey]) -> Vec<AccountUpdate> {
+    let (tx, mut rx) = mpsc::channel(16);
+    stream.subscribe(keys, tx).await.unwrap();
+    let mut out = Vec::new();
+    while let Some(u) = rx.recv().await {
+        out.push(u);
+    }
+    out
+}
+
+/* ---- 文件回放：按顺序、只推订阅的账户，没有 data 表示账户关闭 ---- */
+#[tokio::test]
+async fn file_stream_replays_subscribed_keys() {
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let (vault, other) = (&accs[1], &accs[2]);
+    let file = write_updates(&[
+        line(10, &vault.0, Some(&with_amount(&vault.1, 1))),
+        line(11, &other.0, Some(&other.1)),
+        "".into(),
+        line(12, &vault.0, None),
+    ]);
+
+    let got = collect(&FileAccounts::new(file.path()), &[vault.0]).await;
+    assert_eq!(got.len(), 2);
+    assert_eq!((got[0].key, got[0].slot), (vault.0, 10));
+    assert_eq!(dex::pools::token_amount(&got[0].account.as_ref().unwrap().data).unwrap(), 1);
+    assert_eq!((got[1].slot, got[1].account.is_none()), (12, true));
+
+    assert!(FileAccounts::new("/nonexistent/updates.ndjson").subscribe(&[vault.0], mpsc::channel(1).0).await.is_err());
+}
+
+/* ---- 缓存：金库更新递增版本号，旧 slot / 无关账户被忽略 ---- */
+#[test]
+fn cache_versions_and_stale_slots() {
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let (pool, vault_a) = (&accs[0], &accs[1]);
+    let mut cache = PoolCache::new();
+    let c = cache.insert(pool.0, pool.1.clone(), vec![], [50_000_000_000_000, 7_500_000_000_000], 100).unwrap();
+    assert_eq!((c.version, c.slot), (1, 100));
+    assert_eq!(cache.accounts().len(), 3);
+
+    let update = |slot, amount| AccountUpdate { key: vault_a.0, slot, account: Some(with_amount(&vault_a.1, amount)) };
+    let [Applied::Changed(c)] = cache.apply(&update(105, 40_000_000_000_000))[..] else { panic!("not applied") };
+    assert_eq!((c.pool, c.slot, c.version), (pool.0, 105, 2));
+    assert_eq!(cache.get(&pool.0).unwrap().vault[0], 40_000_000_000_000);
+
+    // 比已有数据旧
+    assert_eq!(cache.apply(&update(104, 1)), vec![Applied::Ignored]);
+    assert_eq!(cache.get(&pool.0).unwrap().vault[0], 40_000_000_000_000);
+    // 不认识的账户
+    let stranger = AccountUpdate { key: Pubkey::new_unique(), slot: 200, account: None };
+    assert!(cache.apply(&stranger).is_empty());
+    assert_eq!(cache.version(), 2);
+
+    // 受影响的路径：任一方向经过 SOL/USDC 这一跳
+    let path = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert!(c.touches(&path(&[USDC, "X", SOL, USDC])));
+    assert!(c.touches(&path(&[SOL, USDC, SOL])));
+    assert!(!c.touches(&path(&[SOL, "X", USDC])));
+
+    // 池子账户关闭：整个池子移出缓存
+    assert_eq!(cache.apply(&AccountUpdate { key: pool.0, slot: 300, account: None }), vec![Applied::Ignored]);
+    assert!(cache.is_empty() && cache.accounts().is_empty());
+}
+
+/* ---- 共用账户：推送分发给每个池子，移除一个池子不影响另一个的订阅 ---- */
+#[test]
+fn cache_fans_out_shared_accounts() {
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let (pool, vault_a) = (&accs[0], &accs[1]);
+    // 同一份池子数据挂在两个 key 下：金库完全共用
+    let twin = Pubkey::new_unique();
+    let mut cache = PoolCache::new();
+    let vault = [50_000_000_000_000, 7_500_000_000_000];
+    cache.insert(pool.0, pool.1.clone(), vec![], vault, 100).unwrap();
+    cache.insert(twin, pool.1.clone(), vec![], vault, 100).unwrap();
+    assert_eq!(cache.accounts().len(), 4);
+
+    let update = |slot, amount| AccountUpdate { key: vault_a.0, slot, account: Some(with_amount(&vault_a.1, amount)) };
+    let changed: Vec<Pubkey> = cache
+        .apply(&update(105, 40_000_000_000_000))
+        .into_iter()
+        .map(|a| match a {
+            Applied::Changed(c) => c.pool,
+            a => panic!("{:?}", a),
+        })
+        .collect();
+    assert_eq!(changed.len(), 2);
+    assert!(changed.contains(&pool.0) && changed.contains(&twin));
+    assert_eq!(cache.get(&twin).unwrap().vault[0], 40_000_000_000_000);
+
+    // 移除一个：共用的金库仍在订阅列表里，推送仍到达另一个池子
+    cache.remove(&pool.0).unwrap();
+    let accounts = cache.accounts();
+    assert_eq!(accounts.len(), 3);
+    assert!(accounts.contains(&vault_a.0) && !accounts.contains(&pool.0));
+    let [Applied::Changed(c)] = cache.apply(&update(106, 30_000_000_000_000))[..] else { panic!("not applied") };
+    assert_eq!(c.pool, twin);
+}
+
+/* ---- refresh 不把 slot 清零：之后到达的旧推送仍被丢弃 ---- */
+#[tokio::test]
+async fn refresh_keeps_slots() {
+    let src = source(&["synthetic_raydium_sol_usdc.json"]);
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let quoter = DexQuoter::new(&src);
+    quoter.add_pools(&[accs[0].0]).await.unwrap();
+
+    let (usdc_vault, usdc) = (&accs[2].0, &accs[2].1);
+    let update = |slot, amount| AccountUpdate { key: *usdc_vault, slot, account: Some(with_amount(usdc, amount)) };
+    assert_eq!(quoter.apply_update(&update(500, 15_000_000_000_000)).await.unwrap().len(), 1);
+
+    quoter.refresh().await.unwrap();
+    assert_eq!(quoter.pool(&accs[0].0).unwrap().slot, 500);
+    assert!(quoter.apply_update(&update(499, 1)).await.unwrap().is_empty());
+    assert_ne!(quoter.pool(&accs[0].0).unwrap().vault[1], 1);
+}
+
+/* ---- watch：推送驱动报价变化，每次变化都通知 ---- */
+#[tokio::test]
+async fn watch_applies_updates_and_notifies() {
+    let src = source(&["synthetic_raydium_sol_usdc.json"]);
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let quoter = DexQuoter::new(&src);
+    quoter.add_pools(&[accs[0].0]).await.unwrap();
+    let before = quoter.quote(SOL.into(), USDC.into(), 1_000_000_000).await.unwrap().out_amount;
+
+    // USDC 金库翻倍，之后一条旧 slot 的推送应被忽略
+    let (usdc_vault, usdc) = (&accs[2].0, &accs[2].1);
+    let file = write_updates(&[
+        line(500, usdc_vault, Some(&with_amount(usdc, 15_000_000_000_000))),
+        line(499, usdc_vault, Some(&with_amount(usdc, 1))),
+    ]);
+    let (tx, mut rx) = mpsc::channel(8);
+    quoter.watch(&FileAccounts::new(file.path()), tx).await.unwrap();
+
+    let c = rx.recv().await.unwrap();
+    assert_eq!((c.pool, c.slot), (accs[0].0, 500));
+    assert!(rx.recv().await.is_none());
+    assert_eq!(quoter.version(), c.version);
+
+    let after = quoter.quote(SOL.into(), USDC.into(), 1_000_000_000).await.unwrap().out_amount;
+    assert!(after > before * 199 / 100, "{} vs {}", after, before);
+}
+
+/* ---- CLMM 价格移到新的 tick array：从 source 补拉，并把新账户加入订阅 ---- */
+#[tokio::test]
+async fn clmm_update_fetches_new_tick_arrays() {
+    let src = source(&["synthetic_whirlpool_sol_usdc.json"]);
+    let accs = fixture("synthetic_whirlpool_sol_usdc.json");
+    let key = accs[0].0;
+    let quoter = DexQuoter::new(&src);
+    quoter.add_pools(&[key]).await.unwrap();
+    let subscribed_before = quoter.accounts();
+
+    // 价格跳到 tick -5000（所在 array 起点 -5632）
+    let mut moved = accs[0].1.clone();
+    moved.data[65..81].copy_from_slice(&sqrt_price_at_tick(-5000).unwrap().to_le_bytes());
+    moved.data[81..85].copy_from_slice(&(-5000i32).to_le_bytes());
+    let [c] = quoter.apply_update(&AccountUpdate { key, slot: 900, account: Some(moved) }).await.unwrap()[..] else { panic!("not applied") };
+    assert_eq!(c.slot, 900);
+
+    let new_array = whirlpool::tick_array_address(&key, 11_264);
+    assert!(!subscribed_before.contains(&new_array));
+    assert!(quoter.accounts().contains(&new_array));
+    // 旧的最左侧 array 不再需要
+    assert!(!quoter.accounts().contains(&whirlpool::tick_array_address(&key, -39_424)));
+
+    let q = quoter.quote(SOL.into(), USDC.into(), 1_000_000).await.unwrap();
+    // 价格约 1.0001^-5000 ≈ 0.6065 USDC 最小单位 / lamport
+    assert!((600_000..606_000).contains(&q.out_amount), "{}", q.out_amount);
+}
+
+/* ---- websocket：accountSubscribe 握手 + accountNotification 解析 ---- */
+#[tokio::test]
+async fn ws_stream_subscribes_and_forwards_notifications() {
+    let accs = fixture("synthetic_raydium_sol_usdc.json");
+    let (vault, account) = (accs[1].0, with_amount(&accs[1].1, 42));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+        let Some(Ok(Message::Text(req))) = ws.next().await else { panic!("no subscribe request") };
+        let req: Value = serde_json::from_str(&req).unwrap();
+        assert_eq!(req["method"], "accountSubscribe");
+        assert_eq!(req["params"][0], vault.to_string());
+        assert_eq!(req["params"][1]["encoding"], "base64");
+
+        let ack = json!({ "jsonrpc": "2.0", "id": req["id"], "result": 7 });
+        ws.send(Message::Text(ack.to_string())).await.unwrap();
+        let note = json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "subscription": 7,
+                "result": {
+                    "context": { "slot": 1234 },
+                    "value": { "owner": account.owner.to_string(), "data": [b64(&account.data), "base64"], "lamports": 1 },
+                },
+            },
+        });
+        ws.send(Message::Text(note.to_string())).await.unwrap();
+        // 保持连接直到客户端断开
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let (tx, mut rx) = mpsc::channel(4);
+    WsAccounts::new(url).subscribe(&[vault], tx).await.unwrap();
+    let u = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+    assert_eq!((u.key, u.slot), (vault, 1234));
+    assert_eq!(dex::pools::token_amount(&u.account.unwrap().data).unwrap(), 42);
+
+    drop(rx);
+    server.abort();
+}
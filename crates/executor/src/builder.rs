@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::signers::Signers;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::compute_budget::ComputeBudget;
+use crate::error::{ExecError, ExecResult};
+use crate::leg::LegInstructions;
+use crate::priority_fee::FeePlan;
+
+/// 单笔交易序列化后的上限（字节）
+pub const TX_SIZE_LIMIT: usize = PACKET_DATA_SIZE;
+
+/// 把多跳合成一笔 v0 交易：任何一跳失败整笔回滚
+///
+/// 指令顺序：合并后的 ComputeBudget → 去重后的 setup → 各跳 swap（按路径顺序）
+/// → 去重后的 cleanup（同一条只在最后执行一次）→ 去重后的 other。
+#[derive(Debug, Clone)]
+pub struct AtomicTxBuilder {
+    pub payer: Pubkey,
+    /// 覆盖合并结果（如按模拟结果重新设置 CU 上限/优先费）
+    pub unit_limit: Option<u32>,
+    pub unit_price: Option<u64>,
+    pub size_limit: usize,
+}
+
+/// 编译好的未签名交易
+#[derive(Debug, Clone)]
+pub struct BuiltTx {
+    pub message: VersionedMessage,
+    /// 签名后的序列化大小（字节）
+    pub size: usize,
+    pub compute_budget: ComputeBudget,
+}
+
+impl AtomicTxBuilder {
+    pub fn new(payer: Pubkey) -> Self {
+        Self { payer, unit_limit: None, unit_price: None, size_limit: TX_SIZE_LIMIT }
+    }
+
+    pub fn unit_limit(mut self, n: u32) -> Self {
+        self.unit_limit = Some(n);
+        self
+    }
+
+    pub fn unit_price(mut self, micro_lamports: u64) -> Self {
+        self.unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// 按费用安排设置 CU 上限和优先费
+    pub fn fees(self, plan: &FeePlan) -> Self {
+        self.unit_limit(plan.unit_limit).unit_price(plan.unit_price)
+    }
+
+    /// 合并后的指令序列
+    pub fn instructions(&self, legs: &[LegInstructions]) -> ExecResult<(Vec<Instruction>, ComputeBudget)> {
+        if legs.is_empty() {
+            return Err(ExecError::EmptyPath);
+        }
+
+        let mut budget = ComputeBudget::default();
+        let mut setup = Vec::new();
+        let mut swaps = Vec::new();
+        let mut cleanup = Vec::new();
+        let mut other = Vec::new();
+
+        for leg in legs {
+            for ix in &leg.compute_budget {
+                budget.absorb(ix);
+            }
+            // 有的路由会把 ComputeBudget 混在 setup/other 里，一并合并
+            for ix in &leg.setup {
+                if ComputeBudget::is_compute_budget(ix) { budget.absorb(ix) } else { push_unique(&mut setup, ix) }
+            }
+            swaps.push(leg.swap.clone());
+            if let Some(ix) = &leg.cleanup {
+                // 保留最后一次出现：先删旧的再追加
+                cleanup.retain(|x| x != ix);
+                cleanup.push(ix.clone());
+            }
+            for ix in &leg.other {
+                if ComputeBudget::is_compute_budget(ix) { budget.absorb(ix) } else { push_unique(&mut other, ix) }
+            }
+        }
+
+        if self.unit_limit.is_some() { budget.unit_limit = self.unit_limit; }
+        if self.unit_price.is_some() { budget.unit_price = self.unit_price; }
+
+        let mut ixs = budget.instructions();
+        ixs.extend(setup);
+        ixs.extend(swaps);
+        ixs.extend(cleanup);
+        ixs.extend(other);
+        Ok((ixs, budget))
+    }
+
+    /// 编译 v0 消息并检查大小；alts 为各跳 ALT 的实际内容（按 key 去重）
+    pub fn build(
+        &self,
+        legs: &[LegInstructions],
+        alts: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> ExecResult<BuiltTx> {
+        let (ixs, compute_budget) = self.instructions(legs)?;
+
+        let mut seen = HashSet::new();
+        let alts: Vec<AddressLookupTableAccount> = alts.iter().filter(|a| seen.insert(a.key)).cloned().collect();
+
+        let msg = v0::Message::try_compile(&self.payer, &ixs, &alts, recent_blockhash)
+            .map_err(|e| ExecError::Compile(e.to_string()))?;
+        let message = VersionedMessage::V0(msg);
+
+        let size = signed_size(&message)?;
+        if size > self.size_limit {
+            return Err(ExecError::TooLarge { size, limit: self.size_limit });
+        }
+        Ok(BuiltTx { message, size, compute_budget })
+    }
+}
+
+impl BuiltTx {
+    pub fn sign<T: Signers + ?Sized>(&self, signers: &T) -> ExecResult<VersionedTransaction> {
+        VersionedTransaction::try_new(self.message.clone(), signers).map_err(|e| ExecError::Sign(e.to_string()))
+    }
+}
+
+fn push_unique(v: &mut Vec<Instruction>, ix: &Instruction) {
+    if !v.contains(ix) {
+        v.push(ix.clone());
+    }
+}
+
+/// 按所需签名数补占位签名后的序列化大小
+fn signed_size(message: &VersionedMessage) -> ExecResult<usize> {
+    let n = message.header().num_required_signatures as usize;
+    let tx = VersionedTransaction { signatures: vec![Signature::default(); n], message: message.clone() };
+    bincode::serialized_size(&tx)
+        .map(|s| s as usize)
+        .map_err(|e| ExecError::Serialize(e.to_string()))
+}
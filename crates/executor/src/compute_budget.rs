@@ -0,0 +1,58 @@
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::instruction::Instruction;
+
+/// 单笔交易的计算预算上限
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// 合并后的计算预算：每类指令只保留一条
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: Option<u32>,
+    /// micro-lamports / CU
+    pub unit_price: Option<u64>,
+    pub heap_frame: Option<u32>,
+    pub loaded_accounts_data_size: Option<u32>,
+}
+
+impl ComputeBudget {
+    pub fn is_compute_budget(ix: &Instruction) -> bool {
+        ix.program_id == compute_budget::id()
+    }
+
+    /// 合并一条 ComputeBudget 指令：CU 上限按跳累加（多跳在同一笔交易里各自消耗），其余取最大
+    pub fn absorb(&mut self, ix: &Instruction) {
+        let d = &ix.data;
+        let u32_at = || d.get(1..5).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+        let u64_at = || d.get(1..9).map(|b| u64::from_le_bytes(b.try_into().unwrap()));
+        match d.first() {
+            Some(1) => self.heap_frame = max(self.heap_frame, u32_at()),
+            Some(2) => {
+                if let Some(n) = u32_at() {
+                    let sum = self.unit_limit.unwrap_or(0).saturating_add(n);
+                    self.unit_limit = Some(sum.min(MAX_COMPUTE_UNIT_LIMIT));
+                }
+            }
+            Some(3) => self.unit_price = max(self.unit_price, u64_at()),
+            Some(4) => self.loaded_accounts_data_size = max(self.loaded_accounts_data_size, u32_at()),
+            _ => {}
+        }
+    }
+
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut v = Vec::new();
+        if let Some(n) = self.unit_limit { v.push(ComputeBudgetInstruction::set_compute_unit_limit(n)); }
+        if let Some(p) = self.unit_price { v.push(ComputeBudgetInstruction::set_compute_unit_price(p)); }
+        if let Some(h) = self.heap_frame { v.push(ComputeBudgetInstruction::request_heap_frame(h)); }
+        if let Some(s) = self.loaded_accounts_data_size {
+            v.push(ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(s));
+        }
+        v
+    }
+}
+
+fn max<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
@@ -0,0 +1,43 @@
+use solana_sdk::transaction::TransactionError;
+use thiserror::Error;
+
+/// 执行层错误
+#[derive(Debug, Error)]
+pub enum ExecError {
+    #[error("路径为空")]
+    EmptyPath,
+
+    #[error("交易过大: {size} 字节 > 上限 {limit}")]
+    TooLarge { size: usize, limit: usize },
+
+    #[error("消息编译失败: {0}")]
+    Compile(String),
+
+    #[error("签名失败: {0}")]
+    Sign(String),
+
+    #[error("序列化失败: {0}")]
+    Serialize(String),
+
+    #[error("RPC 错误: {0}")]
+    Rpc(String),
+
+    /// 节点正常响应但拒绝了请求（如预检失败），换节点也一样，不触发故障转移
+    #[error("RPC 拒绝请求: {0}")]
+    Rejected(String),
+
+    #[error("模拟失败: {err}")]
+    SimulationFailed { err: TransactionError, logs: Vec<String> },
+
+    #[error("模拟结果异常: {0}")]
+    Simulation(String),
+
+    #[error("模拟利润不足: {simulated} < {min}")]
+    InsufficientProfit { min: i128, simulated: i128 },
+
+    #[error("费用 {fee} lamports 不低于预期利润 {profit}")]
+    FeeExceedsProfit { fee: u64, profit: i128 },
+}
+
+/// 执行层统一返回类型
+pub type ExecResult<T> = Result<T, ExecError>;
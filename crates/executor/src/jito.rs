@@ -0,0 +1,283 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use tokio::time::{sleep, timeout, Instant};
+use crate::error::{ExecError, ExecResult};
+use crate::rpc::Rpc;
+use crate::sender::{gave_up, SendConfig, SendOutcome, Submitter};
+
+/// 主网 block engine
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// 单次 JSON-RPC 请求的超时
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Jito 小费收款账户（任选其一）
+pub const TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// block engine 接受的最低小费
+pub const MIN_TIP_LAMPORTS: u64 = 1_000;
+
+/// 小费按预期利润的比例定价
+#[derive(Debug, Clone, Copy)]
+pub struct TipPolicy {
+    pub profit_share_bps: u32,
+    pub min_tip: u64,
+    pub max_tip: u64,
+}
+
+impl Default for TipPolicy {
+    fn default() -> Self {
+        Self { profit_share_bps: 2_000, min_tip: MIN_TIP_LAMPORTS, max_tip: 10_000_000 }
+    }
+}
+
+impl TipPolicy {
+    /// 利润 × 比例，夹到 [min, max]；小费不低于利润时拒绝
+    pub fn tip(&self, expected_profit: i128) -> ExecResult<u64> {
+        let share = (expected_profit.max(0) * self.profit_share_bps as i128 / 10_000).min(u64::MAX as i128) as u64;
+        let tip = share.clamp(self.min_tip.max(MIN_TIP_LAMPORTS), self.max_tip.max(self.min_tip));
+        if tip as i128 >= expected_profit {
+            return Err(ExecError::FeeExceedsProfit { fee: tip, profit: expected_profit });
+        }
+        Ok(tip)
+    }
+}
+
+/// 按交易签名挑一个小费账户（分散写锁）
+pub fn tip_account(tx: &VersionedTransaction) -> Pubkey {
+    let b = tx.signatures.first().map(|s| s.as_ref()[0]).unwrap_or(0);
+    TIP_ACCOUNTS[b as usize % TIP_ACCOUNTS.len()]
+}
+
+/// 付小费的交易（与主交易共用 blockhash）
+pub fn tip_transaction(payer: &Keypair, to: &Pubkey, lamports: u64, blockhash: Hash) -> VersionedTransaction {
+    let ix = system_instruction::transfer(&payer.pubkey(), to, lamports);
+    Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash).into()
+}
+
+/// getInflightBundleStatuses 的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum InflightStatus {
+    /// 找不到（过期或从未收到）
+    Invalid,
+    Pending,
+    /// 各区域都没能上链
+    Failed,
+    Landed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BundleStatus {
+    pub bundle_id: String,
+    pub status: InflightStatus,
+    pub landed_slot: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Deserialize)]
+struct RpcErrorBody {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+/// block engine 的 JSON-RPC 客户端
+#[derive(Debug, Clone)]
+pub struct JitoClient {
+    http: reqwest::Client,
+    url: String,
+    auth: Option<String>,
+}
+
+impl JitoClient {
+    /// base_url 如 `https://mainnet.block-engine.jito.wtf`；单次请求超时为 `DEFAULT_REQUEST_TIMEOUT`
+    pub fn new(base_url: &str) -> ExecResult<Self> {
+        Self::with_timeout(base_url, DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    pub fn with_timeout(base_url: &str, request_timeout: Duration) -> ExecResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(request_timeout)
+            .build()
+            .map_err(|e| ExecError::Rpc(e.to_string()))?;
+        Ok(Self {
+            http,
+            url: format!("{}/api/v1/bundles", base_url.trim_end_matches('/')),
+            auth: None,
+        })
+    }
+
+    /// x-jito-auth（有白名单 UUID 时提高限额）
+    pub fn auth(mut self, uuid: impl Into<String>) -> Self {
+        self.auth = Some(uuid.into());
+        self
+    }
+
+    async fn call(&self, method: &str, params: Value) -> ExecResult<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut req = self.http.post(&self.url).json(&body);
+        if let Some(a) = &self.auth {
+            req = req.header("x-jito-auth", a);
+        }
+        let resp = req.send().await.map_err(|e| ExecError::Rpc(e.to_string()))?;
+        let status = resp.status();
+        let r: RpcResponse = resp
+            .json()
+            .await
+            .map_err(|e| ExecError::Rpc(format!("{} {}: {}", method, status, e)))?;
+        match (r.result, r.error) {
+            (_, Some(e)) => Err(ExecError::Rpc(format!("{}: {}", method, e.message))),
+            (Some(v), None) => Ok(v),
+            (None, None) => Err(ExecError::Rpc(format!("{}: 空响应", method))),
+        }
+    }
+
+    /// 提交 bundle（最多 5 笔，按顺序原子执行），返回 bundle id
+    pub async fn send_bundle(&self, txs: &[VersionedTransaction]) -> ExecResult<String> {
+        let encoded = txs
+            .iter()
+            .map(|tx| {
+                bincode::serialize(tx)
+                    .map(|b| base64::engine::general_purpose::STANDARD.encode(b))
+                    .map_err(|e| ExecError::Serialize(e.to_string()))
+            })
+            .collect::<ExecResult<Vec<_>>>()?;
+        let v = self.call("sendBundle", json!([encoded, { "encoding": "base64" }])).await?;
+        serde_json::from_value(v).map_err(|e| ExecError::Rpc(format!("sendBundle: {}", e)))
+    }
+
+    /// 近 5 分钟内提交的 bundle 状态
+    pub async fn inflight_statuses(&self, ids: &[String]) -> ExecResult<Vec<BundleStatus>> {
+        let v = self.call("getInflightBundleStatuses", json!([ids])).await?;
+        let w: WithContext<Vec<BundleStatus>> =
+            serde_json::from_value(v).map_err(|e| ExecError::Rpc(format!("getInflightBundleStatuses: {}", e)))?;
+        Ok(w.value)
+    }
+}
+
+/// 以 Jito bundle 提交：[主交易, 小费交易]；未上链前按间隔重提，直到 blockhash 过期或超过 `SendConfig::timeout`
+///
+/// 目前只作为库提供，bot-runner / cli-runner 还没有接入。
+pub struct JitoSubmitter<R> {
+    client: JitoClient,
+    /// 只用来查区块高度判断过期
+    rpc: R,
+    payer: Arc<Keypair>,
+    tip: TipPolicy,
+    cfg: SendConfig,
+}
+
+impl<R: Rpc> JitoSubmitter<R> {
+    pub fn new(client: JitoClient, rpc: R, payer: Arc<Keypair>) -> Self {
+        Self {
+            client,
+            rpc,
+            payer,
+            tip: TipPolicy::default(),
+            cfg: SendConfig { rebroadcast_interval: Duration::from_secs(2), ..SendConfig::default() },
+        }
+    }
+
+    pub fn tip_policy(mut self, tip: TipPolicy) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn config(mut self, cfg: SendConfig) -> Self {
+        self.cfg = cfg;
+        self
+    }
+}
+
+impl<R: Rpc> Submitter for JitoSubmitter<R> {
+    async fn submit(&self, tx: &VersionedTransaction, last_valid_block_height: u64, expected_profit: i128) -> ExecResult<SendOutcome> {
+        let tip = self.tip.tip(expected_profit)?;
+        let tip_tx = tip_transaction(&self.payer, &tip_account(tx), tip, *tx.message.recent_blockhash());
+        let bundle = [tx.clone(), tip_tx];
+
+        let start = Instant::now();
+        let signature = tx.signatures.first().copied().unwrap_or_default();
+        // 相同内容的 bundle id 相同，重提不会新增
+        let mut ids: Vec<String> = Vec::new();
+        let mut submissions = 0u32;
+        let mut last_error = None;
+        let mut last_submit: Option<Instant> = None;
+        let mut pending = false;
+
+        let done = |slot, failure, broadcasts| SendOutcome {
+            signature,
+            slot,
+            failure,
+            broadcasts,
+            tip: if slot.is_some() { tip } else { 0 },
+            elapsed: start.elapsed(),
+        };
+
+        // 每个请求最多等到总时长用完，卡住的连接也不会拖住整个提交
+        let left = || self.cfg.timeout.saturating_sub(start.elapsed());
+
+        loop {
+            // 已在处理中就不再重提
+            if !pending && last_submit.is_none_or(|t| t.elapsed() >= self.cfg.rebroadcast_interval) {
+                match within(left(), self.client.send_bundle(&bundle)).await {
+                    Ok(id) => {
+                        submissions += 1;
+                        if !ids.contains(&id) { ids.push(id); }
+                    }
+                    Err(e) => last_error = Some(e.to_string()),
+                }
+                last_submit = Some(Instant::now());
+            }
+
+            sleep(self.cfg.poll_interval).await;
+
+            if !ids.is_empty() {
+                if let Ok(st) = within(left(), self.client.inflight_statuses(&ids)).await {
+                    if let Some(s) = st.iter().find(|s| s.status == InflightStatus::Landed) {
+                        return Ok(done(Some(s.landed_slot.unwrap_or_default()), None, submissions));
+                    }
+                    pending = st.iter().any(|s| s.status == InflightStatus::Pending);
+                }
+            }
+
+            // 区块高度查不到、bundle 反复 Failed/Invalid 时靠总时长兜底
+            let expired = within(left(), self.rpc.block_height()).await.is_ok_and(|h| h > last_valid_block_height);
+            if expired || start.elapsed() >= self.cfg.timeout {
+                return Ok(done(None, Some(gave_up(submissions, last_error)), submissions));
+            }
+        }
+    }
+}
+
+/// 最多等 left；超时按端点故障处理
+async fn within<T>(left: Duration, f: impl Future<Output = ExecResult<T>>) -> ExecResult<T> {
+    timeout(left, f).await.unwrap_or_else(|_| Err(ExecError::Rpc("请求超时".into())))
+}
@@ -0,0 +1,56 @@
+use client::{JupInstruction, SwapInstructionsResponse};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use utils::{AppError, AppResult};
+
+/// 一跳的指令集（对应 Jupiter /swap-instructions 的返回）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegInstructions {
+    pub compute_budget: Vec<Instruction>,
+    /// 如创建 ATA、wrap SOL
+    pub setup: Vec<Instruction>,
+    pub swap: Instruction,
+    /// 如 unwrap SOL
+    pub cleanup: Option<Instruction>,
+    pub other: Vec<Instruction>,
+    pub address_lookup_tables: Vec<Pubkey>,
+}
+
+impl LegInstructions {
+    /// 只有 swap 指令的一跳
+    pub fn swap_only(swap: Instruction) -> Self {
+        Self {
+            compute_budget: vec![],
+            setup: vec![],
+            swap,
+            cleanup: None,
+            other: vec![],
+            address_lookup_tables: vec![],
+        }
+    }
+}
+
+/// Jupiter /swap-instructions 的响应转成一跳
+impl TryFrom<&SwapInstructionsResponse> for LegInstructions {
+    type Error = AppError;
+
+    fn try_from(r: &SwapInstructionsResponse) -> AppResult<Self> {
+        let all = |v: &[JupInstruction]| v.iter().map(JupInstruction::to_instruction).collect::<AppResult<Vec<_>>>();
+        Ok(Self {
+            compute_budget: all(&r.compute_budget_instructions)?,
+            setup: r.setup()?,
+            swap: r.swap_instruction.to_instruction()?,
+            cleanup: r.cleanup_instruction.as_ref().map(JupInstruction::to_instruction).transpose()?,
+            other: all(&r.other_instructions)?,
+            address_lookup_tables: r.lookup_tables()?,
+        })
+    }
+}
+
+impl TryFrom<SwapInstructionsResponse> for LegInstructions {
+    type Error = AppError;
+
+    fn try_from(r: SwapInstructionsResponse) -> AppResult<Self> {
+        Self::try_from(&r)
+    }
+}
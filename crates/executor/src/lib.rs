@@ -1 +1,22 @@
-//套利执行逻辑
\ No newline at end of file
+//套利执行逻辑
+pub mod error;
+pub mod leg;
+pub mod compute_budget;
+pub mod builder;
+pub mod rpc;
+pub mod simulate;
+pub mod sender;
+pub mod priority_fee;
+pub mod jito;
+
+pub use error::{ExecError, ExecResult};
+pub use leg::LegInstructions;
+pub use compute_budget::ComputeBudget;
+pub use builder::{AtomicTxBuilder, BuiltTx, TX_SIZE_LIMIT};
+pub use rpc::{AccountData, Confirmation, FakeRpc, Failover, Rpc, SigStatus, SimulateResult};
+pub use simulate::{ProfitAccount, SimReport, SimulationGate};
+pub use priority_fee::{FeeLedger, FeePlan, FeePolicy, FeeRecord};
+pub use sender::{RpcSender, SendConfig, SendFailure, SendOutcome, Submitter};
+pub use jito::{JitoClient, JitoSubmitter, TipPolicy};
+#[cfg(feature = "solana-rpc")]
+pub use rpc::SolanaRpc;
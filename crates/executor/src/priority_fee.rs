@@ -0,0 +1,180 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use crate::compute_budget::MAX_COMPUTE_UNIT_LIMIT;
+use crate::error::{ExecError, ExecResult};
+use crate::rpc::Rpc;
+use crate::sender::SendOutcome;
+pub use utils::fees::{FeePlan, FeeRecord};
+
+/// 每个签名的基础费（lamports）
+pub const BASE_FEE_PER_SIGNATURE: u64 = 5_000;
+
+/// 优先费策略（价格单位均为 micro-lamports / CU）
+#[derive(Debug, Clone, Copy)]
+pub struct FeePolicy {
+    /// 取近期优先费的第几百分位
+    pub percentile: u8,
+    pub min_unit_price: u64,
+    pub max_unit_price: u64,
+    /// 总费用（基础费 + 优先费）最多占预期利润的比例（bps）
+    pub max_profit_share_bps: u32,
+    /// CU 上限 = 模拟消耗 × (1 + margin)
+    pub cu_margin_bps: u32,
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self {
+            percentile: 75,
+            min_unit_price: 0,
+            max_unit_price: 5_000_000,
+            max_profit_share_bps: 5_000,
+            cu_margin_bps: 1_500,
+        }
+    }
+}
+
+/// 改写已编译消息里的 CU 上限/价格（如 Jupiter /swap 返回的交易），缺的插到最前面；
+/// 只改指令数据、不动账户表，所以要求消息里已有 ComputeBudget 程序。改完需重新签名。
+pub fn apply_plan(plan: &FeePlan, message: &mut VersionedMessage) -> ExecResult<()> {
+    let program = message
+        .static_account_keys()
+        .iter()
+        .position(|k| *k == compute_budget::id())
+        .ok_or_else(|| ExecError::Compile("消息里没有 ComputeBudget 程序".into()))? as u8;
+    let ixs = match message {
+        VersionedMessage::Legacy(m) => &mut m.instructions,
+        VersionedMessage::V0(m) => &mut m.instructions,
+    };
+
+    let wanted = [
+        ComputeBudgetInstruction::set_compute_unit_limit(plan.unit_limit).data,
+        ComputeBudgetInstruction::set_compute_unit_price(plan.unit_price).data,
+    ];
+    for data in wanted.into_iter().rev() {
+        match ixs.iter_mut().find(|ix| ix.program_id_index == program && ix.data.first() == data.first()) {
+            Some(ix) => ix.data = data,
+            None => ixs.insert(0, CompiledInstruction { program_id_index: program, accounts: vec![], data }),
+        }
+    }
+    Ok(())
+}
+
+/// unit_limit × unit_price（micro-lamports）折成 lamports，向上取整
+pub fn priority_fee_lamports(unit_limit: u32, unit_price: u64) -> u64 {
+    (unit_limit as u128 * unit_price as u128).div_ceil(1_000_000) as u64
+}
+
+impl FeePolicy {
+    /// 近期优先费的分位数（最近秩法），再夹到 [min, max]
+    pub fn estimate_price(&self, recent: &[u64]) -> u64 {
+        let mut v = recent.to_vec();
+        v.sort_unstable();
+        let p = match v.len() {
+            0 => 0,
+            n => v[(n * self.percentile.min(100) as usize).div_ceil(100).max(1) - 1],
+        };
+        p.clamp(self.min_unit_price, self.max_unit_price.max(self.min_unit_price))
+    }
+
+    /// 模拟消耗加余量，不超过协议上限
+    pub fn unit_limit(&self, units_consumed: u64) -> u32 {
+        let with_margin = (units_consumed as u128 * (10_000 + self.cu_margin_bps as u128)).div_ceil(10_000);
+        with_margin.clamp(1, MAX_COMPUTE_UNIT_LIMIT as u128) as u32
+    }
+
+    /// 按预期利润（lamports）给出费用安排；连基础费都覆盖不了时拒绝
+    ///
+    /// 优先费价格取 min(市场分位数, 预算 / unit_limit)，预算 = 利润 × 比例 - 基础费。
+    pub fn plan(&self, recent: &[u64], units_consumed: u64, signatures: usize, expected_profit: i128) -> ExecResult<FeePlan> {
+        let unit_limit = self.unit_limit(units_consumed);
+        let base_fee = BASE_FEE_PER_SIGNATURE * signatures.max(1) as u64;
+        if expected_profit <= base_fee as i128 {
+            return Err(ExecError::FeeExceedsProfit { fee: base_fee, profit: expected_profit });
+        }
+
+        let budget = (expected_profit * self.max_profit_share_bps as i128 / 10_000 - base_fee as i128).max(0) as u128;
+        let cap = (budget * 1_000_000 / unit_limit as u128).min(u64::MAX as u128) as u64;
+        let unit_price = self.estimate_price(recent).min(cap);
+
+        Ok(FeePlan { unit_limit, unit_price, priority_fee: priority_fee_lamports(unit_limit, unit_price), base_fee })
+    }
+
+    /// 查询与交易可写账户相关的近期优先费后给出安排
+    pub async fn plan_for<R: Rpc + ?Sized>(
+        &self,
+        rpc: &R,
+        message: &VersionedMessage,
+        units_consumed: u64,
+        expected_profit: i128,
+    ) -> ExecResult<FeePlan> {
+        let recent = rpc.recent_prioritization_fees(&writable_accounts(message)).await?;
+        let signatures = message.header().num_required_signatures as usize;
+        self.plan(&recent, units_consumed, signatures, expected_profit)
+    }
+}
+
+/// 消息里的可写静态账户（优先费按写锁竞争计价）
+pub fn writable_accounts(message: &VersionedMessage) -> Vec<Pubkey> {
+    let h = message.header();
+    let keys = message.static_account_keys();
+    let signed = h.num_required_signatures as usize;
+    let signed_writable = signed.saturating_sub(h.num_readonly_signed_accounts as usize);
+    let unsigned_writable = keys.len().saturating_sub(h.num_readonly_unsigned_accounts as usize);
+    keys.iter()
+        .enumerate()
+        .filter(|(i, _)| *i < signed_writable || (*i >= signed && *i < unsigned_writable))
+        .map(|(_, k)| *k)
+        .collect()
+}
+
+/* ---------------- 费用记录 ---------------- */
+
+impl SendOutcome {
+    /// 按发送结果记一笔费用
+    pub fn fee_record(&self, plan: FeePlan, expected_profit: i128) -> FeeRecord {
+        let landed = self.slot.is_some();
+        let pending = self.pending();
+        FeeRecord {
+            ts_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+            signature: self.signature.to_string(),
+            plan,
+            landed,
+            pending,
+            tip: self.tip,
+            paid: if landed || pending { plan.total() + self.tip } else { 0 },
+            expected_profit,
+        }
+    }
+}
+
+/// 追加写入费用记录
+pub struct FeeLedger {
+    out: Mutex<BufWriter<File>>,
+}
+
+impl FeeLedger {
+    pub fn create(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| ExecError::Serialize(e.to_string()))?;
+        Ok(Self { out: Mutex::new(BufWriter::new(f)) })
+    }
+
+    pub fn record(&self, rec: &FeeRecord) -> ExecResult<()> {
+        let line = serde_json::to_string(rec).map_err(|e| ExecError::Serialize(e.to_string()))?;
+        let mut out = self.out.lock().unwrap();
+        writeln!(out, "{}", line)
+            .and_then(|_| out.flush())
+            .map_err(|e| ExecError::Serialize(e.to_string()))
+    }
+}
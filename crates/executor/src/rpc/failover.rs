@@ -0,0 +1,228 @@
+use std::future::Future;
+use futures::future::join_all;
+use std::sync::Mutex;
+use std::time::Duration;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::time::Instant;
+use crate::error::{ExecError, ExecResult};
+use super::{AccountData, Rpc, SigStatus, SimulateResult};
+
+/// 端点健康判定
+#[derive(Debug, Clone, Copy)]
+pub struct HealthConfig {
+    /// 连续失败几次后摘除
+    pub max_consecutive_failures: u32,
+    /// 摘除多久后重新尝试
+    pub cooldown: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { max_consecutive_failures: 3, cooldown: Duration::from_secs(30) }
+    }
+}
+
+/// 某个端点的健康快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    pub name: String,
+    pub healthy: bool,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    successes: u64,
+    failures: u64,
+    consecutive: u32,
+    down_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+struct Endpoint<R> {
+    name: String,
+    rpc: R,
+    health: Mutex<Health>,
+}
+
+/// 多端点故障转移：按配置顺序尝试健康端点，遇到 `ExecError::Rpc`（节点/网络故障）换下一个；
+/// 其它错误说明节点已正常响应，直接返回。所有端点都被摘除时仍按最早恢复的顺序兜底尝试。
+pub struct Failover<R> {
+    endpoints: Vec<Endpoint<R>>,
+    cfg: HealthConfig,
+}
+
+impl<R> Failover<R> {
+    /// endpoints：(名称, 客户端)，名称只用于健康报告
+    pub fn new(endpoints: Vec<(String, R)>) -> Self {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|(name, rpc)| Endpoint { name, rpc, health: Mutex::new(Health::default()) })
+            .collect();
+        Self { endpoints, cfg: HealthConfig::default() }
+    }
+
+    pub fn health_config(mut self, cfg: HealthConfig) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// 各端点健康状况（按配置顺序）
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|e| {
+                let h = e.health.lock().unwrap();
+                EndpointHealth {
+                    name: e.name.clone(),
+                    healthy: h.down_until.is_none_or(|t| t <= now),
+                    successes: h.successes,
+                    failures: h.failures,
+                    last_error: h.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// 依次在各端点上执行 f，直到拿到非 `ExecError::Rpc` 的结果
+    pub async fn call<'a, T, F, Fut>(&'a self, f: F) -> ExecResult<T>
+    where
+        F: Fn(&'a R) -> Fut,
+        Fut: Future<Output = ExecResult<T>>,
+    {
+        let mut last = ExecError::Rpc("没有可用的 RPC 端点".into());
+        for i in self.order() {
+            let ep = &self.endpoints[i];
+            match f(&ep.rpc).await {
+                Err(ExecError::Rpc(e)) => {
+                    self.mark_failure(ep, &e);
+                    last = ExecError::Rpc(format!("{}: {}", ep.name, e));
+                }
+                res => {
+                    self.mark_success(ep);
+                    return res;
+                }
+            }
+        }
+        Err(last)
+    }
+
+    /// 健康的按配置顺序在前，被摘除的按恢复时间排在后面
+    fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut up = Vec::new();
+        let mut down = Vec::new();
+        for (i, e) in self.endpoints.iter().enumerate() {
+            match e.health.lock().unwrap().down_until {
+                Some(t) if t > now => down.push((t, i)),
+                _ => up.push(i),
+            }
+        }
+        down.sort();
+        up.extend(down.into_iter().map(|(_, i)| i));
+        up
+    }
+
+    fn mark_success(&self, ep: &Endpoint<R>) {
+        let mut h = ep.health.lock().unwrap();
+        h.successes += 1;
+        h.consecutive = 0;
+        h.down_until = None;
+    }
+
+    fn mark_failure(&self, ep: &Endpoint<R>, err: &str) {
+        let mut h = ep.health.lock().unwrap();
+        h.failures += 1;
+        h.consecutive += 1;
+        h.last_error = Some(err.to_string());
+        if h.consecutive >= self.cfg.max_consecutive_failures.max(1) {
+            h.down_until = Some(Instant::now() + self.cfg.cooldown);
+        }
+    }
+}
+
+impl<R: Rpc> Rpc for Failover<R> {
+    async fn latest_blockhash(&self) -> ExecResult<(Hash, u64)> {
+        self.call(|r| r.latest_blockhash()).await
+    }
+
+    async fn simulate(&self, tx: &VersionedTransaction, accounts: &[Pubkey]) -> ExecResult<SimulateResult> {
+        self.call(|r| r.simulate(tx, accounts)).await
+    }
+
+    async fn block_height(&self) -> ExecResult<u64> {
+        self.call(|r| r.block_height()).await
+    }
+
+    async fn send(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        self.call(|r| r.send(tx)).await
+    }
+
+    /// 同时发给所有未被摘除的端点，任一成功即可
+    async fn broadcast(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        let now = Instant::now();
+        let targets: Vec<&Endpoint<R>> = self
+            .endpoints
+            .iter()
+            .filter(|e| e.health.lock().unwrap().down_until.is_none_or(|t| t <= now))
+            .collect();
+        if targets.is_empty() {
+            return self.send(tx).await;
+        }
+
+        let results = join_all(targets.iter().map(|e| e.rpc.send(tx))).await;
+        let mut ok = None;
+        let mut last = None;
+        for (ep, res) in targets.into_iter().zip(results) {
+            match res {
+                Ok(sig) => {
+                    self.mark_success(ep);
+                    ok.get_or_insert(sig);
+                }
+                Err(ExecError::Rpc(e)) => {
+                    self.mark_failure(ep, &e);
+                    last = Some(ExecError::Rpc(format!("{}: {}", ep.name, e)));
+                }
+                Err(e) => {
+                    self.mark_success(ep);
+                    last = Some(e);
+                }
+            }
+        }
+        match (ok, last) {
+            (Some(sig), _) => Ok(sig),
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(ExecError::Rpc("没有可用的 RPC 端点".into())),
+        }
+    }
+
+    async fn signature_status(&self, sig: &Signature) -> ExecResult<Option<SigStatus>> {
+        self.call(|r| r.signature_status(sig)).await
+    }
+
+    async fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> ExecResult<Vec<u64>> {
+        self.call(|r| r.recent_prioritization_fees(accounts)).await
+    }
+
+    async fn account(&self, key: &Pubkey) -> ExecResult<Option<AccountData>> {
+        self.call(|r| r.account(key)).await
+    }
+
+    async fn token_balance(&self, key: &Pubkey) -> ExecResult<Option<u64>> {
+        self.call(|r| r.token_balance(key)).await
+    }
+}
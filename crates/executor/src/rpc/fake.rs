@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::error::{ExecError, ExecResult};
+use crate::simulate::TOKEN_PROGRAM_ID;
+use super::{AccountData, Confirmation, Rpc, SigStatus, SimulateResult};
+
+#[derive(Default)]
+struct State {
+    blockhash: Hash,
+    last_valid_block_height: u64,
+    accounts: HashMap<Pubkey, AccountData>,
+    /// 模拟结束后的账户状态（未设置的取当前状态）
+    post: HashMap<Pubkey, AccountData>,
+    simulation: SimulateResult,
+    sent: Vec<VersionedTransaction>,
+    statuses: HashMap<Signature, SigStatus>,
+    prioritization_fees: Vec<u64>,
+    block_height: u64,
+    /// 每次 block_height 调用后前进多少
+    height_step: u64,
+    /// 第 n 次 send 后视为已确认（0 表示不自动确认）
+    land_after: u32,
+    fail_next: u32,
+    reject_next: u32,
+    /// block_height 一直报错
+    height_down: bool,
+    calls: u64,
+}
+
+/// 内存里的假 RPC：状态全部由测试预设，不访问网络
+#[derive(Default)]
+pub struct FakeRpc {
+    state: Mutex<State>,
+}
+
+/// 只填 amount 的 SPL Token 账户
+fn token_account(amount: u64) -> AccountData {
+    let mut data = vec![0u8; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    AccountData { lamports: 2_039_280, owner: TOKEN_PROGRAM_ID, data }
+}
+
+impl FakeRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_blockhash(&self, hash: Hash, last_valid_block_height: u64) {
+        let mut s = self.state.lock().unwrap();
+        s.blockhash = hash;
+        s.last_valid_block_height = last_valid_block_height;
+    }
+
+    pub fn set_account(&self, key: Pubkey, acc: AccountData) {
+        self.state.lock().unwrap().accounts.insert(key, acc);
+    }
+
+    pub fn remove_account(&self, key: &Pubkey) {
+        self.state.lock().unwrap().accounts.remove(key);
+    }
+
+    pub fn set_lamports(&self, key: Pubkey, lamports: u64) {
+        self.set_account(key, AccountData { lamports, ..Default::default() });
+    }
+
+    pub fn set_token_balance(&self, key: Pubkey, amount: u64) {
+        self.set_account(key, token_account(amount));
+    }
+
+    /// 模拟结果模板（err / logs / units_consumed）；accounts 按请求现算
+    pub fn set_simulation(&self, sim: SimulateResult) {
+        self.state.lock().unwrap().simulation = sim;
+    }
+
+    /// 模拟结束后 key 的状态
+    pub fn set_post_account(&self, key: Pubkey, acc: AccountData) {
+        self.state.lock().unwrap().post.insert(key, acc);
+    }
+
+    pub fn set_post_lamports(&self, key: Pubkey, lamports: u64) {
+        self.set_post_account(key, AccountData { lamports, ..Default::default() });
+    }
+
+    pub fn set_post_token_balance(&self, key: Pubkey, amount: u64) {
+        self.set_post_account(key, token_account(amount));
+    }
+
+    pub fn set_prioritization_fees(&self, fees: Vec<u64>) {
+        self.state.lock().unwrap().prioritization_fees = fees;
+    }
+
+    pub fn set_status(&self, sig: Signature, status: SigStatus) {
+        self.state.lock().unwrap().statuses.insert(sig, status);
+    }
+
+    /// 区块高度；step > 0 时每次查询后前进 step（模拟出块）
+    pub fn set_block_height(&self, height: u64, step: u64) {
+        let mut s = self.state.lock().unwrap();
+        s.block_height = height;
+        s.height_step = step;
+    }
+
+    /// 第 n 次 send 之后该签名视为已确认（之前的广播“丢包”）；0 关闭
+    pub fn land_after_sends(&self, n: u32) {
+        self.state.lock().unwrap().land_after = n;
+    }
+
+    /// block_height 一直返回 `ExecError::Rpc`（其余调用不受影响）
+    pub fn fail_block_height(&self, down: bool) {
+        self.state.lock().unwrap().height_down = down;
+    }
+
+    /// 接下来 n 次调用返回 `ExecError::Rpc`（模拟节点故障）
+    pub fn fail_next(&self, n: u32) {
+        self.state.lock().unwrap().fail_next = n;
+    }
+
+    /// 接下来 n 次调用返回 `ExecError::Rejected`（节点拒绝请求本身）
+    pub fn reject_next(&self, n: u32) {
+        self.state.lock().unwrap().reject_next = n;
+    }
+
+    /// 已广播的交易
+    pub fn sent(&self) -> Vec<VersionedTransaction> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    /// 收到的调用次数（含注入失败的）
+    pub fn calls(&self) -> u64 {
+        self.state.lock().unwrap().calls
+    }
+
+    /// 记一次调用；需要注入失败时返回错误
+    fn enter(&self) -> ExecResult<std::sync::MutexGuard<'_, State>> {
+        let mut s = self.state.lock().unwrap();
+        s.calls += 1;
+        if s.fail_next > 0 {
+            s.fail_next -= 1;
+            return Err(ExecError::Rpc("injected failure".into()));
+        }
+        if s.reject_next > 0 {
+            s.reject_next -= 1;
+            return Err(ExecError::Rejected("injected rejection".into()));
+        }
+        Ok(s)
+    }
+}
+
+impl Rpc for FakeRpc {
+    async fn latest_blockhash(&self) -> ExecResult<(Hash, u64)> {
+        let s = self.enter()?;
+        Ok((s.blockhash, s.last_valid_block_height))
+    }
+
+    async fn simulate(&self, _tx: &VersionedTransaction, accounts: &[Pubkey]) -> ExecResult<SimulateResult> {
+        let s = self.enter()?;
+        let accounts = accounts
+            .iter()
+            .map(|k| s.post.get(k).or_else(|| s.accounts.get(k)).cloned())
+            .collect();
+        Ok(SimulateResult { accounts, ..s.simulation.clone() })
+    }
+
+    async fn block_height(&self) -> ExecResult<u64> {
+        let mut s = self.enter()?;
+        if s.height_down {
+            return Err(ExecError::Rpc("block height unavailable".into()));
+        }
+        let h = s.block_height;
+        s.block_height += s.height_step;
+        Ok(h)
+    }
+
+    async fn send(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        let mut s = self.enter()?;
+        let sig = tx.signatures.first().copied().unwrap_or_default();
+        s.sent.push(tx.clone());
+        if s.land_after > 0 && s.sent.len() as u32 >= s.land_after {
+            let slot = s.block_height;
+            s.statuses.entry(sig).or_insert(SigStatus { slot, confirmation: Confirmation::Confirmed, err: None });
+        }
+        Ok(sig)
+    }
+
+    async fn signature_status(&self, sig: &Signature) -> ExecResult<Option<SigStatus>> {
+        let s = self.enter()?;
+        Ok(s.statuses.get(sig).cloned())
+    }
+
+    async fn recent_prioritization_fees(&self, _accounts: &[Pubkey]) -> ExecResult<Vec<u64>> {
+        let s = self.enter()?;
+        Ok(s.prioritization_fees.clone())
+    }
+
+    async fn account(&self, key: &Pubkey) -> ExecResult<Option<AccountData>> {
+        let s = self.enter()?;
+        Ok(s.accounts.get(key).cloned())
+    }
+}
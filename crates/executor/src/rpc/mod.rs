@@ -0,0 +1,126 @@
+#![allow(async_fn_in_trait)]
+
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use crate::error::ExecResult;
+use crate::simulate::token_amount;
+
+pub mod failover;
+pub mod fake;
+#[cfg(feature = "solana-rpc")]
+pub mod solana;
+
+pub use failover::{EndpointHealth, Failover, HealthConfig};
+pub use fake::FakeRpc;
+#[cfg(feature = "solana-rpc")]
+pub use solana::SolanaRpc;
+
+/// 账户状态（链上或模拟结束时）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountData {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// simulateTransaction 的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulateResult {
+    /// 交易级错误（None 表示模拟成功）
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// 与请求的 accounts 一一对应
+    pub accounts: Vec<Option<AccountData>>,
+}
+
+/// 确认级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confirmation {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+/// getSignatureStatuses 中的一条
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigStatus {
+    pub slot: u64,
+    pub confirmation: Confirmation,
+    /// 已上链但执行失败
+    pub err: Option<TransactionError>,
+}
+
+/// 链上 RPC 抽象；执行层和 bot 只依赖它（测试用 FakeRpc）
+pub trait Rpc: Send + Sync {
+    /// 最新 blockhash 及其 last_valid_block_height
+    async fn latest_blockhash(&self) -> ExecResult<(Hash, u64)>;
+
+    /// 模拟交易（不校验签名、替换最新 blockhash），并返回 accounts 在模拟结束时的状态
+    async fn simulate(&self, tx: &VersionedTransaction, accounts: &[Pubkey]) -> ExecResult<SimulateResult>;
+
+    /// 当前区块高度（与 last_valid_block_height 比较判断过期）
+    async fn block_height(&self) -> ExecResult<u64>;
+
+    /// 广播已签名交易（不等待确认）
+    async fn send(&self, tx: &VersionedTransaction) -> ExecResult<Signature>;
+
+    /// 尽量广播到所有可用节点；单节点实现等同 send
+    async fn broadcast(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        self.send(tx).await
+    }
+
+    /// 签名状态；节点还没见过该签名时返回 None
+    async fn signature_status(&self, sig: &Signature) -> ExecResult<Option<SigStatus>>;
+
+    /// 近期各 slot 写锁定这些账户所付的优先费（micro-lamports / CU）
+    async fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> ExecResult<Vec<u64>>;
+
+    /// 账户数据；不存在时返回 None
+    async fn account(&self, key: &Pubkey) -> ExecResult<Option<AccountData>>;
+
+    /// SPL Token 账户余额；账户不存在时返回 None
+    async fn token_balance(&self, key: &Pubkey) -> ExecResult<Option<u64>> {
+        Ok(self.account(key).await?.and_then(|a| token_amount(&a.data)))
+    }
+}
+
+impl<R: Rpc + ?Sized> Rpc for &R {
+    async fn latest_blockhash(&self) -> ExecResult<(Hash, u64)> {
+        (**self).latest_blockhash().await
+    }
+
+    async fn simulate(&self, tx: &VersionedTransaction, accounts: &[Pubkey]) -> ExecResult<SimulateResult> {
+        (**self).simulate(tx, accounts).await
+    }
+
+    async fn block_height(&self) -> ExecResult<u64> {
+        (**self).block_height().await
+    }
+
+    async fn send(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        (**self).send(tx).await
+    }
+
+    async fn broadcast(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        (**self).broadcast(tx).await
+    }
+
+    async fn signature_status(&self, sig: &Signature) -> ExecResult<Option<SigStatus>> {
+        (**self).signature_status(sig).await
+    }
+
+    async fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> ExecResult<Vec<u64>> {
+        (**self).recent_prioritization_fees(accounts).await
+    }
+
+    async fn account(&self, key: &Pubkey) -> ExecResult<Option<AccountData>> {
+        (**self).account(key).await
+    }
+
+    async fn token_balance(&self, key: &Pubkey) -> ExecResult<Option<u64>> {
+        (**self).token_balance(key).await
+    }
+}
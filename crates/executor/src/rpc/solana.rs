@@ -0,0 +1,123 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::rpc_config::{
+    RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::error::{ExecError, ExecResult};
+use super::{AccountData, Confirmation, Failover, Rpc, SigStatus, SimulateResult};
+
+/// 多端点的 solana-client 实现
+pub type SolanaRpc = Failover<RpcClient>;
+
+impl Failover<RpcClient> {
+    /// 每个 URL 一个 RpcClient（confirmed 级别），按给定顺序故障转移
+    pub fn from_urls<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(
+            urls.into_iter()
+                .map(|u| {
+                    let u = u.into();
+                    (u.clone(), RpcClient::new_with_commitment(u, CommitmentConfig::confirmed()))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// 传输层/IO/超时以及节点自报不健康算端点故障（`ExecError::Rpc`，触发故障转移）；
+/// 其余是节点对请求本身的回答（预检失败、参数错误等），换节点也一样
+fn rpc_err(e: ClientError) -> ExecError {
+    match e.kind() {
+        ClientErrorKind::Io(_)
+        | ClientErrorKind::Reqwest(_)
+        | ClientErrorKind::Middleware(_)
+        | ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::NodeUnhealthy { .. },
+            ..
+        }) => ExecError::Rpc(e.to_string()),
+        _ => ExecError::Rejected(e.to_string()),
+    }
+}
+
+impl Rpc for RpcClient {
+    async fn latest_blockhash(&self) -> ExecResult<(Hash, u64)> {
+        self.get_latest_blockhash_with_commitment(self.commitment()).await.map_err(rpc_err)
+    }
+
+    async fn simulate(&self, tx: &VersionedTransaction, accounts: &[Pubkey]) -> ExecResult<SimulateResult> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            // 账户默认按 base64 返回
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: None,
+                addresses: accounts.iter().map(|k| k.to_string()).collect(),
+            }),
+            ..Default::default()
+        };
+        let res = self.simulate_transaction_with_config(tx, config).await.map_err(rpc_err)?.value;
+
+        let accounts = res
+            .accounts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|a| {
+                a.map(|a| AccountData {
+                    lamports: a.lamports,
+                    owner: a.owner.parse().unwrap_or_default(),
+                    data: a.data.decode().unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        Ok(SimulateResult {
+            err: res.err,
+            logs: res.logs.unwrap_or_default(),
+            units_consumed: res.units_consumed,
+            accounts,
+        })
+    }
+
+    async fn block_height(&self) -> ExecResult<u64> {
+        self.get_block_height_with_commitment(self.commitment()).await.map_err(rpc_err)
+    }
+
+    async fn send(&self, tx: &VersionedTransaction) -> ExecResult<Signature> {
+        // 发送前已经模拟过；重发由调用方负责
+        let config = RpcSendTransactionConfig { skip_preflight: true, max_retries: Some(0), ..Default::default() };
+        self.send_transaction_with_config(tx, config).await.map_err(rpc_err)
+    }
+
+    async fn signature_status(&self, sig: &Signature) -> ExecResult<Option<SigStatus>> {
+        let res = self.get_signature_statuses(&[*sig]).await.map_err(rpc_err)?;
+        Ok(res.value.into_iter().next().flatten().map(|s| {
+            let confirmation = if s.satisfies_commitment(CommitmentConfig::finalized()) {
+                Confirmation::Finalized
+            } else if s.satisfies_commitment(CommitmentConfig::confirmed()) {
+                Confirmation::Confirmed
+            } else {
+                Confirmation::Processed
+            };
+            SigStatus { slot: s.slot, confirmation, err: s.err }
+        }))
+    }
+
+    async fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> ExecResult<Vec<u64>> {
+        let fees = self.get_recent_prioritization_fees(accounts).await.map_err(rpc_err)?;
+        Ok(fees.into_iter().map(|f| f.prioritization_fee).collect())
+    }
+
+    async fn account(&self, key: &Pubkey) -> ExecResult<Option<AccountData>> {
+        let res = self.get_account_with_commitment(key, self.commitment()).await.map_err(rpc_err)?;
+        Ok(res.value.map(|a| AccountData { lamports: a.lamports, owner: a.owner, data: a.data }))
+    }
+}
@@ -0,0 +1,253 @@
+#![allow(async_fn_in_trait)]
+
+use std::fmt;
+use std::time::Duration;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use tokio::time::{sleep, Instant};
+use crate::error::ExecResult;
+use crate::rpc::{Confirmation, Rpc, SigStatus};
+use crate::simulate::TOKEN_PROGRAM_ID;
+
+pub const JUPITER_V6_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// 各程序“滑点超限”的自定义错误码
+const SLIPPAGE_CODES: &[(Pubkey, u32)] = &[
+    // SlippageToleranceExceeded
+    (JUPITER_V6_PROGRAM_ID, 6001),
+    // ExceededSlippage
+    (RAYDIUM_AMM_V4_PROGRAM_ID, 30),
+];
+
+/// SPL Token 的 InsufficientFunds
+const TOKEN_INSUFFICIENT_FUNDS: u32 = 1;
+
+/// 交易没有成功上链的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendFailure {
+    /// blockhash 已过期仍未上链（可以换 blockhash 重签）
+    Expired,
+    /// 一次都没广播成功
+    Unsent(String),
+    /// 超时前已被节点看到但没达到要求的确认级别（附最后一次查到的状态），之后仍可能上链
+    Unconfirmed(SigStatus),
+    SlippageExceeded { program: Pubkey, code: u32 },
+    /// SOL 不够付手续费/租金，或代币余额不足
+    InsufficientFunds,
+    /// 其它程序自定义错误（instruction 为指令下标）
+    ProgramError { instruction: u8, program: Option<Pubkey>, code: u32 },
+    Other(TransactionError),
+}
+
+impl fmt::Display for SendFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendFailure::Expired => write!(f, "blockhash 过期，交易未上链"),
+            SendFailure::Unsent(e) => write!(f, "广播失败: {}", e),
+            SendFailure::Unconfirmed(st) => {
+                write!(f, "超时未确认（slot {} 处于 {:?}），仍可能上链", st.slot, st.confirmation)
+            }
+            SendFailure::SlippageExceeded { program, code } => {
+                write!(f, "滑点超限（{} 错误码 {:#x}）", program, code)
+            }
+            SendFailure::InsufficientFunds => write!(f, "余额不足"),
+            SendFailure::ProgramError { instruction, program, code } => match program {
+                Some(p) => write!(f, "指令 {} 失败：{} 错误码 {:#x}", instruction, p, code),
+                None => write!(f, "指令 {} 失败：错误码 {:#x}", instruction, code),
+            },
+            SendFailure::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// 链上执行错误分类；需要 tx 才能知道出错指令属于哪个程序
+pub fn classify(tx: &VersionedTransaction, err: &TransactionError) -> SendFailure {
+    match err {
+        TransactionError::InsufficientFundsForFee | TransactionError::InsufficientFundsForRent { .. } => {
+            SendFailure::InsufficientFunds
+        }
+        TransactionError::BlockhashNotFound => SendFailure::Expired,
+        TransactionError::InstructionError(i, InstructionError::Custom(code)) => {
+            let program = program_of(tx, *i);
+            match program {
+                Some(p) if SLIPPAGE_CODES.contains(&(p, *code)) => SendFailure::SlippageExceeded { program: p, code: *code },
+                Some(p) if p == TOKEN_PROGRAM_ID && *code == TOKEN_INSUFFICIENT_FUNDS => SendFailure::InsufficientFunds,
+                _ => SendFailure::ProgramError { instruction: *i, program, code: *code },
+            }
+        }
+        TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => SendFailure::InsufficientFunds,
+        e => SendFailure::Other(e.clone()),
+    }
+}
+
+/// 第 i 条顶层指令的程序（程序 id 总在静态账户里）
+fn program_of(tx: &VersionedTransaction, i: u8) -> Option<Pubkey> {
+    let ix = tx.message.instructions().get(i as usize)?;
+    tx.message.static_account_keys().get(ix.program_id_index as usize).copied()
+}
+
+/// 发送结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendOutcome {
+    pub signature: Signature,
+    /// 上链的 slot（执行失败也会上链）
+    pub slot: Option<u64>,
+    /// None 表示成功
+    pub failure: Option<SendFailure>,
+    /// 成功广播的次数
+    pub broadcasts: u32,
+    /// 随交易支付的小费（lamports，Jito bundle 才有）
+    pub tip: u64,
+    pub elapsed: Duration,
+}
+
+impl SendOutcome {
+    pub fn landed(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// 结果未定：已被看到但没等到确认
+    pub fn pending(&self) -> bool {
+        matches!(self.failure, Some(SendFailure::Unconfirmed(_)))
+    }
+}
+
+/// 发送方式：普通 RPC 广播或 Jito bundle
+pub trait Submitter: Send + Sync {
+    /// tx 已签名；expected_profit（lamports）供需要付小费的实现定价
+    async fn submit(
+        &self,
+        tx: &VersionedTransaction,
+        last_valid_block_height: u64,
+        expected_profit: i128,
+    ) -> ExecResult<SendOutcome>;
+}
+
+/// 重发/轮询节奏
+#[derive(Debug, Clone, Copy)]
+pub struct SendConfig {
+    /// 轮询签名状态的间隔
+    pub poll_interval: Duration,
+    /// 还没被任何节点看到时，每隔多久重发一次
+    pub rebroadcast_interval: Duration,
+    /// 达到该确认级别才算成功
+    pub commitment: Confirmation,
+    /// 总时长上限：区块高度一直查不到（节点全挂）时也能结束
+    pub timeout: Duration,
+}
+
+impl Default for SendConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(400),
+            rebroadcast_interval: Duration::from_secs(2),
+            commitment: Confirmation::Confirmed,
+            // blockhash 约 150 个块（~60s）有效，留些余量
+            timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+/// 没上链就放弃时的原因：一次都没发出去算 Unsent，否则按过期处理
+pub(crate) fn gave_up(sent: u32, last_error: Option<String>) -> SendFailure {
+    match (sent, last_error) {
+        (0, Some(e)) => SendFailure::Unsent(e),
+        _ => SendFailure::Expired,
+    }
+}
+
+/// 基于 RPC 的发送器：反复广播直到上链或 blockhash 过期
+pub struct RpcSender<R> {
+    rpc: R,
+    cfg: SendConfig,
+}
+
+impl<R: Rpc> RpcSender<R> {
+    pub fn new(rpc: R) -> Self {
+        Self { rpc, cfg: SendConfig::default() }
+    }
+
+    pub fn config(mut self, cfg: SendConfig) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    /// last_valid_block_height：签名时所用 blockhash 的有效期；超过 `SendConfig::timeout` 仍未确认也放弃，
+    /// 此时若已被节点看到则返回 `SendFailure::Unconfirmed`
+    pub async fn send(&self, tx: &VersionedTransaction, last_valid_block_height: u64) -> SendOutcome {
+        let start = Instant::now();
+        let signature = tx.signatures.first().copied().unwrap_or_default();
+        let mut broadcasts = 0u32;
+        let mut last_error = None;
+        let mut last_broadcast: Option<Instant> = None;
+        let mut seen: Option<SigStatus> = None;
+
+        let done = |slot, failure, broadcasts| SendOutcome {
+            signature,
+            slot,
+            failure,
+            broadcasts,
+            tip: 0,
+            elapsed: start.elapsed(),
+        };
+
+        loop {
+            // 没被任何节点看到就按间隔重发
+            if seen.is_none() && last_broadcast.is_none_or(|t| t.elapsed() >= self.cfg.rebroadcast_interval) {
+                match self.rpc.broadcast(tx).await {
+                    Ok(_) => broadcasts += 1,
+                    Err(e) => last_error = Some(e.to_string()),
+                }
+                last_broadcast = Some(Instant::now());
+            }
+
+            sleep(self.cfg.poll_interval).await;
+
+            if let Some(st) = self.status(&signature).await {
+                if let Some(err) = &st.err {
+                    return done(Some(st.slot), Some(classify(tx, err)), broadcasts);
+                }
+                if st.confirmation >= self.cfg.commitment {
+                    return done(Some(st.slot), None, broadcasts);
+                }
+                seen = Some(st);
+            }
+
+            // 过期：最后再查一次，避免刚好在边界上链
+            if let Ok(h) = self.rpc.block_height().await {
+                if h > last_valid_block_height {
+                    match self.status(&signature).await {
+                        Some(st) => seen = Some(st),
+                        None => return done(None, Some(gave_up(broadcasts, last_error)), broadcasts),
+                    }
+                }
+            }
+
+            if start.elapsed() >= self.cfg.timeout {
+                let failure = match seen {
+                    Some(st) => SendFailure::Unconfirmed(st),
+                    None => gave_up(broadcasts, last_error),
+                };
+                return done(None, Some(failure), broadcasts);
+            }
+        }
+    }
+
+    async fn status(&self, sig: &Signature) -> Option<SigStatus> {
+        self.rpc.signature_status(sig).await.ok().flatten()
+    }
+}
+
+impl<R: Rpc> Submitter for RpcSender<R> {
+    async fn submit(&self, tx: &VersionedTransaction, last_valid_block_height: u64, _expected_profit: i128) -> ExecResult<SendOutcome> {
+        Ok(self.send(tx, last_valid_block_height).await)
+    }
+}
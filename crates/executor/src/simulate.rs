@@ -0,0 +1,141 @@
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::error::{ExecError, ExecResult};
+use crate::rpc::{AccountData, Rpc};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// owner 在 mint 下的 ATA（仅经典 Token 程序）
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// 用哪个账户衡量利润
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfitAccount {
+    /// SPL Token 账户（base 为代币时用其 ATA）
+    Token(Pubkey),
+    /// 钱包本身的 lamports（base 为 SOL 且不走 wSOL 时；差值已扣除手续费）
+    Native(Pubkey),
+}
+
+impl ProfitAccount {
+    /// 按 base mint 选账户：SOL（Jupiter 默认自动 wrap/unwrap）看钱包 lamports，其余看 ATA
+    pub fn for_mint(owner: &Pubkey, mint: &Pubkey) -> Self {
+        if *mint == WSOL_MINT {
+            ProfitAccount::Native(*owner)
+        } else {
+            ProfitAccount::Token(associated_token_address(owner, mint))
+        }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            ProfitAccount::Token(k) | ProfitAccount::Native(k) => *k,
+        }
+    }
+
+    /// 当前链上余额（账户不存在按 0 算）
+    pub async fn balance_on<R: Rpc + ?Sized>(&self, rpc: &R) -> ExecResult<u64> {
+        match self {
+            ProfitAccount::Native(k) => Ok(rpc.account(k).await?.map_or(0, |a| a.lamports)),
+            ProfitAccount::Token(k) => Ok(rpc.token_balance(k).await?.unwrap_or(0)),
+        }
+    }
+
+    fn balance(&self, acc: &AccountData) -> ExecResult<u64> {
+        match self {
+            ProfitAccount::Native(_) => Ok(acc.lamports),
+            ProfitAccount::Token(k) => token_amount(&acc.data)
+                .ok_or_else(|| ExecError::Simulation(format!("{} 不是 token 账户", k))),
+        }
+    }
+}
+
+/// SPL Token 账户布局：mint(32) | owner(32) | amount(u64 LE) | …
+pub fn token_amount(data: &[u8]) -> Option<u64> {
+    data.get(64..72).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// 某个程序消耗的 CU（来自 "Program X consumed N of M compute units"）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramUnits {
+    pub program: String,
+    pub consumed: u64,
+}
+
+/// 模拟通过后的报告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimReport {
+    pub units_consumed: Option<u64>,
+    pub programs: Vec<ProgramUnits>,
+    pub post_balance: u64,
+    /// post - pre（base 最小单位）
+    pub profit: i128,
+    pub logs: Vec<String>,
+}
+
+/// 发送前的模拟闸门：模拟失败或利润不足都拒绝
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationGate {
+    /// 最低利润（base 最小单位，可为负表示允许小亏，如普通兑换只看是否成功）
+    pub min_profit: i128,
+}
+
+impl SimulationGate {
+    /// pre_balance：发送前利润账户的余额
+    pub async fn check<R: Rpc + ?Sized>(
+        &self,
+        rpc: &R,
+        tx: &VersionedTransaction,
+        account: ProfitAccount,
+        pre_balance: u64,
+    ) -> ExecResult<SimReport> {
+        let sim = rpc.simulate(tx, &[account.pubkey()]).await?;
+        if let Some(err) = sim.err {
+            return Err(ExecError::SimulationFailed { err, logs: error_logs(&sim.logs) });
+        }
+
+        let acc = sim.accounts.first().cloned().flatten().unwrap_or_default();
+        let post_balance = account.balance(&acc)?;
+        let profit = post_balance as i128 - pre_balance as i128;
+        if profit < self.min_profit {
+            return Err(ExecError::InsufficientProfit { min: self.min_profit, simulated: profit });
+        }
+
+        Ok(SimReport {
+            units_consumed: sim.units_consumed,
+            programs: program_units(&sim.logs),
+            post_balance,
+            profit,
+            logs: sim.logs,
+        })
+    }
+}
+
+/// 解析每个程序（顶层调用）的 CU 消耗
+pub fn program_units(logs: &[String]) -> Vec<ProgramUnits> {
+    logs.iter()
+        .filter_map(|l| {
+            let rest = l.strip_prefix("Program ")?;
+            let (program, rest) = rest.split_once(" consumed ")?;
+            let consumed = rest.split_whitespace().next()?.parse().ok()?;
+            Some(ProgramUnits { program: program.to_string(), consumed })
+        })
+        .collect()
+}
+
+/// 挑出报错相关的日志行（失败时返回给调用方）
+pub fn error_logs(logs: &[String]) -> Vec<String> {
+    logs.iter()
+        .filter(|l| l.contains("failed") || l.contains("Error") || l.contains("error"))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use executor::{AtomicTxBuilder, ComputeBudget, ExecError, LegInstructions};
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+#[test]
+fn fixture_wallet_matches() {
+    let user = common::user().pubkey();
+    assert!(common::cycle()[0].swap.accounts.iter().any(|a| a.pubkey == user && a.is_signer));
+}
+
+#[test]
+fn merges_two_legs_into_one_v0_tx() {
+    let user = common::user();
+    let legs = common::cycle();
+    let builder = AtomicTxBuilder::new(user.pubkey());
+
+    let (ixs, budget) = builder.instructions(&legs).unwrap();
+    // CU 上限按跳累加，价格取最大
+    assert_eq!(budget, ComputeBudget { unit_limit: Some(500_000), unit_price: Some(5_000), ..Default::default() });
+    assert_eq!(ixs.iter().filter(|i| i.program_id == compute_budget::id()).count(), 2);
+
+    // setup：leg1 = 2×ATA + wrap(2)，leg2 的两个 ATA 重复 → 去掉
+    // 顺序：2 CU + 4 setup + 2 swap + 1 cleanup（两跳的 close wSOL 相同，只留一次且在最后）
+    assert_eq!(ixs.len(), 2 + 4 + 2 + 1);
+    assert_eq!(ixs[6], legs[0].swap);
+    assert_eq!(ixs[7], legs[1].swap);
+    assert_eq!(Some(&ixs[8]), legs[1].cleanup.as_ref());
+
+    let built = builder.build(&legs, &common::alts(), Hash::new_unique()).unwrap();
+    assert!(built.size <= executor::TX_SIZE_LIMIT, "size={}", built.size);
+    assert_eq!(built.message.address_table_lookups().map(|l| l.len()), Some(2));
+
+    // 签名后可序列化/反序列化，大小与预估一致
+    let tx = built.sign(&[&user]).unwrap();
+    assert!(tx.verify_with_results().iter().all(|ok| *ok));
+    let bytes = bincode::serialize(&tx).unwrap();
+    assert_eq!(bytes.len(), built.size);
+    let back: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(back, tx);
+}
+
+#[test]
+fn overrides_compute_budget() {
+    let legs = common::cycle();
+    let b = AtomicTxBuilder::new(common::user().pubkey()).unit_limit(420_000).unit_price(7);
+    let (ixs, _) = b.instructions(&legs).unwrap();
+    assert_eq!(ixs[0], ComputeBudgetInstruction::set_compute_unit_limit(420_000));
+    assert_eq!(ixs[1], ComputeBudgetInstruction::set_compute_unit_price(7));
+}
+
+#[test]
+fn without_lookup_tables_the_cycle_is_too_large() {
+    let legs = common::cycle();
+    let err = AtomicTxBuilder::new(common::user().pubkey()).build(&legs, &[], Hash::default()).unwrap_err();
+    match err {
+        ExecError::TooLarge { size, limit } => assert!(size > limit),
+        e => panic!("unexpected: {e}"),
+    }
+}
+
+#[test]
+fn empty_path_is_rejected() {
+    let b = AtomicTxBuilder::new(Pubkey::new_unique());
+    assert!(matches!(b.instructions(&[]), Err(ExecError::EmptyPath)));
+}
+
+#[test]
+fn cu_limit_is_capped() {
+    let big = |n| {
+        let mut l = LegInstructions::swap_only(Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![AccountMeta::new(Pubkey::new_unique(), false)]));
+        l.compute_budget = vec![ComputeBudgetInstruction::set_compute_unit_limit(n)];
+        l
+    };
+    let (_, budget) = AtomicTxBuilder::new(Pubkey::new_unique()).instructions(&[big(1_000_000), big(1_000_000)]).unwrap();
+    assert_eq!(budget.unit_limit, Some(1_400_000));
+}
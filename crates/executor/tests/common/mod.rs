@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+use std::collections::HashMap;
+use std::str::FromStr;
+use executor::{AtomicTxBuilder, LegInstructions};
+use client::SwapInstructionsResponse;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{keypair_from_seed, Keypair};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+fn pk(s: &str) -> Pubkey { Pubkey::from_str(s).unwrap() }
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// 录制的 Jupiter /swap-instructions 响应转成的一跳
+pub fn leg(name: &str) -> LegInstructions {
+    let r: SwapInstructionsResponse = serde_json::from_str(&fixture(name)).unwrap();
+    r.try_into().unwrap()
+}
+
+pub fn alts() -> Vec<AddressLookupTableAccount> {
+    let m: HashMap<String, Vec<String>> = serde_json::from_str(&fixture("alts.json")).unwrap();
+    let mut v: Vec<_> = m
+        .into_iter()
+        .map(|(k, a)| AddressLookupTableAccount { key: pk(&k), addresses: a.iter().map(|s| pk(s)).collect() })
+        .collect();
+    v.sort_by_key(|a| a.key);
+    v
+}
+
+/// 录制 fixture 时用的钱包（seed = [7; 32]）
+pub fn user() -> Keypair {
+    keypair_from_seed(&[7u8; 32]).unwrap()
+}
+
+/// SOL → USDC → SOL
+pub fn cycle() -> Vec<LegInstructions> {
+    vec![leg("swap_ix_sol_usdc.json"), leg("swap_ix_usdc_sol.json")]
+}
+
+/// 已签名的 SOL → USDC → SOL 交易
+/// 指令顺序：0-1 CU，2-5 setup（4 为 Token syncNative），6-7 Jupiter swap，8 cleanup
+pub fn signed_cycle() -> VersionedTransaction {
+    let user = user();
+    let built = AtomicTxBuilder::new(user.pubkey())
+        .build(&cycle(), &alts(), Hash::new_unique())
+        .unwrap();
+    built.sign(&[&user]).unwrap()
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use base64::Engine as _;
+use executor::jito::{tip_account, TIP_ACCOUNTS};
+use executor::{
+    ExecError, FakeRpc, JitoClient, JitoSubmitter, RpcSender, SendConfig, SendFailure, Submitter, TipPolicy,
+};
+use serde_json::{json, Value};
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/* ---- block engine 替身：按脚本应答 JSON-RPC ---- */
+#[derive(Default)]
+struct Engine {
+    /// 收到的 bundle
+    bundles: Vec<Vec<VersionedTransaction>>,
+    /// 第几次状态查询起返回 Landed（0 = 永远 Pending）
+    land_on_poll: usize,
+    polls: usize,
+    /// sendBundle 一律返回该错误
+    reject: Option<String>,
+    /// 收下请求但永不应答
+    hang: bool,
+}
+
+fn handle(engine: &Mutex<Engine>, req: Value) -> Value {
+    let mut e = engine.lock().unwrap();
+    let result = match req["method"].as_str().unwrap() {
+        "sendBundle" => {
+            if let Some(msg) = &e.reject {
+                return json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": msg } });
+            }
+            assert_eq!(req["params"][1]["encoding"], "base64");
+            let txs = req["params"][0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| {
+                    let b = base64::engine::general_purpose::STANDARD.decode(t.as_str().unwrap()).unwrap();
+                    bincode::deserialize(&b).unwrap()
+                })
+                .collect();
+            e.bundles.push(txs);
+            json!("bundle-1")
+        }
+        "getInflightBundleStatuses" => {
+            e.polls += 1;
+            let (status, slot) = if e.land_on_poll > 0 && e.polls >= e.land_on_poll {
+                ("Landed", json!(4242))
+            } else {
+                ("Pending", Value::Null)
+            };
+            json!({ "context": { "slot": 1 }, "value": [{ "bundle_id": "bundle-1", "status": status, "landed_slot": slot }] })
+        }
+        m => panic!("unexpected method {m}"),
+    };
+    json!({ "jsonrpc": "2.0", "id": 1, "result": result })
+}
+
+/// 在本地随机端口起一个 HTTP 服务，返回 base url
+async fn serve(engine: Arc<Mutex<Engine>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                // 读完请求头，再按 Content-Length 读请求体
+                let (head_end, len) = loop {
+                    let n = sock.read(&mut chunk).await.unwrap();
+                    if n == 0 { return; }
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&buf[..i]).to_ascii_lowercase();
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                            .unwrap_or(0);
+                        break (i + 4, len);
+                    }
+                };
+                while buf.len() < head_end + len {
+                    let n = sock.read(&mut chunk).await.unwrap();
+                    if n == 0 { return; }
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                if engine.lock().unwrap().hang {
+                    return std::future::pending().await;
+                }
+                let req: Value = serde_json::from_slice(&buf[head_end..head_end + len]).unwrap();
+                let body = handle(&engine, req).to_string();
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+            });
+        }
+    });
+    format!("http://{}", addr)
+}
+
+fn fast() -> SendConfig {
+    SendConfig {
+        poll_interval: Duration::from_millis(10),
+        rebroadcast_interval: Duration::from_millis(30),
+        ..SendConfig::default()
+    }
+}
+
+fn fake_rpc() -> FakeRpc {
+    let rpc = FakeRpc::new();
+    rpc.set_block_height(1_000, 1);
+    rpc
+}
+
+fn submitter<'a>(url: &str, rpc: &'a FakeRpc) -> JitoSubmitter<&'a FakeRpc> {
+    JitoSubmitter::new(JitoClient::new(url).unwrap(), rpc, Arc::new(common::user()))
+        .tip_policy(TipPolicy { profit_share_bps: 1_000, ..TipPolicy::default() })
+        .config(fast())
+}
+
+/// 泛型调用方只认 Submitter
+async fn run<S: Submitter>(s: &S, tx: &VersionedTransaction, profit: i128) -> executor::SendOutcome {
+    s.submit(tx, 2_000, profit).await.unwrap()
+}
+
+#[test]
+fn tip_policy() {
+    let p = TipPolicy { profit_share_bps: 1_000, min_tip: 1_000, max_tip: 50_000 };
+    assert_eq!(p.tip(100_000).unwrap(), 10_000);
+    assert_eq!(p.tip(20_000).unwrap(), 2_000);
+    assert_eq!(p.tip(5_000).unwrap(), 1_000);
+    assert_eq!(p.tip(10_000_000).unwrap(), 50_000);
+    assert!(matches!(p.tip(900), Err(ExecError::FeeExceedsProfit { fee: 1_000, profit: 900 })));
+}
+
+#[tokio::test]
+async fn bundle_with_tip_lands() {
+    let engine = Arc::new(Mutex::new(Engine { land_on_poll: 3, ..Engine::default() }));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+    let tx = common::signed_cycle();
+
+    let out = run(&submitter(&url, &rpc), &tx, 500_000).await;
+    assert!(out.landed(), "{:?}", out.failure);
+    assert_eq!(out.slot, Some(4242));
+    assert_eq!(out.signature, tx.signatures[0]);
+    assert_eq!(out.tip, 50_000);
+
+    let e = engine.lock().unwrap();
+    // Pending 期间不重提
+    assert_eq!(e.bundles.len(), 1);
+    let bundle = &e.bundles[0];
+    assert_eq!(bundle.len(), 2);
+    assert_eq!(bundle[0], tx);
+
+    // 小费：同一 blockhash、付款人签名、转给小费账户
+    let tip = &bundle[1];
+    assert!(tip.verify_with_results().iter().all(|ok| *ok));
+    assert_eq!(tip.message.recent_blockhash(), tx.message.recent_blockhash());
+    let keys = tip.message.static_account_keys();
+    let ix = &tip.message.instructions()[0];
+    assert_eq!(keys[ix.program_id_index as usize], system_program::id());
+    assert_eq!(keys[ix.accounts[1] as usize], tip_account(&tx));
+    assert!(TIP_ACCOUNTS.contains(&tip_account(&tx)));
+    let SystemInstruction::Transfer { lamports } = bincode::deserialize(&ix.data).unwrap() else { panic!() };
+    assert_eq!(lamports, 50_000);
+}
+
+#[tokio::test]
+async fn bundle_expires_when_never_landed() {
+    let engine = Arc::new(Mutex::new(Engine::default()));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+    rpc.set_block_height(1_990, 1);
+
+    let out = run(&submitter(&url, &rpc), &common::signed_cycle(), 500_000).await;
+    assert_eq!(out.failure, Some(SendFailure::Expired));
+    assert_eq!((out.slot, out.tip), (None, 0));
+    assert!(out.broadcasts >= 1);
+}
+
+#[tokio::test]
+async fn bundle_gives_up_at_timeout_when_block_height_is_down() {
+    let engine = Arc::new(Mutex::new(Engine::default()));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+    rpc.fail_block_height(true);
+
+    let s = submitter(&url, &rpc).config(SendConfig { timeout: Duration::from_millis(300), ..fast() });
+    let out = run(&s, &common::signed_cycle(), 500_000).await;
+    assert_eq!(out.failure, Some(SendFailure::Expired));
+    assert!(out.elapsed >= Duration::from_millis(300) && out.elapsed < Duration::from_secs(5), "{:?}", out.elapsed);
+}
+
+#[tokio::test]
+async fn hung_block_engine_does_not_outlive_the_timeout() {
+    let engine = Arc::new(Mutex::new(Engine { hang: true, ..Engine::default() }));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+
+    let s = submitter(&url, &rpc).config(SendConfig { timeout: Duration::from_millis(300), ..fast() });
+    let out = run(&s, &common::signed_cycle(), 500_000).await;
+    assert!(matches!(out.failure, Some(SendFailure::Unsent(_))), "{:?}", out.failure);
+    assert!(out.elapsed < Duration::from_secs(2), "{:?}", out.elapsed);
+}
+
+#[tokio::test]
+async fn rejected_bundle_is_unsent() {
+    let engine = Arc::new(Mutex::new(Engine { reject: Some("bundle contains an expired blockhash".into()), ..Engine::default() }));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+    rpc.set_block_height(1_995, 1);
+
+    let out = run(&submitter(&url, &rpc), &common::signed_cycle(), 500_000).await;
+    assert!(
+        matches!(&out.failure, Some(SendFailure::Unsent(m)) if m.contains("expired blockhash")),
+        "{:?}",
+        out.failure
+    );
+    assert!(engine.lock().unwrap().bundles.is_empty());
+}
+
+#[tokio::test]
+async fn tip_above_profit_is_refused_before_sending() {
+    let engine = Arc::new(Mutex::new(Engine::default()));
+    let url = serve(engine.clone()).await;
+    let rpc = fake_rpc();
+
+    let err = submitter(&url, &rpc).submit(&common::signed_cycle(), 2_000, 500).await.unwrap_err();
+    assert!(matches!(err, ExecError::FeeExceedsProfit { .. }));
+    assert!(engine.lock().unwrap().bundles.is_empty());
+}
+
+#[tokio::test]
+async fn plain_rpc_is_a_submitter_too() {
+    let rpc = fake_rpc();
+    rpc.land_after_sends(1);
+    let sender = RpcSender::new(&rpc).config(fast());
+
+    let out = run(&sender, &common::signed_cycle(), 0).await;
+    assert!(out.landed());
+    assert_eq!(out.tip, 0);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use client::SwapInstructionsResponse;
+use executor::{AtomicTxBuilder, LegInstructions};
+use solana_sdk::pubkey::Pubkey;
+use utils::AppError;
+
+fn response() -> SwapInstructionsResponse {
+    let text = std::fs::read_to_string(format!("{}/tests/fixtures/swap_ix_sol_usdc.json", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    serde_json::from_str(&text).unwrap()
+}
+
+/* ---- 录制响应 → 执行器的一跳 ---- */
+#[test]
+fn converts_to_leg_instructions() {
+    let leg: LegInstructions = response().try_into().unwrap();
+
+    let cb: Pubkey = "ComputeBudget111111111111111111111111111111".parse().unwrap();
+    let jup: Pubkey = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".parse().unwrap();
+    assert_eq!(leg.compute_budget.len(), 2);
+    assert!(leg.compute_budget.iter().all(|ix| ix.program_id == cb));
+    // SetComputeUnitLimit(200_000)
+    assert_eq!(leg.compute_budget[0].data, vec![2, 0x40, 0x0d, 0x03, 0]);
+    assert_eq!(leg.setup.len(), 4);
+    assert_eq!(leg.swap.program_id, jup);
+    assert_eq!(leg.swap.accounts.len(), 22);
+    let user: Pubkey = "GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB".parse().unwrap();
+    assert!(leg.swap.accounts.iter().any(|a| a.pubkey == user && a.is_signer));
+    assert!(leg.cleanup.is_some());
+    assert_eq!(leg.address_lookup_tables, vec!["9M7mZTiBaBZFWSFfU7J3V8uGaQpA7Voem7HBrSYiRdfC".parse::<Pubkey>().unwrap()]);
+}
+
+/* ---- 转换后的指令可直接交给 AtomicTxBuilder ---- */
+#[test]
+fn leg_feeds_tx_builder() {
+    let leg = LegInstructions::try_from(&response()).unwrap();
+    let payer: Pubkey = "GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB".parse().unwrap();
+    let (ixs, budget) = AtomicTxBuilder::new(payer).instructions(&[leg]).unwrap();
+
+    // 2 CU + 4 setup + swap + cleanup
+    assert_eq!(ixs.len(), 8);
+    assert_eq!(budget.unit_limit, Some(200_000));
+}
+
+/* ---- 坏数据报 ParseError，不 panic ---- */
+#[test]
+fn invalid_pubkey_is_parse_error() {
+    let mut r = response();
+    r.swap_instruction.program_id = "not-a-key".into();
+    assert!(matches!(LegInstructions::try_from(&r), Err(AppError::ParseError(_))));
+
+    let mut r = response();
+    r.swap_instruction.data = "%%%".into();
+    assert!(matches!(LegInstructions::try_from(&r), Err(AppError::ParseError(_))));
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use std::time::Duration;
+use executor::priority_fee::{apply_plan, priority_fee_lamports, writable_accounts};
+use executor::{
+    AtomicTxBuilder, Confirmation, ExecError, FakeRpc, FeeLedger, FeePlan, FeePolicy, SendFailure,
+    SendOutcome, SigStatus,
+};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use utils::fees::{average_paid, read_fee_records};
+
+#[test]
+fn price_percentile_and_unit_limit() {
+    let p = FeePolicy::default();
+    let recent: Vec<u64> = (1..=100).rev().collect();
+    assert_eq!(p.estimate_price(&recent), 75);
+    assert_eq!(p.estimate_price(&[]), 0);
+    assert_eq!(FeePolicy { max_unit_price: 50, ..p }.estimate_price(&recent), 50);
+    assert_eq!(FeePolicy { min_unit_price: 1_000, ..p }.estimate_price(&[]), 1_000);
+
+    // +15%，向上取整，且不超过 1.4M
+    assert_eq!(p.unit_limit(200_000), 230_000);
+    assert_eq!(p.unit_limit(100_001), 115_002);
+    assert_eq!(p.unit_limit(5_000_000), 1_400_000);
+
+    assert_eq!(priority_fee_lamports(200_000, 10_000), 2_000);
+    assert_eq!(priority_fee_lamports(1, 1), 1);
+}
+
+#[test]
+fn priority_fee_is_capped_by_expected_profit() {
+    let p = FeePolicy::default();
+    let recent = vec![1_000_000; 10];
+
+    // 利润充足：按市场价
+    let plan = p.plan(&recent, 200_000, 1, 10_000_000).unwrap();
+    assert_eq!(plan, FeePlan { unit_limit: 230_000, unit_price: 1_000_000, priority_fee: 230_000, base_fee: 5_000 });
+
+    // 利润 20_000：最多花一半 = 10_000，扣掉基础费还剩 5_000 给优先费
+    let plan = p.plan(&recent, 200_000, 1, 20_000).unwrap();
+    assert!(plan.total() <= 10_000, "{plan:?}");
+    assert_eq!(plan.unit_price, 5_000 * 1_000_000 / 230_000);
+
+    // 连基础费都不够
+    let err = p.plan(&recent, 200_000, 2, 10_000).unwrap_err();
+    assert!(matches!(err, ExecError::FeeExceedsProfit { fee: 10_000, profit: 10_000 }));
+}
+
+#[tokio::test]
+async fn plan_from_rpc_and_rebuild() {
+    let rpc = FakeRpc::new();
+    rpc.set_prioritization_fees(vec![0, 10, 20_000, 30_000]);
+    let user = common::user();
+    let legs = common::cycle();
+    let builder = AtomicTxBuilder::new(user.pubkey());
+    let built = builder.clone().build(&legs, &common::alts(), Hash::new_unique()).unwrap();
+
+    // 付款人一定在可写账户里
+    assert!(writable_accounts(&built.message).contains(&user.pubkey()));
+
+    let plan = FeePolicy::default().plan_for(&rpc, &built.message, 300_000, 50_000_000).await.unwrap();
+    assert_eq!((plan.unit_limit, plan.unit_price), (345_000, 20_000));
+
+    let (ixs, _) = builder.fees(&plan).instructions(&legs).unwrap();
+    assert_eq!(ixs[0], ComputeBudgetInstruction::set_compute_unit_limit(345_000));
+    assert_eq!(ixs[1], ComputeBudgetInstruction::set_compute_unit_price(20_000));
+}
+
+#[test]
+fn plan_rewrites_compiled_message() {
+    let user = common::user();
+    let plan = FeePlan { unit_limit: 345_000, unit_price: 20_000, priority_fee: 6_900, base_fee: 5_000 };
+    let budget_ixs = |m: &solana_sdk::message::VersionedMessage| -> Vec<Vec<u8>> {
+        let keys = m.static_account_keys();
+        m.instructions()
+            .iter()
+            .filter(|ix| keys[ix.program_id_index as usize] == solana_sdk::compute_budget::id())
+            .map(|ix| ix.data.clone())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    };
+    let want = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(345_000).data,
+        ComputeBudgetInstruction::set_compute_unit_price(20_000).data,
+    ];
+
+    // 已有 CU 上限和价格：原地改写，指令条数不变
+    let mut built = AtomicTxBuilder::new(user.pubkey()).build(&common::cycle(), &common::alts(), Hash::new_unique()).unwrap();
+    let n = built.message.instructions().len();
+    apply_plan(&plan, &mut built.message).unwrap();
+    assert_eq!(budget_ixs(&built.message), want);
+    assert_eq!(built.message.instructions().len(), n);
+
+    // 只有 CU 上限：补一条价格（顺序无关）
+    let mut legs = common::cycle();
+    for leg in &mut legs {
+        leg.compute_budget.retain(|ix| ix.data.first() == Some(&2));
+    }
+    let mut built = AtomicTxBuilder::new(user.pubkey()).build(&legs, &common::alts(), Hash::new_unique()).unwrap();
+    let n = built.message.instructions().len();
+    apply_plan(&plan, &mut built.message).unwrap();
+    assert_eq!(budget_ixs(&built.message), want);
+    assert_eq!(built.message.instructions().len(), n + 1);
+    assert!(built.sign(&[&user]).is_ok());
+
+    // 没有 ComputeBudget 程序
+    for leg in &mut legs {
+        leg.compute_budget.clear();
+    }
+    let mut built = AtomicTxBuilder::new(user.pubkey()).build(&legs, &common::alts(), Hash::new_unique()).unwrap();
+    assert!(matches!(apply_plan(&plan, &mut built.message), Err(ExecError::Compile(_))));
+}
+
+#[test]
+fn ledger_records_fee_paid() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fees.ndjson");
+    let ledger = FeeLedger::create(&path).unwrap();
+    let plan = FeePlan { unit_limit: 200_000, unit_price: 10_000, priority_fee: 2_000, base_fee: 5_000 };
+
+    let outcome = |slot: Option<u64>| SendOutcome {
+        signature: Signature::new_unique(),
+        slot,
+        failure: slot.is_none().then_some(SendFailure::Expired),
+        broadcasts: 1,
+        tip: 0,
+        elapsed: Duration::ZERO,
+    };
+    ledger.record(&outcome(Some(5)).fee_record(plan, 100_000)).unwrap();
+    // 未上链不扣费
+    ledger.record(&outcome(None).fee_record(plan, 100_000)).unwrap();
+    let cheap = FeePlan { unit_price: 0, priority_fee: 0, ..plan };
+    ledger.record(&outcome(Some(6)).fee_record(cheap, 100_000)).unwrap();
+
+    // 超时时还没确认：记为未定，不当作未上链
+    let seen = SigStatus { slot: 9, confirmation: Confirmation::Processed, err: None };
+    let pending = SendOutcome { failure: Some(SendFailure::Unconfirmed(seen)), ..outcome(None) };
+    ledger.record(&pending.fee_record(plan, 100_000)).unwrap();
+
+    let recs = read_fee_records(&path).unwrap();
+    assert_eq!(recs.len(), 4);
+    assert_eq!(recs.iter().map(|r| r.paid).collect::<Vec<_>>(), vec![7_000, 0, 5_000, 7_000]);
+    assert_eq!(recs.iter().map(|r| r.pending).collect::<Vec<_>>(), vec![false, false, false, true]);
+    assert_eq!(average_paid(&recs), Some(6_000));
+    assert_eq!(average_paid(&recs[1..2]), None);
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+use executor::rpc::HealthConfig;
+use executor::{Confirmation, ExecError, FakeRpc, Failover, Rpc, SigStatus};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+fn pair() -> (FakeRpc, FakeRpc) {
+    let (a, b) = (FakeRpc::new(), FakeRpc::new());
+    a.set_blockhash(Hash::new_from_array([1; 32]), 100);
+    b.set_blockhash(Hash::new_from_array([2; 32]), 200);
+    (a, b)
+}
+
+fn failover<'a>(a: &'a FakeRpc, b: &'a FakeRpc) -> Failover<&'a FakeRpc> {
+    Failover::new(vec![("a".into(), a), ("b".into(), b)])
+        .health_config(HealthConfig { max_consecutive_failures: 2, cooldown: Duration::from_secs(10) })
+}
+
+#[tokio::test]
+async fn fake_accounts_and_balances() {
+    let rpc = FakeRpc::new();
+    let (ata, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+    rpc.set_token_balance(ata, 42);
+    rpc.set_lamports(wallet, 7);
+
+    assert_eq!(rpc.token_balance(&ata).await.unwrap(), Some(42));
+    assert_eq!(rpc.account(&wallet).await.unwrap().unwrap().lamports, 7);
+    // 非 token 账户 / 不存在的账户
+    assert_eq!(rpc.token_balance(&wallet).await.unwrap(), None);
+    assert_eq!(rpc.account(&Pubkey::new_unique()).await.unwrap(), None);
+
+    let sig = Signature::new_unique();
+    assert_eq!(rpc.signature_status(&sig).await.unwrap(), None);
+    let st = SigStatus { slot: 9, confirmation: Confirmation::Finalized, err: None };
+    rpc.set_status(sig, st.clone());
+    assert_eq!(rpc.signature_status(&sig).await.unwrap(), Some(st));
+}
+
+#[tokio::test]
+async fn fails_over_to_next_endpoint() {
+    let (a, b) = pair();
+    let rpc = failover(&a, &b);
+
+    a.fail_next(1);
+    assert_eq!(rpc.latest_blockhash().await.unwrap().1, 200);
+    // a 只失败一次，下次仍优先用 a
+    assert_eq!(rpc.latest_blockhash().await.unwrap().1, 100);
+
+    let h = rpc.health();
+    assert_eq!((h[0].successes, h[0].failures, h[0].healthy), (1, 1, true));
+    assert_eq!(h[0].last_error.as_deref(), Some("injected failure"));
+    assert_eq!((h[1].successes, h[1].failures), (1, 0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn unhealthy_endpoint_is_skipped_until_cooldown() {
+    let (a, b) = pair();
+    let rpc = failover(&a, &b);
+
+    a.fail_next(2);
+    rpc.latest_blockhash().await.unwrap();
+    rpc.latest_blockhash().await.unwrap();
+    assert!(!rpc.health()[0].healthy);
+
+    // 摘除期间不再打到 a
+    let calls = a.calls();
+    for _ in 0..3 {
+        assert_eq!(rpc.latest_blockhash().await.unwrap().1, 200);
+    }
+    assert_eq!(a.calls(), calls);
+
+    tokio::time::advance(Duration::from_secs(11)).await;
+    assert_eq!(rpc.latest_blockhash().await.unwrap().1, 100);
+    assert!(rpc.health()[0].healthy);
+}
+
+#[tokio::test]
+async fn all_endpoints_down_reports_last_error() {
+    let (a, b) = pair();
+    let rpc = failover(&a, &b);
+    a.fail_next(1);
+    b.fail_next(1);
+
+    let err = rpc.latest_blockhash().await.unwrap_err();
+    assert!(matches!(&err, ExecError::Rpc(m) if m.starts_with("b:")), "{err}");
+    // 节点正常响应的“否定结果”不触发故障转移
+    assert_eq!(rpc.account(&Pubkey::new_unique()).await.unwrap(), None);
+    assert_eq!(b.calls(), 1);
+}
+
+#[tokio::test]
+async fn rejected_requests_do_not_fail_over() {
+    let (a, b) = pair();
+    let rpc = failover(&a, &b);
+    a.reject_next(3);
+
+    for _ in 0..3 {
+        assert!(matches!(rpc.latest_blockhash().await, Err(ExecError::Rejected(_))));
+    }
+    // a 仍然健康，b 从没被打到
+    assert!(rpc.health()[0].healthy);
+    assert_eq!(b.calls(), 0);
+}
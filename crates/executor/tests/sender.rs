@@ -0,0 +1,158 @@
+mod common;
+
+use std::time::Duration;
+use executor::sender::{classify, JUPITER_V6_PROGRAM_ID};
+use executor::simulate::TOKEN_PROGRAM_ID;
+use executor::{Confirmation, ExecError, FakeRpc, Failover, RpcSender, SendConfig, Rpc, SendFailure, SigStatus};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+fn cfg() -> SendConfig {
+    SendConfig {
+        poll_interval: Duration::from_millis(400),
+        rebroadcast_interval: Duration::from_secs(1),
+        commitment: Confirmation::Confirmed,
+        timeout: Duration::from_secs(60),
+    }
+}
+
+fn fake() -> FakeRpc {
+    let rpc = FakeRpc::new();
+    rpc.set_blockhash(Hash::new_unique(), 1_000);
+    rpc.set_block_height(900, 1);
+    rpc
+}
+
+#[tokio::test(start_paused = true)]
+async fn rebroadcasts_until_landed() {
+    let rpc = fake();
+    rpc.land_after_sends(3);
+    let tx = common::signed_cycle();
+
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 1_000).await;
+    assert!(out.landed(), "{:?}", out.failure);
+    assert_eq!(out.signature, tx.signatures[0]);
+    assert_eq!(out.broadcasts, 3);
+    assert_eq!(rpc.sent().len(), 3);
+    assert!(out.slot.is_some());
+    // 每 1s 重发一次：第 3 次在 ~2s 时发出
+    assert!(out.elapsed >= Duration::from_secs(2) && out.elapsed < Duration::from_secs(3), "{:?}", out.elapsed);
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_after_blockhash_expiry() {
+    let rpc = fake();
+    let tx = common::signed_cycle();
+
+    // 每次轮询前进 1 个块：第 11 次轮询时高度 910 > 909
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 909).await;
+    assert_eq!(out.failure, Some(SendFailure::Expired));
+    assert_eq!(out.slot, None);
+    assert!(out.broadcasts >= 4, "broadcasts={}", out.broadcasts);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unsent_when_every_broadcast_fails() {
+    let rpc = fake();
+    let tx = common::signed_cycle();
+    // 广播/状态查询全部失败，只剩区块高度可用
+    rpc.set_block_height(1_001, 0);
+    rpc.fail_next(2);
+
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 1_000).await;
+    assert_eq!(out.broadcasts, 0);
+    assert!(matches!(out.failure, Some(SendFailure::Unsent(ref e)) if e.contains("injected")), "{:?}", out.failure);
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_at_timeout_when_block_height_is_down() {
+    let rpc = fake();
+    rpc.fail_block_height(true);
+    let tx = common::signed_cycle();
+
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 1_000).await;
+    assert_eq!(out.failure, Some(SendFailure::Expired));
+    assert!(out.broadcasts > 1);
+    assert!(out.elapsed >= Duration::from_secs(60) && out.elapsed < Duration::from_secs(61), "{:?}", out.elapsed);
+}
+
+#[tokio::test(start_paused = true)]
+async fn seen_but_unconfirmed_at_timeout_is_pending() {
+    let rpc = fake();
+    rpc.fail_block_height(true);
+    let tx = common::signed_cycle();
+    let st = SigStatus { slot: 42, confirmation: Confirmation::Processed, err: None };
+    rpc.set_status(tx.signatures[0], st.clone());
+
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 1_000).await;
+    assert_eq!(out.failure, Some(SendFailure::Unconfirmed(st)));
+    assert!(out.pending() && !out.landed());
+    // 被看到后不再重发
+    assert_eq!(out.broadcasts, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn landed_with_error_is_classified() {
+    let rpc = fake();
+    let tx = common::signed_cycle();
+    rpc.set_status(
+        tx.signatures[0],
+        SigStatus {
+            slot: 77,
+            confirmation: Confirmation::Confirmed,
+            err: Some(TransactionError::InstructionError(7, InstructionError::Custom(6001))),
+        },
+    );
+
+    let out = RpcSender::new(&rpc).config(cfg()).send(&tx, 1_000).await;
+    assert_eq!(out.slot, Some(77));
+    assert_eq!(out.failure, Some(SendFailure::SlippageExceeded { program: JUPITER_V6_PROGRAM_ID, code: 6001 }));
+    assert_eq!(out.broadcasts, 1);
+}
+
+#[test]
+fn classifies_failures() {
+    let tx = common::signed_cycle();
+    let ix = |i, e| TransactionError::InstructionError(i, e);
+
+    assert_eq!(classify(&tx, &TransactionError::InsufficientFundsForFee), SendFailure::InsufficientFunds);
+    assert_eq!(classify(&tx, &TransactionError::BlockhashNotFound), SendFailure::Expired);
+    // 4 = Token syncNative：自定义错误 1 即 InsufficientFunds
+    assert_eq!(classify(&tx, &ix(4, InstructionError::Custom(1))), SendFailure::InsufficientFunds);
+    assert_eq!(
+        classify(&tx, &ix(6, InstructionError::Custom(6024))),
+        SendFailure::ProgramError { instruction: 6, program: Some(JUPITER_V6_PROGRAM_ID), code: 6024 }
+    );
+    assert_eq!(
+        classify(&tx, &ix(4, InstructionError::Custom(17))),
+        SendFailure::ProgramError { instruction: 4, program: Some(TOKEN_PROGRAM_ID), code: 17 }
+    );
+    assert_eq!(
+        classify(&tx, &ix(3, InstructionError::InvalidAccountData)),
+        SendFailure::Other(ix(3, InstructionError::InvalidAccountData))
+    );
+
+    let f = SendFailure::ProgramError { instruction: 6, program: Some(JUPITER_V6_PROGRAM_ID), code: 6024 };
+    assert!(f.to_string().contains("0x1788"));
+}
+
+#[tokio::test]
+async fn broadcast_reaches_every_endpoint() {
+    let (a, b) = (fake(), fake());
+    let rpc = Failover::new(vec![("a".into(), &a), ("b".into(), &b)]);
+    let tx = common::signed_cycle();
+
+    assert_eq!(rpc.broadcast(&tx).await.unwrap(), tx.signatures[0]);
+    assert_eq!((a.sent().len(), b.sent().len()), (1, 1));
+
+    // 部分端点失败不影响结果，只记入健康状况
+    a.fail_next(1);
+    assert!(rpc.broadcast(&tx).await.is_ok());
+    assert_eq!((a.sent().len(), b.sent().len()), (1, 2));
+    assert_eq!(rpc.health()[0].failures, 1);
+
+    a.fail_next(1);
+    b.fail_next(1);
+    assert!(matches!(rpc.broadcast(&tx).await, Err(ExecError::Rpc(_))));
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use executor::{ExecError, FakeRpc, ProfitAccount, SimulateResult, SimulationGate};
+use executor::simulate::{program_units, token_amount};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+
+/* SPL Token 账户数据：只填 amount */
+fn token_data(amount: u64) -> Vec<u8> {
+    let mut d = vec![0u8; 165];
+    d[64..72].copy_from_slice(&amount.to_le_bytes());
+    d
+}
+
+fn logs() -> Vec<String> {
+    [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+        "Program ComputeBudget111111111111111111111111111111 success",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 182345 of 499700 compute units",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 150000 of 317355 compute units",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn tx() -> VersionedTransaction {
+    common::signed_cycle()
+}
+
+/* 模拟成功、CU 与日志固定 */
+fn fake() -> FakeRpc {
+    let rpc = FakeRpc::new();
+    rpc.set_simulation(SimulateResult { logs: logs(), units_consumed: Some(332_645), ..Default::default() });
+    rpc
+}
+
+#[test]
+fn parses_token_amount_and_program_units() {
+    assert_eq!(token_amount(&token_data(42)), Some(42));
+    assert_eq!(token_amount(&[0; 10]), None);
+
+    let units = program_units(&logs());
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0].consumed, 182_345);
+    assert_eq!(units.iter().map(|u| u.consumed).sum::<u64>(), 332_345);
+}
+
+#[tokio::test]
+async fn passes_when_simulated_profit_meets_minimum() {
+    let ata = Pubkey::new_unique();
+    let rpc = fake();
+    rpc.set_token_balance(ata, 1_000_000);
+    rpc.set_post_token_balance(ata, 1_000_150);
+
+    let account = ProfitAccount::Token(ata);
+    let pre = account.balance_on(&rpc).await.unwrap();
+    assert_eq!(pre, 1_000_000);
+
+    let report = SimulationGate { min_profit: 100 }.check(&rpc, &tx(), account, pre).await.unwrap();
+    assert_eq!(report.profit, 150);
+    assert_eq!(report.post_balance, 1_000_150);
+    assert_eq!(report.units_consumed, Some(332_645));
+    assert_eq!(report.programs.len(), 2);
+    assert!(rpc.sent().is_empty());
+}
+
+#[tokio::test]
+async fn refuses_when_profit_below_minimum() {
+    let ata = Pubkey::new_unique();
+    let rpc = fake();
+    rpc.set_post_token_balance(ata, 999_990);
+
+    let err = SimulationGate { min_profit: 1 }
+        .check(&rpc, &tx(), ProfitAccount::Token(ata), 1_000_000)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ExecError::InsufficientProfit { min: 1, simulated: -10 }), "{err:?}");
+}
+
+#[tokio::test]
+async fn refuses_when_simulation_errors() {
+    let mut logs = logs();
+    logs.push("Program log: Error: Slippage tolerance exceeded".into());
+    logs.push("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771".into());
+    let rpc = FakeRpc::new();
+    rpc.set_simulation(SimulateResult {
+        err: Some(TransactionError::InstructionError(3, InstructionError::Custom(6001))),
+        logs,
+        ..Default::default()
+    });
+
+    let err = SimulationGate { min_profit: i128::MIN }
+        .check(&rpc, &tx(), ProfitAccount::Token(Pubkey::new_unique()), 0)
+        .await
+        .unwrap_err();
+    let ExecError::SimulationFailed { err, logs } = err else { panic!("{err:?}") };
+    assert_eq!(err, TransactionError::InstructionError(3, InstructionError::Custom(6001)));
+    assert_eq!(logs.len(), 2);
+}
+
+#[tokio::test]
+async fn native_profit_uses_lamports() {
+    let wallet = Pubkey::new_unique();
+    let rpc = fake();
+    rpc.set_lamports(wallet, 4_990_000);
+    rpc.set_post_lamports(wallet, 5_000_000);
+
+    let account = ProfitAccount::Native(wallet);
+    let pre = account.balance_on(&rpc).await.unwrap();
+    let report = SimulationGate { min_profit: 0 }.check(&rpc, &tx(), account, pre).await.unwrap();
+    assert_eq!(report.profit, 10_000);
+
+    // 账户不存在（模拟中被关闭）→ 余额按 0 算
+    let gone = Pubkey::new_unique();
+    let err = SimulationGate { min_profit: 0 }
+        .check(&rpc, &tx(), ProfitAccount::Native(gone), 1)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ExecError::InsufficientProfit { .. }));
+}
+
+#[test]
+fn profit_account_for_mint() {
+    use executor::simulate::WSOL_MINT;
+    let user = common::user().pubkey();
+    assert_eq!(ProfitAccount::for_mint(&user, &WSOL_MINT), ProfitAccount::Native(user));
+
+    // USDC ATA 与 Jupiter fixture 中的目标账户一致
+    let usdc: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
+    let ProfitAccount::Token(ata) = ProfitAccount::for_mint(&user, &usdc) else { panic!() };
+    assert!(common::cycle()[0].swap.accounts.iter().any(|a| a.pubkey == ata));
+}
@@ -44,5 +44,7 @@ impl MintResolver for LocalResolver {
     fn get_decimals(&self, symbol: &str) -> Option<u8> {
         self.decimals.get(&symbol.to_ascii_uppercase()).copied()
     }
-    fn is_tradable(&self, _mint: &str) -> Option<bool> { Some(true) }
+    fn is_tradable(&self, mint: &str) -> Option<bool> {
+        Some(self.tradable.get(mint).copied().unwrap_or(true))
+    }
 }
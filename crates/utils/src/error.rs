@@ -15,6 +15,9 @@ pub enum AppError {
     #[error("未找到报价路径")]
     NoQuoteFound,
 
+    #[error("报价请求被拒绝: {0}")]
+    QuoteRejected(String),
+
     #[error("未知错误: {0}")]
     Other(String),
 
@@ -23,6 +26,9 @@ pub enum AppError {
 
     #[error("外部服务错误: {0}")]
     External(String),
+
+    #[error("请求被限速（Retry-After: {retry_after:?}）")]
+    RateLimited { retry_after: Option<std::time::Duration> },
 }
 
 /// 项目统一返回类型
@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::{AppError, AppResult};
+
+/// 一笔交易的费用安排（执行层据此设置 ComputeBudget）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeePlan {
+    pub unit_limit: u32,
+    pub unit_price: u64,
+    /// unit_limit × unit_price（向上取整到 lamport）
+    pub priority_fee: u64,
+    pub base_fee: u64,
+}
+
+impl FeePlan {
+    pub fn total(&self) -> u64 {
+        self.base_fee + self.priority_fee
+    }
+}
+
+/// 一笔交易实际花掉的费用（NDJSON 一行，执行层写、扫描/回测读）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeRecord {
+    pub ts_ms: u64,
+    pub signature: String,
+    pub plan: FeePlan,
+    /// 上链即扣费（执行失败也一样）
+    pub landed: bool,
+    /// 超时时已被节点看到、结果未定：paid 按上链计，但不算进平均费用
+    #[serde(default)]
+    pub pending: bool,
+    /// Jito 小费（bundle 上链才付）
+    #[serde(default)]
+    pub tip: u64,
+    /// 基础费 + 优先费 + 小费
+    pub paid: u64,
+    pub expected_profit: i128,
+}
+
+/// 读取费用记录（跳过无法解析的行）
+pub fn read_fee_records(path: impl AsRef<Path>) -> AppResult<Vec<FeeRecord>> {
+    let path = path.as_ref();
+    let f = File::open(path).map_err(|e| AppError::Custom(format!("{}: {}", path.display(), e)))?;
+    Ok(BufReader::new(f)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|l| serde_json::from_str(&l).ok())
+        .collect())
+}
+
+/// 已上链交易的平均费用（lamports）；没有记录时返回 None
+pub fn average_paid(records: &[FeeRecord]) -> Option<u64> {
+    let landed: Vec<u64> = records.iter().filter(|r| r.landed).map(|r| r.paid).collect();
+    if landed.is_empty() {
+        return None;
+    }
+    Some(landed.iter().sum::<u64>() / landed.len() as u64)
+}
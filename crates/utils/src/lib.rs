@@ -2,6 +2,7 @@ pub mod error;
 pub mod wallet;
 pub mod token;
 pub mod printer;
+pub mod fees;
 
 
 pub use error::{AppError, AppResult};
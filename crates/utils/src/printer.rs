@@ -6,14 +6,20 @@ pub struct MatrixRow {
     pub start: f64,
     pub end: f64,
     pub delta_bps: f64, // 原始 bps（基点）
+    pub legs: Vec<String>, // 逐跳明细（每跳一行，空则不显示 Legs 列）
 }
 
 pub fn print_matrix_table(rows: Vec<MatrixRow>) {
+    let show_legs = rows.iter().any(|r| !r.legs.is_empty());
+
+    let mut header = vec!["Arbitrage", "Path", "Start", "End", "Change (%)"]; // 改成人话百分比
+    if show_legs { header.push("Legs"); }
+
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["Arbitrage", "Path", "Start", "End", "Change (%)"]); // 改成人话百分比
+        .set_header(header);
 
     for row in rows {
         // bps -> 百分比（%）
@@ -27,13 +33,16 @@ pub fn print_matrix_table(rows: Vec<MatrixRow>) {
             Cell::new(pct_str).fg(Color::Red)
         };
 
-        table.add_row(Row::from(vec![
+        let mut cells = vec![
             Cell::new(if row.profitable { "✅ Profitable" } else { "🧊 No Profit" }),
             Cell::new(row.path),
             Cell::new(format!("{:.6}", row.start)),
             Cell::new(format!("{:.6}", row.end)),
             pct_cell,
-        ]));
+        ];
+        if show_legs { cells.push(Cell::new(row.legs.join("\n"))); }
+
+        table.add_row(Row::from(cells));
     }
 
     println!("{table}");